@@ -0,0 +1,54 @@
+//! Time to render many pages, with many templates
+//!
+//! Run with `cargo bench`
+
+use serde_json::json;
+use std::time::Instant;
+use unreact::prelude::*;
+
+/// Create app with `count` templates, where each page uses a few of them as partials
+fn create_app(count: usize) -> Unreact {
+  let mut vfs = Vfs::new();
+  vfs.templates.insert(
+    "page".to_string(),
+    "<h1>{{title}}</h1>{{>part_0}}{{>part_1}}{{#each items}}{{>part_2}}{{/each}}".to_string(),
+  );
+  for i in 0..count {
+    vfs.templates.insert(
+      format!("part_{i}"),
+      format!("<p class=\"part-{i}\">{{{{title}}}} {{{{this}}}}</p>"),
+    );
+  }
+
+  let url = SiteUrl::parse("https://mysite.com").expect("Invalid url");
+  Unreact::from_vfs(Config::default(), false, url, vfs)
+}
+
+/// Render `pages` pages, with `templates` templates, and print time taken
+fn bench(templates: usize, pages: usize) {
+  let mut app = create_app(templates);
+
+  let start = Instant::now();
+  for i in 0..pages {
+    app
+      .page(
+        &format!("page_{i}"),
+        "page",
+        &json!({"title": format!("Page {i}"), "items": [1, 2, 3]}),
+      )
+      .expect("Failed to render page");
+  }
+  let time = start.elapsed();
+
+  println!(
+    "{templates:>5} templates, {pages:>5} pages: {:>10.2?} ({:.2?} per page)",
+    time,
+    time / pages as u32
+  );
+}
+
+fn main() {
+  for (templates, pages) in [(10, 100), (100, 100), (100, 1000), (500, 1000)] {
+    bench(templates, pages);
+  }
+}
@@ -0,0 +1,41 @@
+use serde_json::json;
+use unreact::prelude::*;
+
+/// Posts of blog, as `(slug, title, date, markdown)`
+const POSTS: &[(&str, &str, &str, &str)] = &[
+  (
+    "hello-world",
+    "Hello world",
+    "2024-01-01",
+    "This is the first post.\n\nEdit `src/main.rs` to add more!",
+  ),
+  (
+    "second-post",
+    "Second post",
+    "2024-01-02",
+    "Posts are written in **Markdown**, and rendered with `templates/post.hbs`.",
+  ),
+];
+
+fn main() -> UnreactResult<()> {
+  let mut app = Unreact::new(Config::default(), is_dev(), "https://example.com")?;
+
+  // Create pages for posts
+  let mut posts = Vec::new();
+  for (slug, title, date, content) in POSTS {
+    let content = unreact::markdown_to_html(content, &MarkdownOptions::default());
+    app.page(
+      &format!("posts/{slug}"),
+      "post",
+      &json!({ "title": title, "date": date, "content": content }),
+    )?;
+    posts.push(json!({ "path": format!("posts/{slug}"), "title": title, "date": date }));
+  }
+
+  // List of posts
+  app.index("index", &json!({ "title": "Posts", "posts": posts }))?;
+  app.not_found("404", &json!({ "title": "Not found" }))?;
+
+  app.finish_watch()?;
+  Ok(())
+}
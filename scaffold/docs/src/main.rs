@@ -0,0 +1,39 @@
+use serde_json::json;
+use unreact::prelude::*;
+
+/// Pages of documentation, in order of sidebar, as `(path, title, markdown)`
+const PAGES: &[(&str, &str, &str)] = &[
+  (
+    "getting-started",
+    "Getting started",
+    "## Install\n\nRun `cargo run -- --dev`, and open <http://127.0.0.1:8080>.",
+  ),
+  (
+    "configuration",
+    "Configuration",
+    "## Config\n\nChange the `Config` in `src/main.rs`.",
+  ),
+];
+
+fn main() -> UnreactResult<()> {
+  let mut app = Unreact::new(Config::default(), is_dev(), "https://example.com")?;
+
+  // Sidebar links, shared by every page
+  let nav = PAGES
+    .iter()
+    .map(|(path, title, _)| json!({ "path": path, "title": title }))
+    .collect::<Vec<_>>();
+  app.set_globals(json!({ "nav": nav }));
+
+  // Create pages
+  for (path, title, content) in PAGES {
+    let content = unreact::markdown_to_html(content, &MarkdownOptions::default());
+    app.page(path, "page", &json!({ "title": title, "content": content }))?;
+  }
+
+  app.index("index", &json!({ "title": "Documentation" }))?;
+  app.not_found("404", &json!({ "title": "Not found" }))?;
+
+  app.finish_watch()?;
+  Ok(())
+}
@@ -1,23 +1,141 @@
-use handlebars::Handlebars;
-use serde_json::Value;
-use std::{fs, path::Path};
+use handlebars::{Handlebars, HelperDef, Template};
+use serde_json::{json, Map, Value};
+use std::{
+  borrow::Cow,
+  collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+  fs,
+  ops::ControlFlow,
+  path::Path,
+  sync::{mpsc::Receiver, Arc, Mutex, PoisonError, RwLock},
+  time::{Instant, SystemTime},
+};
+
+use crate::{
+  assets::{Assets, ASSETS_FILE},
+  audit::audit,
+  cache::{BuildCache, CacheKind, TemplateCache},
+  collection::{feed_items, COLLECTION_FEED_FILE, COLLECTION_SITEMAP_FILE, PAGES_SITEMAP_FILE},
+  context_schema::merge_schema,
+  create_build_dir, create_dir_all_safe,
+  date::{unix_seconds, DateTime},
+  dev_build::{claim_dev_build, DevBuildLock},
+  engine::{Inbuilt, TemplateEngine},
+  error_page::{error_page, error_style},
+  events::Subscribers,
+  feed::create_feed,
+  format_html,
+  fragments::{with_fragments, CacheHelper, Fragments},
+  front_matter::parse_front_matter,
+  hash_content,
+  hooks::Hooks,
+  host::{local_repository, CNAME_FILE, NOJEKYLL_FILE},
+  i18n::{register_translate_helper, Locales},
+  images::{image_size, process_image, srcset, unsupported_warning},
+  jsonld::{jsonld, schema_from_args},
+  lazy::LazyTemplates,
+  limits::{
+    end_partial, guarded, start_partial, wrap_guard, LimitedOutput, GUARD_END, GUARD_START,
+  },
+  links::{broken_links, links_to, redirect_page, relative_links, rename_links},
+  list_files,
+  lockfile::hash_dir,
+  manifest::{BuildManifest, BuildManifestEntry, BUILD_MANIFEST_FILE, MANIFEST_FILE},
+  markdown::{extract_shortcodes, insert_shortcodes},
+  markdown_to_html, merge_json, normalize_path,
+  package::package_dir,
+  parallel::{for_each_parallel, thread_count},
+  partial_docs::partial_docs_json,
+  path_check::check_path,
+  plugin::load_plugin,
+  profile::{wrap_partial, PROFILE_END, PROFILE_START},
+  progress::ProgressTracker,
+  public::{copy_file, list_public},
+  replace_dir,
+  schedule::publish_schedule,
+  scss::{compile_scss, StyleFiles},
+  server,
+  sink::BuildSink,
+  sitemap::{robots, sitemap, sitemap_index, url_path, ROBOTS_FILE, SITEMAP_FILE},
+  staging_dir,
+  verify::verify_file,
+  watch::{styles_declared, template_dependencies},
+  workspace::{dev_address, use_manifest_dir},
+  AssetWarning, AuditOptions, BuildEvent, BuildProgress, BuildReport, BuildStage, CacheStats,
+  CollectionOptions, CopyOptions, DirectoryMode, FeedItem, FeedOptions, FileMap, FormatOptions,
+  HeadOptions, Host, ImageFormat, ImageOptions, ImageProcessor, LinkCheck, Lockfile, Manifest,
+  ManifestDiff, MarkdownOptions, Page, PageSource, PartialDoc, PathNormalization, Plugin,
+  PngProcessor, Profile, RenderLimits, SiteUrl, SitemapOptions, UnreactError, UnreactResult, Vfs,
+  ADDRESS, DEFAULT_PACKAGE_LEVEL, DEV_BUILD_DIR,
+};
 
+#[cfg(feature = "indexnow")]
 use crate::{
-  create_dir_all_safe, load_filemap, merge_json, server, File, FileMap, UnreactError,
-  UnreactResult, DEV_BUILD_DIR,
+  indexnow::{create_request, key_file},
+  IndexNow, IndexNowRequest,
+};
+
+#[cfg(feature = "dev-server")]
+use crate::{
+  partial_docs::partial_docs_page,
+  server::{DevEvent, DevServer, ReloadSignal, ServerHandle},
+  stats_page::{stats_page, STATS_DIR},
+  watch::watch_dirs,
 };
 
 /// Config for directories and options
 ///
 /// Use `Config::default()` for default config
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Config {
+  /// Url of site, used instead of `url` given to `Unreact::new`
+  ///
+  /// For setting url in a config file, see `Config::from_file`
+  ///
+  /// Default: `None`
+  pub url: Option<String>,
   /// Directory of output files - build directory
   ///
-  /// For production. Temporary folder `./.devbuild` is used in development
+  /// For production. A temporary folder in `dev_build` is used in development
   ///
   /// Default: `"build"`
   pub build: String,
+  /// If repository name is added to url as base path when building in GitHub Actions, for a GitHub Pages project site
+  ///
+  /// For example, `https://user.github.io` is `https://user.github.io/repo` when `GITHUB_REPOSITORY` is `user/repo`, so every `URL`, `LINK`, `STYLE`, and sitemap url works.
+  /// Ignored for other domains, urls which already have a path, and if `base_path` is set, see `SiteUrl::with_repository`
+  ///
+  /// In dev mode outside of GitHub Actions, repository is read from the `origin` remote of the local git repository, so the dev server serves the site under `/repo/` and `URL` includes it, like the deployed site
+  ///
+  /// Default: `false`, or `true` for `Config::github_pages()`
+  pub github_base_path: bool,
+  /// Path which site is served under, such as `"/repo"`, instead of path of url
+  ///
+  /// Every `URL`, `LINK`, `STYLE`, feed, and sitemap url includes the path, and the dev server serves the site under the same path, so links work the same in development and production.
+  /// `Some("")` serves site at root of domain, even if url has a path
+  ///
+  /// Default: `None`, which uses path of url
+  pub base_path: Option<String>,
+  /// If links to site in pages are relative to the page (such as `../about` in `blog/post.html`), instead of absolute urls
+  ///
+  /// So build works under any path, or opened as files. Changes `href`, `src`, and `srcset` attributes, except of canonical links.
+  /// Feeds and sitemap keep absolute urls
+  ///
+  /// Default: `false`
+  pub relative_links: bool,
+  /// If build directory is removed by `Unreact::new`, and files are written directly to it
+  ///
+  /// Otherwise, files are written to a staging directory (such as `./build.staging`), which replaces the build directory only if the build succeeds.
+  /// A failed build keeps the previous build
+  ///
+  /// Default: `false`
+  pub clean: bool,
+  /// Other urls to build the same site for, each with its own build directory, such as `("https://mirror.mysite.com", "build-mirror")`
+  ///
+  /// Pages are rendered once. Every occurence of the site url in output files (such as from the `URL` partial, feeds, and sitemap) is replaced with the url of the target.
+  /// Each build directory is replaced separately, see `clean`. Ignored in dev mode
+  ///
+  /// Default: Empty
+  pub targets: Vec<(String, String)>,
   /// Directory of templates and partials (`.hbs`)
   ///
   /// Can contain nested files
@@ -30,35 +148,504 @@ pub struct Config {
   ///
   /// Default: `"public"`
   pub public: String,
-  /// Directory of styles (`.scss`)
+  /// Directory of styles (`.scss` and `.css`)
   ///
-  /// Can contain nested files
+  /// Can contain nested files. Styles starting with `_` (such as `_mixins.scss`) are partials, which are only used by `@use` and `@import` in other styles.
+  /// Imports are found relative to the style, then relative to this directory
+  ///
+  /// Plain css files are minified, if enabled, but not compiled
   ///
   /// Default: `"styles"`
   pub styles: String,
+  /// Directory of Markdown content (`.md`), with front matter, see `Unreact::pages_from_content`
+  ///
+  /// Optional, does not need to exist unless used
+  ///
+  /// Default: `"content"`
+  pub content: String,
+  /// Url of repository of site, such as `"https://github.com/user/repo"`, for `edit_url` of content pages, see `Config::edit_url`
+  ///
+  /// Default: `None`
+  pub repository: Option<String>,
+  /// Branch of repository to edit files on
+  ///
+  /// Default: `"main"`
+  pub repository_branch: String,
+  /// Directory of site in repository, if site is not at root of repository
+  ///
+  /// Default: `""`
+  pub repository_dir: String,
+  /// Directory of data files (`.json`, `.yaml`, `.yml`, or `.toml`), available to every template as `data`
+  ///
+  /// `./data/nav.json` is `data.nav`, and `./data/authors/main.json` is `data.authors.main`.
+  /// YAML and TOML files support nested keys and tables, but not inline tables (`{ a = 1 }`) or multi-line strings
+  ///
+  /// Optional, does not need to exist unless used
+  ///
+  /// Default: `"data"`
+  pub data: String,
+  /// Directory of translation files (`.json`), such as `./locales/de.json`, see `Unreact::set_locales`
+  ///
+  /// Optional, does not need to exist unless used
+  ///
+  /// Default: `"locales"`
+  pub locales: String,
+  /// Directory of compiled styles (`.css`) in build directory
+  ///
+  /// Use `""` to write styles to root of build directory
+  ///
+  /// Default: `"styles"`
+  pub styles_output: String,
+  /// If nested styles are written directly to `styles_output`, without their subdirectories
+  ///
+  /// For example, `./styles/pages/about.scss` is written to `./build/styles/about.css`
+  ///
+  /// Default: `false`
+  pub flatten_styles: bool,
+  /// Custom output paths of specific styles, relative to build directory, **without** `.css` extension
+  ///
+  /// Overrides `styles_output` and `flatten_styles`, to keep existing urls when migrating a site
+  ///
+  /// For example, `("main", "css/site")` writes `./styles/main.scss` to `./build/css/site.css`
+  ///
+  /// Default: Empty
+  pub style_paths: HashMap<String, String>,
+  /// If `STYLES` partial links only styles which the template of the page uses, instead of every style
+  ///
+  /// A template uses a style with the same name as itself or any partial it uses (`./styles/blog/post.scss` for `./templates/blog/post.hbs`),
+  /// and styles declared in a comment, such as `{{!-- styles: code, tables --}}`.
+  /// Styles which no template uses (such as `main`) are linked on every page
+  ///
+  /// Default: `false`
+  pub page_styles: bool,
+  /// Styles (by name, such as `"critical"`) and public files (by path, such as `"js/boot.js"`) which are only embedded in pages,
+  /// with `inline_style` and `inline_script` helpers, and not written to build directory
+  ///
+  /// Default: Empty
+  pub inline_only: Vec<String>,
+  /// If templates and public files matching patterns of `.gitignore` and `.unreactignore` files are left out,
+  /// such as `node_modules/` or editor backups (`*~`) inside the templates or public directory
+  ///
+  /// Ignore files are read from the workspace, and from the directory and its subdirectories. Ignore files themselves are also left out
+  ///
+  /// Default: `false`
+  pub ignore_files: bool,
   /// If warning is sent in dev mode
   ///
   /// Default: `true`
   pub dev_warning: bool,
+  /// If templates are read from disk when first rendered in dev mode, instead of all in `Unreact::new`
+  ///
+  /// Starts the dev server sooner for sites with many templates. A template and the partials it uses are read when a page is rendered with it, and read again when they change.
+  /// Pages are rendered on one thread, as templates are read during render. Ignored in production, and for apps created with `Unreact::from_vfs`
+  ///
+  /// Default: `false`
+  pub lazy_templates: bool,
+  /// Directory of temporary dev builds, relative to workspace
+  ///
+  /// Each app in dev mode builds in its own subdirectory, such as `target/unreact-dev/blog-1234-0` (with name of crate, if run with cargo), so projects and tests running at the same time do not replace each other's files.
+  /// Subdirectories are locked while their process runs, and dev builds of processes which have ended (including crashes) are removed when the next app in dev mode is created
+  ///
+  /// Default: `"target/unreact-dev"`
+  pub dev_build: String,
+  /// Port of dev server. If it is in use, such as by dev server of another site in a cargo workspace, the next free port is used
+  ///
+  /// Use `0` for any free port. Port is chosen when app is created, so the `URL` partial has the same port as the server
+  ///
+  /// Default: `8080`
+  pub dev_port: u16,
+  /// If directories are relative to directory of crate manifest (`CARGO_MANIFEST_DIR`, set by `cargo run`), when directory of templates is not in current directory
+  ///
+  /// Lets each site crate of a cargo workspace be run from root of workspace, such as with `cargo run -p blog`.
+  /// Directories and files of config (such as `templates`, `build`, `cache`, and `lockfile`) are joined to the relative path of the manifest directory in `Unreact::new`.
+  /// Current directory of process is not changed, so other relative paths (such as paths given to `Unreact::package`) are not affected.
+  /// `dev_build` is also not changed, so dev builds are in `target` of workspace
+  ///
+  /// Config files of `Config::from_file` are always read from directory of manifest if not in current directory
+  ///
+  /// Default: `true`
+  pub manifest_dir: bool,
+  /// How dev server responds to paths of directories, such as redirecting `/blog` to `/blog/`, see `DirectoryMode`
+  ///
+  /// Default: `DirectoryMode::Redirect`, like GitHub Pages
+  pub dev_directories: DirectoryMode,
+  /// If dev server prints a line for every request, with method, path, status, and time
+  ///
+  /// Default: `true`
+  pub dev_log: bool,
   /// If `html` and `css` files are minified in build
   ///
   /// Default: `true`
   pub minify: bool,
+  /// Formatting of `html` files, if `minify` is `false`, see `FormatOptions`
+  ///
+  /// Default: No formatting
+  pub format: FormatOptions,
+  /// Production host of site, see `Host`
+  ///
+  /// Default: `Host::Generic`
+  pub host: Host,
+  /// Tags in the inbuilt `HTML_HEAD` partial, see `HeadOptions`
+  ///
+  /// Default: `HeadOptions::default()`
+  pub head: HeadOptions,
+  /// Extensions enabled for Markdown content, see `MarkdownOptions`
+  ///
+  /// Default: GitHub-flavored Markdown (all extensions enabled)
+  pub markdown: MarkdownOptions,
+  /// If `manifest.json` is written to build directory, listing every output file with size and hash
+  ///
+  /// See `Manifest`
+  ///
+  /// Default: `false`
+  pub manifest: bool,
+  /// If `build-manifest.json` is written to build directory, listing every output file with size, hash, and the template and source file which produced it
+  ///
+  /// Useful to find which template produced a page, see `BuildManifest`
+  ///
+  /// Default: `false`
+  pub build_manifest: bool,
+  /// Custom domain of site, such as `www.mysite.com`, written to a `CNAME` file in build directory
+  ///
+  /// Needed by GitHub Pages when deploying from a branch, such as `gh-pages`. A `.nojekyll` file is also written with `Host::GitHubPages`,
+  /// so files starting with `_` are served
+  ///
+  /// Default: `None`
+  pub cname: Option<String>,
+  /// Amount of threads to render, minify, and write pages, and compile styles with
+  ///
+  /// Use `0` for the amount of available CPUs. Ignored when `profile` is enabled
+  ///
+  /// Pages registered one at a time (such as with `Unreact::page`) are rendered together when building.
+  /// `Unreact::pages_from_iter`, `Unreact::pages_from_content`, and `Unreact::reload` render all their pages in parallel when called
+  ///
+  /// Default: `1`
+  pub threads: usize,
+  /// Permissions and modified times of files copied from `public` directory, see `CopyOptions`
+  ///
+  /// Use `CopyOptions::reproducible()` for the same build directory on every machine
+  ///
+  /// Default: Keep permissions, use time of copy as modified time
+  pub public_copy: CopyOptions,
+  /// If `sitemap.xml` of every page, and a `robots.txt` linking to it, are written to build directory
+  ///
+  /// The 404 page is not included. Set priority and change frequency of pages with `Unreact::set_sitemap`.
+  /// Collections can have their own sitemap, listed in a sitemap index, see `CollectionOptions::sitemap`
+  ///
+  /// Default: `false`
+  pub sitemap: bool,
+  /// Date which site is built as of, instead of current time, to see the site as it was (or will be) on that date
+  ///
+  /// Content with a `date` in front matter after this date is not built, see `Unreact::pages_from_content`.
+  /// Also used by `Unreact::is_future` and `Unreact::build_date`
+  ///
+  /// Set in config file as `YYYY-MM-DD`, RFC 3339 date and time, or seconds since Unix epoch
+  ///
+  /// Default: `None` (current time)
+  pub build_date: Option<SystemTime>,
+  /// Path of file to write, relative to workspace, with paths and dates of content left out of a production build until its date,
+  /// so CI can rebuild when content is published, see `Config::build_date`
+  ///
+  /// Only written if any content is scheduled, and removed otherwise. Not in build directory, so scheduled pages are not public
+  ///
+  /// Default: `Some("publish-schedule.json")`
+  pub publish_schedule: Option<String>,
+  /// Path of file to write after every build, relative to workspace, such as `partials.json`, with documentation of each template and partial, see `PartialDoc`
+  ///
+  /// Only templates and partials which start with a comment are included. In dev mode, the same reference is also at `/__unreact/partials/`
+  ///
+  /// Default: `None`
+  pub partial_docs: Option<String>,
+  /// Path of lockfile to write after every production build, relative to workspace, such as `unreact.lock`, with hashes of templates, styles, data, content, and public files, see `Lockfile`
+  ///
+  /// Commit the lockfile, so a later build can be checked to use identical inputs, with `locked` or `Unreact::verify_lockfile`
+  ///
+  /// Default: `None`
+  pub lockfile: Option<String>,
+  /// If production build fails with `UnreactError::LockfileMismatch` when inputs are different to `lockfile`, instead of writing it, like `cargo build --locked`
+  ///
+  /// Default: `false`
+  pub locked: bool,
+  /// If compiled styles and public files have a hash of their content in their file name, such as `styles/main.0123456789.css`
+  ///
+  /// Lets the host cache assets forever, as changed files have a new url. `STYLE` and `ASSET` partials link to the fingerprinted path, and `assets.json` in build directory maps original paths to fingerprinted paths
+  ///
+  /// Public files without extension, or starting with `.` or `_` (such as `_headers`), keep their name. Ignored in dev mode
+  ///
+  /// Default: `false`
+  pub fingerprint_assets: bool,
+  /// Unicode normalization of page paths, so paths are the same on every OS, see `PathNormalization`
+  ///
+  /// Applies to paths given to every method which registers or finds a page, and paths of Markdown content files
+  ///
+  /// Default: `PathNormalization::Nfc`
+  pub normalize_paths: PathNormalization,
+  /// If output paths of pages, styles, and public files are lowercase, for case-sensitive hosts (such as GitHub Pages), where `/About` and `/about` are different pages
+  ///
+  /// Paths of `LINK`, `STYLE`, `ASSET`, and `IMAGE` partials are lowercase too, so links written with any casing find the page or file.
+  /// Returns `UnreactError::DuplicateOutput` if two pages, styles, or public files differ only by casing
+  ///
+  /// Default: `false`
+  pub lowercase_paths: bool,
+  /// If output paths are checked when pages and feeds are registered, so the build can be written and checked out on every OS
+  ///
+  /// Rejects reserved Windows names (such as `aux` or `con`), characters not allowed on Windows (such as `:` or `?`), and paths longer than 200 characters
+  ///
+  /// Pages registered with `Unreact::page_plain` are checked when built
+  ///
+  /// Default: `true`
+  pub check_paths: bool,
+  /// If internal links (`href` and `src` attributes) of pages are checked when built, see `LinkCheck`
+  ///
+  /// A link is broken if no output file has its path, as a file, a page without `.html`, or a directory with `index.html`.
+  /// Root-relative links (such as `/about`) must include base path of url. External links are not checked
+  ///
+  /// Default: `LinkCheck::Off`
+  pub check_links: LinkCheck,
+  /// If build continues when a file cannot be written (such as on a read-only mount, or a file locked by another program)
+  ///
+  /// Every failed file is returned in `UnreactError::WriteFail` at the end of the build, instead of stopping at the first `UnreactError::IoError`
+  ///
+  /// Default: `false`
+  pub skip_write_errors: bool,
+  /// If every written file is read again after build, to check that it is identical to output, and that HTML, CSS, JSON, and XML files can still be parsed
+  ///
+  /// Guards against disk errors and minifier bugs, before the build is deployed. Returns `UnreactError::VerifyFail` for the first broken file, and the build directory is not replaced, unless `clean` is set.
+  /// Ignored with a sink, see `Unreact::set_sink`
+  ///
+  /// Default: `false`
+  pub verify_output: bool,
+  /// Directory of build cache, to reuse rendered and minified pages from previous builds
+  ///
+  /// Entries are keyed by hash of templates, data, and config, so the directory can be kept between builds on different machines (such as with a CI cache).
+  /// Unused entries are removed after each build
+  ///
+  /// Custom helpers must give the same output for the same input.
+  /// Names of custom helpers, hooks, and plugins are part of keys, but not their code, so change `cache_version` when it changes
+  ///
+  /// Default: `None`
+  pub cache: Option<String>,
+  /// Version of custom helpers, hooks, and plugins, which is part of keys of `cache`
+  ///
+  /// Change it (such as `"2"`) when the Rust code of a helper, hook, or plugin changes, so pages are not taken from cache with the old output
+  ///
+  /// Default: `""`
+  pub cache_version: String,
+  /// If render times and call counts of templates are collected, and given in `BuildReport::profile` after build
+  ///
+  /// See `Unreact::profile`
+  ///
+  /// Default: `false`
+  pub profile: bool,
+  /// Limits of rendering each page (time, output size, and depth of partials), so a runaway template fails instead of hanging the build
+  ///
+  /// Default: `RenderLimits::default()` (no limits)
+  pub limits: RenderLimits,
+  /// Compression and resized variants of images in public directory, see `ImageOptions`
+  ///
+  /// Default: `ImageOptions::default()` (no processing)
+  pub images: ImageOptions,
+  /// Checks of image sizes, image formats, and missing assets in build, printed as warnings, see `AuditOptions`
+  ///
+  /// Default: `AuditOptions::default()` (no checks)
+  pub audit: AuditOptions,
+}
+
+impl Config {
+  /// Default config for GitHub Pages, see `Host::GitHubPages`
+  pub fn github_pages() -> Self {
+    Config {
+      host: Host::GitHubPages,
+      github_base_path: true,
+      ..Config::default()
+    }
+  }
+
+  /// Default config for Netlify, see `Host::Netlify`
+  pub fn netlify() -> Self {
+    Config {
+      host: Host::Netlify,
+      ..Config::default()
+    }
+  }
+
+  /// Get output path of style, relative to build directory, **without** `.css` extension
+  ///
+  /// `name`: Name of style, **without** `.scss` extension
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use unreact::prelude::*;
+  ///
+  /// let mut config = Config::default();
+  /// assert_eq!(config.style_path("pages/about"), "styles/pages/about");
+  ///
+  /// config.flatten_styles = true;
+  /// assert_eq!(config.style_path("pages/about"), "styles/about");
+  ///
+  /// config.style_paths.insert("main".to_string(), "css/site".to_string());
+  /// assert_eq!(config.style_path("main"), "css/site");
+  ///
+  /// config.lowercase_paths = true;
+  /// assert_eq!(config.style_path("Print"), "styles/print");
+  /// ```
+  pub fn style_path(&self, name: &str) -> String {
+    let path = self.cased_style_path(name);
+    if self.lowercase_paths {
+      path.to_lowercase()
+    } else {
+      path
+    }
+  }
+
+  /// Get output path of style, without `lowercase_paths`
+  fn cased_style_path(&self, name: &str) -> String {
+    // Custom path
+    if let Some(path) = self.style_paths.get(name) {
+      return path.trim_matches('/').to_string();
+    }
+
+    // Remove subdirectories if flattened
+    let name = if self.flatten_styles {
+      name.rsplit('/').next().unwrap_or(name)
+    } else {
+      name
+    };
+
+    let dir = self.styles_output.trim_matches('/');
+    if dir.is_empty() {
+      name.to_string()
+    } else {
+      format!("{dir}/{name}")
+    }
+  }
+
+  /// Get url to edit source file in repository, if `repository` is set
+  ///
+  /// `file`: Path relative to site, such as `"content/posts/hello.md"`
+  ///
+  /// Uses edit urls of GitHub, or of GitLab if url of repository contains `gitlab`
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use unreact::prelude::*;
+  ///
+  /// let mut config = Config::default();
+  /// assert_eq!(config.edit_url("content/posts/hello.md"), None);
+  ///
+  /// config.repository = Some("https://github.com/user/repo/".to_string());
+  /// assert_eq!(
+  ///   config.edit_url("content/posts/hello.md").as_deref(),
+  ///   Some("https://github.com/user/repo/edit/main/content/posts/hello.md"),
+  /// );
+  ///
+  /// config.repository_dir = "site".to_string();
+  /// assert_eq!(
+  ///   config.edit_url("content/posts/hello.md").as_deref(),
+  ///   Some("https://github.com/user/repo/edit/main/site/content/posts/hello.md"),
+  /// );
+  /// ```
+  pub fn edit_url(&self, file: &str) -> Option<String> {
+    let repository = self.repository.as_deref()?.trim_end_matches('/');
+    let edit = if repository.contains("gitlab") {
+      "-/edit"
+    } else {
+      "edit"
+    };
+
+    let dir = self.repository_dir.trim_matches('/');
+    let file = file.trim_start_matches("./").trim_start_matches('/');
+    let path = if dir.is_empty() {
+      file.to_string()
+    } else {
+      format!("{dir}/{file}")
+    };
+    Some(format!(
+      "{repository}/{edit}/{}/{path}",
+      self.repository_branch
+    ))
+  }
 }
 
 impl Default for Config {
   fn default() -> Self {
     Config {
+      url: None,
       build: "build".to_string(),
+      github_base_path: false,
+      base_path: None,
+      relative_links: false,
+      clean: false,
+      targets: Vec::new(),
       templates: "templates".to_string(),
       public: "public".to_string(),
       styles: "styles".to_string(),
+      content: "content".to_string(),
+      repository: None,
+      repository_branch: "main".to_string(),
+      repository_dir: String::new(),
+      data: "data".to_string(),
+      locales: "locales".to_string(),
+      styles_output: "styles".to_string(),
+      flatten_styles: false,
+      style_paths: HashMap::new(),
+      page_styles: false,
+      inline_only: Vec::new(),
+      ignore_files: false,
       dev_warning: true,
+      lazy_templates: false,
+      dev_build: DEV_BUILD_DIR.to_string(),
+      dev_port: 8080,
+      manifest_dir: true,
+      dev_directories: DirectoryMode::default(),
+      dev_log: true,
       minify: true,
+      format: FormatOptions::default(),
+      host: Host::default(),
+      head: HeadOptions::default(),
+      markdown: MarkdownOptions::default(),
+      manifest: false,
+      build_manifest: false,
+      cname: None,
+      threads: 1,
+      public_copy: CopyOptions::default(),
+      sitemap: false,
+      build_date: None,
+      publish_schedule: Some("publish-schedule.json".to_string()),
+      partial_docs: None,
+      lockfile: None,
+      locked: false,
+      fingerprint_assets: false,
+      normalize_paths: PathNormalization::default(),
+      lowercase_paths: false,
+      check_paths: true,
+      check_links: LinkCheck::default(),
+      skip_write_errors: false,
+      verify_output: false,
+      cache: None,
+      cache_version: String::new(),
+      profile: false,
+      limits: RenderLimits::default(),
+      images: ImageOptions::default(),
+      audit: AuditOptions::default(),
     }
   }
 }
 
+/// Output file of build, see `Unreact::compile`
+#[derive(Debug, Clone)]
+enum Output {
+  /// Content of file
+  Content(String),
+  /// Path of public file to copy
+  Copy(String),
+}
+
+/// Output files, with paths relative to build directory
+type Outputs = BTreeMap<String, Output>;
+
 /// API interface object
 ///
 /// Create with `Unreact::new()`
@@ -70,16 +657,98 @@ pub struct Unreact {
   templates: FileMap,
   /// List of styles as file hashmap
   styles: FileMap,
-  /// List of pages as file list
-  pages: Vec<File>,
+  /// Names of styles which are plain css, see `Vfs::plain_styles`
+  plain_styles: BTreeSet<String>,
+  /// Partials of styles, only used by other styles
+  style_partials: FileMap,
+  /// List of registered pages
+  pages: Vec<Page>,
+  /// Collections of items, available to every template, see `Unreact::collection`
+  collections: Map<String, Value>,
+  /// Parsed data files, available to every template, see `Config::data`
+  data: Map<String, Value>,
+  /// Feeds to write, with path in build directory, see `Unreact::feed`
+  ///
+  /// Created when building, so items can use `canonical` and `noindex` of pages
+  feeds: BTreeMap<String, (FeedOptions, Vec<FeedItem>)>,
+  /// Feeds and sitemaps of collections, by name of collection, see `Unreact::collection_with`
+  collection_options: BTreeMap<String, CollectionOptions>,
+  /// New path of each renamed page, by old path, see `Unreact::rename_page`
+  renamed: BTreeMap<String, String>,
   /// Whether app should compile in dev mode
   ///
   /// If true, localhost server will be created
   is_dev: bool,
   /// URL of production server
-  url: String,
+  url: SiteUrl,
   /// Global variables
   globals: Value,
+  /// Manifest of output files, created in `Unreact::finish`
+  manifest: Manifest,
+  /// Render times of templates, if `profile` is enabled in config
+  profile: Arc<Mutex<Profile>>,
+  /// Storage which output files are written to, instead of build directory, see `Unreact::set_sink`
+  sink: Option<Arc<dyn BuildSink>>,
+  /// Functions which change rendered pages and output files
+  hooks: Hooks,
+  /// Plugins, in order registered, see `Unreact::plugin`
+  plugins: Vec<Arc<dyn Plugin>>,
+  /// Locales of site, with translations, see `Unreact::set_locales`
+  locales: Arc<Locales>,
+  /// Template engine used instead of Handlebars, see `Unreact::set_engine`
+  engine: Option<Arc<dyn TemplateEngine>>,
+  /// Encoder of public images, see `Unreact::set_image_processor`
+  image_processor: Arc<dyn ImageProcessor>,
+  /// Handlebars registry with inbuilt and custom helpers, used by every render
+  registry: Handlebars<'static>,
+  /// Names of custom helpers, from `Unreact::register_helper`, for keys of `cache`
+  helper_names: BTreeSet<String>,
+  /// Custom partials, from `Unreact::register_partial`
+  partials: FileMap,
+  /// Templates read when first rendered, with `lazy_templates` set in config
+  ///
+  /// Templates of plugins are still in `templates`
+  lazy: Option<LazyTemplates>,
+  /// Lock of dev build directory, kept until app is dropped, see `Config::dev_build`
+  dev_lock: Option<DevBuildLock>,
+  /// Local address of dev server, from `dev_port` in config
+  dev_addr: String,
+  /// Receivers of build events, see `Unreact::subscribe`
+  subscribers: Subscribers,
+  /// Registry with every template and partial registered, created on first render
+  ///
+  /// Cleared when templates, partials, or helpers change
+  compiled: RwLock<Option<Arc<Handlebars<'static>>>>,
+  /// Compiled templates shared with other instances, see `Unreact::share_templates`
+  shared: Option<TemplateCache>,
+  /// Hash of every template and partial, and config they depend on, for keys of `cache`
+  ///
+  /// Cleared with `compiled`
+  fingerprint: Mutex<Option<String>>,
+  /// Hash of templates and partials which each template depends on, and config, for keys of rendered pages in `cache`
+  ///
+  /// Cleared with `compiled`
+  template_fingerprints: Mutex<HashMap<String, String>>,
+  /// Cache of rendered and minified pages, if `cache` is set in config
+  cache: Option<BuildCache>,
+  /// Fingerprinted paths of styles and public files, shared with helpers
+  assets: Arc<RwLock<Assets>>,
+  /// Files which failed to write in current build, if `skip_write_errors` is set in config
+  write_failures: Mutex<Vec<(String, std::io::Error)>>,
+  /// Fragments rendered by `cache` helper in current build
+  fragments: Arc<Fragments>,
+  /// Warnings of last build, with `audit` set in config
+  asset_warnings: Mutex<Vec<AssetWarning>>,
+  /// Every casing of each lowercase page path, to find pages which differ only by casing, with `lowercase_paths` set in config
+  page_casings: Mutex<BTreeMap<String, BTreeSet<String>>>,
+  /// Paths of content pages left out until their date, with seconds since Unix epoch of date, see `Config::publish_schedule`
+  scheduled: Mutex<BTreeMap<String, i64>>,
+  /// Signal to reload browsers connected to dev server, after a rebuild
+  #[cfg(feature = "dev-server")]
+  reload: ReloadSignal,
+  /// Directories of content, with name of template, from `Unreact::pages_from_content`, to render changed files again in watch mode
+  #[cfg(feature = "dev-server")]
+  content_dirs: Vec<(String, String)>,
 }
 
 impl Unreact {
@@ -87,11 +756,13 @@ impl Unreact {
   ///
   /// Use `Config::default()` as `config` for default config
   ///
+  /// `url`: Url of production site, as `&str` or `SiteUrl`. Returns error if not valid, see `SiteUrl::parse`
+  ///
   /// # Examples
   ///
   /// Compiles a basic site
   ///
-  /// ```
+  /// ```no_run
   /// use unreact::prelude::*;
   ///
   /// fn main() -> UnreactResult<()> {
@@ -103,387 +774,4319 @@ impl Unreact {
   ///   Ok(())
   /// }
   /// ```
-  pub fn new(config: Config, is_dev: bool, url: &str) -> UnreactResult<Self> {
-    // Convert build directory to constant dev build directory if is dev
-    let config = if is_dev {
-      {
-        Config {
-          build: DEV_BUILD_DIR.to_string(),
-          ..config
-        }
-      }
+  pub fn new<U>(config: Config, is_dev: bool, url: U) -> UnreactResult<Self>
+  where
+    U: TryInto<SiteUrl>,
+    UnreactError: From<U::Error>,
+  {
+    let url = match &config.url {
+      Some(url) => SiteUrl::parse(url)?,
+      None => url.try_into()?,
+    };
+    Self::check_targets(&config)?;
+
+    // Project site on GitHub Pages, built in GitHub Actions
+    // Dev server uses repository of git remote, to serve site under the same path
+    let repository = std::env::var("GITHUB_REPOSITORY")
+      .ok()
+      .or_else(|| local_repository().filter(|_| is_dev));
+    let url = match (&config.base_path, repository) {
+      (Some(path), _) => url.with_base_path(path)?,
+      (None, Some(repository)) if config.github_base_path => url.with_repository(&repository),
+      _ => url,
+    };
+
+    // Site crate of a cargo workspace, run from root of workspace
+    let config = use_manifest_dir(config);
+
+    // Convert build directory to a new dev build directory if is dev
+    let (config, dev_lock) = if is_dev {
+      let (build, lock) = claim_dev_build(&config.dev_build)?;
+      (Config { build, ..config }, Some(lock))
     } else {
-      config
+      (config, None)
     };
 
     // Check that directories exists
     Self::check_dirs(&config)?;
 
+    // Read templates and styles from directories
+    // Templates are only listed if lazy
+    let lazy = config.lazy_templates && is_dev;
+    let vfs = if lazy {
+      Vfs::read_without_templates(&config)?
+    } else {
+      Vfs::read(&config)?
+    };
+
     // Create interface
-    Ok(Unreact {
-      templates: Self::load_templates(&config)?,
-      styles: Self::load_styles(&config)?,
+    let mut app = Self::from_vfs(config, is_dev, url, vfs);
+    app.dev_lock = dev_lock;
+    if is_dev {
+      app.dev_addr = dev_address(app.config.dev_port);
+    }
+    if lazy {
+      app.lazy = Some(LazyTemplates::list(&app.config)?);
+    }
+    app.write_assets().read_public(&app.config);
+    Ok(app)
+  }
+
+  /// Create new API interface, with templates and styles from memory
+  ///
+  /// Does not read or write any directories, so `Unreact::render`, `Unreact::render_markdown`, and `Unreact::render_style` work without a file system (such as on `wasm32`)
+  ///
+  /// `Unreact::finish` still writes to the build directory, which must exist
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use unreact::prelude::*;
+  /// use serde_json::json;
+  ///
+  /// fn main() -> UnreactResult<()> {
+  ///   let mut vfs = Vfs::new();
+  ///   vfs.templates.insert("index".to_string(), "<h1>{{msg}}</h1>".to_string());
+  ///   vfs.styles.insert("main".to_string(), "a { b { color: red; } }".to_string());
+  ///
+  ///   let url = SiteUrl::parse("https://mysite.com")?;
+  ///   let app = Unreact::from_vfs(Config::default(), false, url, vfs);
+  ///
+  ///   assert_eq!(app.render("index", &json!({"msg": "Hello!"}))?, "<h1>Hello!</h1>");
+  ///   assert_eq!(app.render_style("main")?, "a b{color:red}");
+  ///
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn from_vfs(config: Config, is_dev: bool, url: SiteUrl, vfs: Vfs) -> Self {
+    let assets = Assets::new(&config, is_dev, &vfs.styles);
+    let mut app = Unreact {
+      assets: Arc::new(RwLock::new(assets)),
+      templates: vfs.templates,
+      styles: vfs.styles,
+      plain_styles: vfs.plain_styles,
+      style_partials: vfs.style_partials,
+      data: vfs.data,
       pages: Vec::new(),
+      collections: Map::new(),
+      feeds: BTreeMap::new(),
+      collection_options: BTreeMap::new(),
+      renamed: BTreeMap::new(),
       config,
       is_dev,
-      url: url.to_string(),
+      url,
       globals: Value::Null,
-    })
+      manifest: Manifest::default(),
+      profile: Arc::default(),
+      engine: None,
+      image_processor: Arc::new(PngProcessor),
+      locales: Arc::default(),
+      hooks: Hooks::default(),
+      plugins: Vec::new(),
+      sink: None,
+      #[cfg(feature = "dev-server")]
+      reload: ReloadSignal::new(),
+      #[cfg(feature = "dev-server")]
+      content_dirs: Vec::new(),
+      registry: Handlebars::new(),
+      helper_names: BTreeSet::new(),
+      partials: FileMap::new(),
+      lazy: None,
+      dev_lock: None,
+      dev_addr: ADDRESS.to_string(),
+      subscribers: Subscribers::default(),
+      compiled: RwLock::new(None),
+      shared: None,
+      fingerprint: Mutex::new(None),
+      template_fingerprints: Mutex::default(),
+      cache: None,
+      write_failures: Mutex::default(),
+      scheduled: Mutex::default(),
+      fragments: Arc::default(),
+      asset_warnings: Mutex::default(),
+      page_casings: Mutex::default(),
+    };
+    app.cache = app.config.cache.as_deref().map(BuildCache::new);
+
+    // Register inbuilt helpers
+    let mut registry = Handlebars::new();
+    app.register_inbuilt_helpers(&mut registry);
+    app.registry = registry;
+
+    app
   }
 
-  /// Set global variables to new `serde_json::Value`
+  /// Register a custom Handlebars helper, which can be used in every template
   ///
-  /// # Examples
+  /// Helper with the same name as an inbuilt helper replaces it
   ///
-  /// Creates a global variable
+  /// See [handlebars](https://docs.rs/handlebars/4/handlebars/trait.HelperDef.html) crate for writing helpers, re-exported as `unreact::handlebars`
+  ///
+  /// # Examples
   ///
   /// ```
   /// use unreact::prelude::*;
+  /// use unreact::handlebars::{Context, Handlebars, Helper, HelperResult, Output, RenderContext};
   /// use serde_json::json;
   ///
   /// fn main() -> UnreactResult<()> {
-  ///   let mut app = Unreact::new(Config::default(), false, "https://mysite.com")?;
+  ///   let mut vfs = Vfs::new();
+  ///   vfs.templates.insert("index".to_string(), "<h1>{{shout msg}}</h1>".to_string());
   ///
-  ///   app.set_globals(json!({"my_global": "From global! :)"}));
+  ///   let url = SiteUrl::parse("https://mysite.com")?;
+  ///   let mut app = Unreact::from_vfs(Config::default(), false, url, vfs);
+  ///
+  ///   app.register_helper(
+  ///     "shout",
+  ///     |h: &Helper, _: &Handlebars, _: &Context, _: &mut RenderContext, out: &mut dyn Output| -> HelperResult {
+  ///       let text = h.param(0).and_then(|x| x.value().as_str()).unwrap_or_default();
+  ///       out.write(&text.to_uppercase())?;
+  ///       Ok(())
+  ///     },
+  ///   );
+  ///
+  ///   assert_eq!(app.render("index", &json!({"msg": "Hello"}))?, "<h1>HELLO</h1>");
   ///
   ///   Ok(())
   /// }
   /// ```
-  // ? Create getter ?
-  pub fn set_globals(&mut self, data: Value) -> &mut Self {
-    self.globals = data;
+  pub fn register_helper(
+    &mut self,
+    name: &str,
+    helper: impl HelperDef + Send + Sync + 'static,
+  ) -> &mut Self {
+    self.registry.register_helper(name, Box::new(helper));
+    self.helper_names.insert(name.to_string());
+    self.clear_registry();
     self
   }
 
-  /// Register new page (file) with any path, without template (plain)
-  ///
-  /// `path`: Output path in build directory, **without** `.html` extension
+  /// Register a custom partial, which can be used in every template, without a file in templates directory
+  ///
+  /// Partial with the same name as a template or inbuilt partial replaces it
+  ///
+  /// Returns error if partial is not a valid template
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use unreact::prelude::*;
+  /// use serde_json::json;
+  ///
+  /// fn main() -> UnreactResult<()> {
+  ///   let mut vfs = Vfs::new();
+  ///   vfs.templates.insert("index".to_string(), "{{>greeting name=\"world\"}}".to_string());
+  ///
+  ///   let url = SiteUrl::parse("https://mysite.com")?;
+  ///   let mut app = Unreact::from_vfs(Config::default(), false, url, vfs);
+  ///
+  ///   app.register_partial("greeting", "<p>Hello {{name}}!</p>")?;
+  ///
+  ///   assert_eq!(app.render("index", &json!({}))?, "<p>Hello world!</p>");
+  ///
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn register_partial(&mut self, name: &str, template: &str) -> UnreactResult<&mut Self> {
+    // Check that partial compiles, before it is used by a render
+    if let Err(err) = Template::compile(template) {
+      return Err(UnreactError::RegisterPartialFail(
+        name.to_string(),
+        Box::new(err),
+      ));
+    }
+
+    self.partials.insert(name.to_string(), template.to_string());
+    self.clear_registry();
+    Ok(self)
+  }
+
+  /// Get documentation of every template and partial which starts with a comment, by name, see `PartialDoc`
+  ///
+  /// Partials registered with `Unreact::register_partial` replace templates of the same name.
+  /// With `lazy_templates` set in config, only templates which are read are included
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use unreact::prelude::*;
+  ///
+  /// fn main() -> UnreactResult<()> {
+  ///   let mut vfs = Vfs::new();
+  ///   vfs.templates.insert("index".to_string(), "{{>card title=\"Hi\"}}".to_string());
+  ///
+  ///   let url = SiteUrl::parse("https://mysite.com")?;
+  ///   let mut app = Unreact::from_vfs(Config::default(), false, url, vfs);
+  ///
+  ///   app.register_partial("card", "{{!--\n  Card\n  @param title: string\n--}}<h2>{{title}}</h2>")?;
+  ///
+  ///   let docs = app.partial_docs();
+  ///   assert_eq!(docs.len(), 1);
+  ///   assert_eq!(docs["card"].description, "Card");
+  ///   assert_eq!(docs["card"].params[0].name, "title");
+  ///
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn partial_docs(&self) -> BTreeMap<String, PartialDoc> {
+    let mut sources = self.templates().into_owned();
+    sources.extend(self.partials.clone());
+    (sources.iter())
+      .filter_map(|(name, template)| Some((name.to_string(), PartialDoc::parse(template)?)))
+      .collect()
+  }
+
+  /// Set locales of site, so every page registered afterwards is rendered once for each locale
+  ///
+  /// The first locale is the default, with pages at their own path. Pages of other locales are in a subdirectory, such as `de/about`
+  ///
+  /// Translations of each locale are read from `locales` directory in config, such as `./locales/de.json`.
+  /// Templates translate keys with the `t` helper, such as `{{t "nav.home"}}` or `{{t "greeting" name=user}}` (for `"Hello {name}"`).
+  /// Keys missing from a locale use the default locale
+  ///
+  /// Data of each page has `locale`, `locale_path` (such as `""` or `"/de"`, for links such as `{{>URL}}{{locale_path}}/about`),
+  /// and `alternates`, with `locale` and `path` of the page in every locale
+  ///
+  /// Returns error if a translation file is missing or is not valid JSON
+  ///
+  /// # Examples
+  ///
+  /// With `./locales/en.json` containing `{"nav": {"home": "Home"}}`, and `./locales/de.json` containing `{"nav": {"home": "Startseite"}}`:
+  ///
+  /// ```no_run
+  /// use unreact::prelude::*;
+  /// use serde_json::Value;
+  ///
+  /// fn main() -> UnreactResult<()> {
+  ///   let mut app = Unreact::new(Config::default(), false, "https://mysite.com")?;
+  ///
+  ///   app.set_locales(&["en", "de"])?;
+  ///
+  ///   // Renders to `./build/index.html` and `./build/de/index.html`
+  ///   app.index("home", &Value::Null)?;
+  ///
+  ///   app.finish()?;
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn set_locales(&mut self, locales: &[&str]) -> UnreactResult<&mut Self> {
+    let names = locales.iter().map(|x| x.to_string()).collect::<Vec<_>>();
+    self.locales = Arc::new(Locales::read(&self.config.locales, &names)?);
+    self.clear_registry();
+    Ok(self)
+  }
+
+  /// Add a function which changes html of every page, after rendering, and before minify or format
+  ///
+  /// `hook`: Called with path of page (**without** `.html` extension) and html, returns new html.
+  /// Hooks run in order added, each with the output of the last
+  ///
+  /// Hook must give the same output for the same input, or `cache` must be cleared when it changes
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use unreact::prelude::*;
+  ///
+  /// fn main() -> UnreactResult<()> {
+  ///   let url = SiteUrl::parse("https://mysite.com")?;
+  ///   let mut app = Unreact::from_vfs(Config::default(), false, url, Vfs::new());
+  ///
+  ///   // Add analytics to every page
+  ///   app.on_page_rendered(|_path, html| html.replace("</body>", "<script src=\"/stats.js\"></script></body>"));
+  ///   app.page_plain("index", "<body>Home</body>");
+  ///
+  ///   let files = app.compile()?;
+  ///   assert_eq!(files["index.html"], "<body>Home<script src=/stats.js></script>");
+  ///
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn on_page_rendered(
+    &mut self,
+    hook: impl Fn(&str, &str) -> String + Send + Sync + 'static,
+  ) -> &mut Self {
+    self.hooks.add_page_rendered(hook);
+    self.clear_fingerprints();
+    self
+  }
+
+  /// Add a function which changes content of every output file, after minify or format, before it is written
+  ///
+  /// `hook`: Called with path of file relative to build directory (**with** extension, such as `"index.html"` or `"styles/main.css"`) and content,
+  /// returns new content. Hooks run in order added, each with the output of the last
+  ///
+  /// Files copied from `public` directory and the manifest are not included. Also used by `Unreact::compile`
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use unreact::prelude::*;
+  ///
+  /// fn main() -> UnreactResult<()> {
+  ///   let url = SiteUrl::parse("https://mysite.com")?;
+  ///   let mut app = Unreact::from_vfs(Config::default(), false, url, Vfs::new());
+  ///
+  ///   // Add comment to html files
+  ///   app.before_write(|file, content| {
+  ///     if file.ends_with(".html") {
+  ///       format!("<!-- Built with unreact -->{content}")
+  ///     } else {
+  ///       content.to_string()
+  ///     }
+  ///   });
+  ///   app.page_plain("index", "Home");
+  ///
+  ///   let files = app.compile()?;
+  ///   assert_eq!(files["index.html"], "<!-- Built with unreact -->Home");
+  ///
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn before_write(
+    &mut self,
+    hook: impl Fn(&str, &str) -> String + Send + Sync + 'static,
+  ) -> &mut Self {
+    self.hooks.add_before_write(hook);
+    self.clear_fingerprints();
+    self
+  }
+
+  /// Get receiver of events of the build lifecycle, such as each page rendered and file written, see `BuildEvent`
+  ///
+  /// Events are sent from the time of subscribing, until app is dropped. Receive them on another thread, or with `try_iter` after a build
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use unreact::prelude::*;
+  /// use serde_json::json;
+  ///
+  /// fn main() -> UnreactResult<()> {
+  ///   let mut vfs = Vfs::new();
+  ///   vfs.templates.insert("index".to_string(), "<h1>Home</h1>".to_string());
+  ///   vfs.styles.insert("main".to_string(), "a { color: red; }".to_string());
+  ///
+  ///   let url = SiteUrl::parse("https://mysite.com")?;
+  ///   let mut app = Unreact::from_vfs(Config::default(), false, url, vfs);
+  ///   let events = app.subscribe();
+  ///
+  ///   app.index("index", &json!({}))?;
+  ///   app.compile()?;
+  ///
+  ///   let events = events.try_iter().collect::<Vec<_>>();
+  ///   assert!(events.contains(&BuildEvent::TemplateLoaded { name: "index".to_string() }));
+  ///   assert!(events.contains(&BuildEvent::PageRendered { path: "index".to_string() }));
+  ///   assert!(events.contains(&BuildEvent::StyleCompiled {
+  ///     name: "main".to_string(),
+  ///     file: "styles/main.css".to_string(),
+  ///   }));
+  ///
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn subscribe(&self) -> Receiver<BuildEvent> {
+    self.subscribers.subscribe()
+  }
+
+  /// Register a plugin, which can change source files, rendered pages, and output files, and read a report of each build, see `Plugin`
+  ///
+  /// Source files are changed with `Plugin::load` immediately, so register plugins before pages.
+  /// Plugins run in order registered, with hooks of `Unreact::on_page_rendered` and `Unreact::before_write`
+  ///
+  /// Returns `UnreactError::PluginFail` if plugin fails to load
+  pub fn plugin(&mut self, plugin: impl Plugin + 'static) -> UnreactResult<&mut Self> {
+    let plugin: Arc<dyn Plugin> = Arc::new(plugin);
+
+    let mut vfs = Vfs {
+      templates: std::mem::take(&mut self.templates),
+      styles: std::mem::take(&mut self.styles),
+      plain_styles: std::mem::take(&mut self.plain_styles),
+      style_partials: std::mem::take(&mut self.style_partials),
+      data: std::mem::take(&mut self.data),
+    };
+    let loaded = load_plugin(&*plugin, &mut vfs);
+    self.templates = vfs.templates;
+    self.styles = vfs.styles;
+    self.plain_styles = vfs.plain_styles;
+    self.style_partials = vfs.style_partials;
+    self.data = vfs.data;
+    self.write_assets().set_styles(&self.config, &self.styles);
+    self.clear_registry();
+    loaded?;
+
+    let transform = Arc::clone(&plugin);
+    self
+      .hooks
+      .add_page_rendered(move |path, html| transform.transform(path, html));
+    let write = Arc::clone(&plugin);
+    self
+      .hooks
+      .add_before_write(move |file, content| write.write(file, content));
+    self.plugins.push(plugin);
+    self.clear_fingerprints();
+    Ok(self)
+  }
+
+  /// Read all templates, styles, and data files from directories in config, and load each plugin
+  fn read_vfs(&self) -> UnreactResult<Vfs> {
+    let mut vfs = match self.lazy {
+      Some(_) => Vfs::read_without_templates(&self.config)?,
+      None => Vfs::read(&self.config)?,
+    };
+    for plugin in &self.plugins {
+      load_plugin(&**plugin, &mut vfs)?;
+    }
+    Ok(vfs)
+  }
+
+  /// Write output files of `Unreact::finish` to custom storage, instead of build directory, see `BuildSink`
+  ///
+  /// No build directory, staging directory, or manifest is written. Ignored in dev mode, as the dev server uses the build directory
+  pub fn set_sink(&mut self, sink: impl BuildSink + 'static) -> &mut Self {
+    self.sink = Some(Arc::new(sink));
+    self
+  }
+
+  /// Share compiled templates with other instances, so templates are parsed once for many builds (such as one for each tenant)
+  ///
+  /// Instances with the same templates, partials, and config use the same registry. Every instance which shares a cache must register the same custom helpers.
+  /// Not used if `profile` is enabled in config
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use unreact::prelude::*;
+  /// use serde_json::json;
+  ///
+  /// fn main() -> UnreactResult<()> {
+  ///   let mut vfs = Vfs::new();
+  ///   vfs.templates.insert("index".to_string(), "<h1>{{name}}</h1>".to_string());
+  ///
+  ///   let cache = TemplateCache::new();
+  ///   for name in ["a", "b", "c"] {
+  ///     let url = SiteUrl::parse("https://mysite.com")?;
+  ///     let mut app = Unreact::from_vfs(Config::default(), false, url, vfs.clone());
+  ///     app.share_templates(&cache);
+  ///     assert_eq!(app.render("index", &json!({"name": name}))?, format!("<h1>{name}</h1>"));
+  ///   }
+  ///
+  ///   // Templates were compiled once
+  ///   assert_eq!(cache.len(), 1);
+  ///
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn share_templates(&mut self, cache: &TemplateCache) -> &mut Self {
+    self.shared = Some(cache.clone());
+    self.clear_registry();
+    self
+  }
+
+  /// Render templates with another template language, instead of Handlebars, see `TemplateEngine`
+  ///
+  /// Custom helpers and partials, and inbuilt partials other than `URL`, `LINK`, and `STYLE`, are only used by Handlebars
+  ///
+  /// The engine must give the same output for the same input, or `cache` must be cleared when it changes
+  pub fn set_engine(&mut self, engine: impl TemplateEngine + 'static) -> &mut Self {
+    self.engine = Some(Arc::new(engine));
+    self.clear_registry();
+    self
+  }
+
+  /// Process images of public directory with another encoder, instead of `PngProcessor`, such as to create WebP images, see `ImageProcessor`
+  ///
+  /// Set processor before registering pages, as `srcset` of `IMAGE` partial depends on formats it supports
+  pub fn set_image_processor(&mut self, processor: impl ImageProcessor + 'static) -> &mut Self {
+    self.image_processor = Arc::new(processor);
+    let mut registry = std::mem::take(&mut self.registry);
+    self.register_image_helper(&mut registry);
+    self.registry = registry;
+    self.clear_registry();
+    self
+  }
+
+  /// Set global variables to new `serde_json::Value`, replacing all globals
+  ///
+  /// Globals are available to every template. Page data overrides globals with the same key, see `Unreact::render`.
+  /// Set globals before registering pages, as pages are rendered when registered
+  ///
+  /// Use `Unreact::merge_globals` or `Unreact::global` to keep other globals
+  ///
+  /// # Examples
+  ///
+  /// Creates a global variable
+  ///
+  /// ```no_run
+  /// use unreact::prelude::*;
+  /// use serde_json::json;
+  ///
+  /// fn main() -> UnreactResult<()> {
+  ///   let mut app = Unreact::new(Config::default(), false, "https://mysite.com")?;
+  ///
+  ///   app.set_globals(json!({"my_global": "From global! :)"}));
+  ///
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn set_globals(&mut self, data: Value) -> &mut Self {
+    self.globals = data;
+    self
+  }
+
+  /// Merge object into global variables, see `Unreact::set_globals`
+  ///
+  /// Nested objects are merged, other values replace existing values, and `null` values remove keys
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use unreact::prelude::*;
+  /// use serde_json::json;
+  ///
+  /// fn main() -> UnreactResult<()> {
+  ///   let url = SiteUrl::parse("https://mysite.com")?;
+  ///   let mut app = Unreact::from_vfs(Config::default(), false, url, Vfs::new());
+  ///
+  ///   app.set_globals(json!({"site": {"name": "My site"}, "year": 2023}));
+  ///   app.merge_globals(json!({"site": {"author": "Me"}, "year": 2024}));
+  ///
+  ///   assert_eq!(
+  ///     app.globals(),
+  ///     &json!({"site": {"name": "My site", "author": "Me"}, "year": 2024})
+  ///   );
+  ///
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn merge_globals(&mut self, data: Value) -> &mut Self {
+    merge_json(&mut self.globals, data);
+    self
+  }
+
+  /// Set one global variable, replacing any value with the same key, see `Unreact::set_globals`
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use unreact::prelude::*;
+  /// use serde_json::json;
+  ///
+  /// fn main() -> UnreactResult<()> {
+  ///   let mut vfs = Vfs::new();
+  ///   vfs.templates.insert("index".to_string(), "{{title}} - {{site}}".to_string());
+  ///
+  ///   let url = SiteUrl::parse("https://mysite.com")?;
+  ///   let mut app = Unreact::from_vfs(Config::default(), false, url, vfs);
+  ///
+  ///   app.global("site", json!("My site")).global("title", json!("Home"));
+  ///
+  ///   // Page data overrides globals
+  ///   assert_eq!(app.render("index", &json!({"title": "About"}))?, "About - My site");
+  ///   assert_eq!(app.render("index", &json!({}))?, "Home - My site");
+  ///
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn global(&mut self, key: &str, value: Value) -> &mut Self {
+    if !self.globals.is_object() {
+      self.globals = Value::Object(Map::new());
+    }
+    if let Value::Object(globals) = &mut self.globals {
+      globals.insert(key.to_string(), value);
+    }
+    self
+  }
+
+  /// Get global variables, see `Unreact::set_globals`
+  pub fn globals(&self) -> &Value {
+    &self.globals
+  }
+
+  /// Register new page (file) with any path, without template (plain)
+  ///
+  /// `path`: Output path in build directory, **without** `.html` extension
+  ///
+  /// `content`: Raw text content to write to file, without template
+  ///
+  /// # Examples
+  ///
+  /// Renders two files with raw text
+  ///
+  /// ```no_run
+  /// use unreact::prelude::*;
+  ///
+  /// fn main() -> UnreactResult<()> {
+  ///   let mut app = Unreact::new(Config::default(), false, "https://mysite.com")?;
+  ///
+  ///   // Renders to `./build/index.html`
+  ///   app.page_plain("index", "This is my site");
+  ///   // Renders to `./build/path/file.html`
+  ///   app.page_plain("path/file", "This file is in ./build/path/file.html");
+  ///
+  ///   app.finish()?;
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn page_plain(&mut self, path: &str, content: &str) -> &mut Self {
+    let path = self.register_path(path);
+    self
+      .pages
+      .push(Page::new(&path, content, PageSource::Plain));
+    self
+  }
+
+  /// Register new page (file) with any path, from Markdown content, without template
+  ///
+  /// Markdown is converted with extensions from `markdown` property in config, and shortcodes are expanded.
+  /// Page is converted when building, the same as `Unreact::page`
+  ///
+  /// See `Unreact::render_markdown`
+  ///
+  /// `path`: Output path in build directory, **without** `.html` extension
+  ///
+  /// `content`: Markdown content to convert to html
+  ///
+  /// # Examples
+  ///
+  /// Renders a page from Markdown, with a table
+  ///
+  /// ```no_run
+  /// use unreact::prelude::*;
+  ///
+  /// fn main() -> UnreactResult<()> {
+  ///   let mut app = Unreact::new(Config::default(), false, "https://mysite.com")?;
+  ///
+  ///   // Renders to `./build/about.html`
+  ///   app.page_markdown("about", "# About\n\n| Name | Role |\n| ---- | ---- |\n| Me | Author |")?;
+  ///
+  ///   app.finish()?;
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn page_markdown(&mut self, path: &str, content: &str) -> UnreactResult<&mut Self> {
+    let path = &self.register_path(path);
+    self.check_path(&format!("{path}.html"))?;
+    self.pages.push(Page::unrendered(
+      path,
+      PageSource::Markdown(content.to_string()),
+    ));
+    Ok(self)
+  }
+
+  /// Register new page (file) with any path, with template
+  ///
+  /// Page is rendered when building, in parallel with other pages (see `Config::threads`), so errors of rendering are returned by `Unreact::finish` or `Unreact::compile`
+  ///
+  /// `path`: Output path in build directory, **without** `.html` extension
+  ///
+  /// `template`: Name of template to render, **without** `.hbs` extension
+  ///
+  /// `data`: JSON data to render with (use `serde_json::json!` macro)
+  ///
+  /// # Examples
+  ///
+  /// Renders two files with templates
+  ///
+  /// ```no_run
+  /// use unreact::prelude::*;
+  /// use serde_json::{json, Value};
+  ///
+  /// fn main() -> UnreactResult<()> {
+  ///   let mut app = Unreact::new(Config::default(), false, "https://mysite.com")?;
+  ///
+  ///   // Renders to `./build/help.html`, using `./templates/help_template.hbs`, with no data
+  ///   app.page("help", "help_template", &Value::Null)?;
+  ///
+  ///   // Renders to `./build/path/file.html`, using `./templates/other/template.hbs`, with a custom message
+  ///   app.page("path/file", "other/template", &json!({"msg": "Hello!"}))?;
+  ///
+  ///   app.finish()?;
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn page(&mut self, path: &str, template: &str, data: &Value) -> UnreactResult<&mut Self> {
+    // Once for each locale, if set
+    for (path, data) in self.locales.localize(&self.register_path(path), data) {
+      self.check_path(&format!("{path}.html"))?;
+      self.pages.push(Page::unrendered(
+        &path,
+        PageSource::Template(template.to_string(), data),
+      ));
+    }
+    Ok(self)
+  }
+
+  /// Register index page (`./index.html`), with template
+  ///
+  /// Alias of `app.page("index", ...)`
+  ///
+  /// `path`: Output path in build directory, **without** `.html` extension
+  ///
+  /// `template`: Name of template to render, **without** `.hbs` extension
+  ///
+  /// `data`: JSON data to render with (use `serde_json::json!` macro)
+  ///
+  /// # Examples
+  ///
+  /// Renders an index page with a custom message
+  ///
+  /// ```no_run
+  /// use unreact::prelude::*;
+  /// use serde_json::{json};
+  ///
+  /// fn main() -> UnreactResult<()> {
+  ///   let mut app = Unreact::new(Config::default(), false, "https://mysite.com")?;
+  ///
+  ///   // Renders to `./build/index.html`, using `./templates/standard.hbs`, with a custom message
+  ///   app.index("standard", &json!({"msg": "Hello!"}))?;
+  ///
+  ///   app.finish()?;
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn index(&mut self, template: &str, data: &Value) -> UnreactResult<&mut Self> {
+    self.page("index", template, data)
+  }
+
+  /// Register 404 (not found) page (`./404.html`)
+  ///
+  /// Alias of `app.page("404", ...)`
+  ///
+  /// `path`: Output path in build directory, **without** `.html` extension
+  ///
+  /// `template`: Name of template to render, **without** `.hbs` extension
+  ///
+  /// `data`: JSON data to render with (use `serde_json::json!` macro)
+  ///
+  /// # Examples
+  ///
+  /// Renders a 404 page
+  ///
+  /// ```no_run
+  /// use unreact::prelude::*;
+  /// use serde_json::{Value};
+  ///
+  /// fn main() -> UnreactResult<()> {
+  ///   let mut app = Unreact::new(Config::default(), false, "https://mysite.com")?;
+  ///
+  ///   // Renders to `./build/404.html`, using `./templates/errors/not_found.hbs`, with no data
+  ///   app.not_found("errors/not_found", &Value::Null)?;
+  ///
+  ///   app.finish()?;
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn not_found(&mut self, template: &str, data: &Value) -> UnreactResult<&mut Self> {
+    self.page("404", template, data)
+  }
+
+  /// Register a page with a template, only if `condition` is true
+  ///
+  /// Template is not rendered if `condition` is false
+  ///
+  /// See `Unreact::page`
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// use unreact::prelude::*;
+  /// use serde_json::json;
+  ///
+  /// fn main() -> UnreactResult<()> {
+  ///   let mut app = Unreact::new(Config::default(), false, "https://mysite.com")?;
+  ///   let show_beta = std::env::var("BETA").is_ok();
+  ///
+  ///   app.page_if(show_beta, "beta", "beta", &json!({"version": 2}))?;
+  ///
+  ///   app.finish()?;
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn page_if(
+    &mut self,
+    condition: bool,
+    path: &str,
+    template: &str,
+    data: &Value,
+  ) -> UnreactResult<&mut Self> {
+    if condition {
+      self.page(path, template, data)?;
+    }
+    Ok(self)
+  }
+
+  /// Register a page with a template, only in development mode
+  ///
+  /// Useful for pages such as a style guide, which should not be deployed
+  ///
+  /// See `Unreact::page`
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// use unreact::prelude::*;
+  /// use serde_json::Value;
+  ///
+  /// fn main() -> UnreactResult<()> {
+  ///   let mut app = Unreact::new(Config::default(), is_dev(), "https://mysite.com")?;
+  ///
+  ///   // Only at `http://127.0.0.1:8080/style-guide`, with `cargo run -- --dev`
+  ///   app.dev_only_page("style-guide", "style_guide", &Value::Null)?;
+  ///
+  ///   app.finish()?;
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn dev_only_page(
+    &mut self,
+    path: &str,
+    template: &str,
+    data: &Value,
+  ) -> UnreactResult<&mut Self> {
+    self.page_if(self.is_dev, path, template, data)
+  }
+
+  /// Check if a page is registered with path
+  ///
+  /// `path`: Output path in build directory, **without** `.html` extension
+  pub fn has_page(&self, path: &str) -> bool {
+    let path = self.page_path(path);
+    self.pages.iter().any(|page| page.path == path)
+  }
+
+  /// Remove a registered page, so it is not written by `Unreact::finish`
+  ///
+  /// Does nothing if no page is registered with path
+  ///
+  /// `path`: Output path in build directory, **without** `.html` extension
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use unreact::prelude::*;
+  ///
+  /// fn main() -> UnreactResult<()> {
+  ///   let url = SiteUrl::parse("https://mysite.com")?;
+  ///   let mut app = Unreact::from_vfs(Config::default(), false, url, Vfs::new());
+  ///
+  ///   app.page_plain("index", "Home").page_plain("internal", "Internal notes");
+  ///
+  ///   // Strip internal pages in production
+  ///   if !is_dev() {
+  ///     app.remove_page("internal");
+  ///   }
+  ///
+  ///   assert!(app.has_page("index"));
+  ///   assert!(!app.has_page("internal"));
+  ///
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn remove_page(&mut self, path: &str) -> &mut Self {
+    let path = self.page_path(path);
+    self.pages.retain(|page| page.path != path);
+    (self.page_casings.get_mut())
+      .unwrap_or_else(PoisonError::into_inner)
+      .remove(&path);
+    self
+  }
+
+  /// Replace a registered page with a template, keeping its path
+  ///
+  /// Returns error if no page is registered with path
+  ///
+  /// `path`: Output path in build directory, **without** `.html` extension
+  ///
+  /// `template`: Name of template to render, **without** `.hbs` extension
+  ///
+  /// `data`: JSON data to render with (use `serde_json::json!` macro)
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use unreact::prelude::*;
+  /// use serde_json::json;
+  ///
+  /// fn main() -> UnreactResult<()> {
+  ///   let mut vfs = Vfs::new();
+  ///   vfs.templates.insert("landing_a".to_string(), "<h1>{{title}}</h1>".to_string());
+  ///   vfs.templates.insert("landing_b".to_string(), "<h2>{{title}}</h2>".to_string());
+  ///
+  ///   let url = SiteUrl::parse("https://mysite.com")?;
+  ///   let mut app = Unreact::from_vfs(Config::default(), false, url, vfs);
+  ///
+  ///   app.page("index", "landing_a", &json!({"title": "Welcome"}))?;
+  ///
+  ///   // Swap to variant B
+  ///   app.replace_page("index", "landing_b", &json!({"title": "Welcome"}))?;
+  ///
+  ///   assert!(app.replace_page("missing", "landing_b", &json!({})).is_err());
+  ///
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn replace_page(
+    &mut self,
+    path: &str,
+    template: &str,
+    data: &Value,
+  ) -> UnreactResult<&mut Self> {
+    // Page of each locale, if set
+    for (path, data) in self.locales.localize(&self.page_path(path), data) {
+      let Some(index) = self.pages.iter().position(|page| page.path == path) else {
+        return Err(UnreactError::PageNotExist(path));
+      };
+
+      let page = &mut self.pages[index];
+      *page = Page {
+        sitemap: page.sitemap.clone(),
+        ..Page::unrendered(&path, PageSource::Template(template.to_string(), data))
+      };
+    }
+    Ok(self)
+  }
+
+  /// Move a registered page to a new path, with a page at the old path which redirects to it
+  ///
+  /// Links to the old path in `href` of every page are changed to the new path, when building.
+  /// Returns templates, custom partials, data files, collections, and pages which still contain the old path (such as `"templates/nav"` or `"pages/blog/index"`), to change by hand
+  ///
+  /// Returns error if no page is registered with old path, or a page is registered with new path
+  ///
+  /// `old`: Current path of page, **without** `.html` extension
+  ///
+  /// `new`: New path of page, **without** `.html` extension
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use unreact::prelude::*;
+  /// use serde_json::json;
+  ///
+  /// fn main() -> UnreactResult<()> {
+  ///   let mut vfs = Vfs::new();
+  ///   vfs.templates.insert("nav".to_string(), r#"<a href="{{>URL}}/about">About</a>"#.to_string());
+  ///
+  ///   let url = SiteUrl::parse("https://mysite.com")?;
+  ///   let mut app = Unreact::from_vfs(Config::default(), false, url, vfs);
+  ///   app.page("index", "nav", &json!({}))?;
+  ///   app.page_plain("about", "About me");
+  ///
+  ///   let references = app.rename_page("about", "about-us")?;
+  ///   assert_eq!(references, vec!["templates/nav"]);
+  ///
+  ///   let files = app.compile()?;
+  ///   assert!(files["index.html"].contains("https://mysite.com/about-us"));
+  ///   assert_eq!(files["about-us.html"], "About me");
+  ///   assert!(files["about.html"].contains(r#"url=https://mysite.com/about-us""#));
+  ///
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn rename_page(&mut self, old: &str, new: &str) -> UnreactResult<Vec<String>> {
+    let old = self.page_path(old);
+    let new = self.page_path(new);
+    let Some(index) = self.pages.iter().position(|page| page.path == old) else {
+      return Err(UnreactError::PageNotExist(old));
+    };
+    if self.pages.iter().any(|page| page.path == new) {
+      return Err(UnreactError::DuplicateOutput(
+        format!("{new}.html"),
+        format!("pages '{old}' and '{new}'"),
+      ));
+    }
+    self.check_path(&format!("{new}.html"))?;
+
+    let new = self.register_path(&new);
+    (self.page_casings.get_mut())
+      .unwrap_or_else(PoisonError::into_inner)
+      .remove(&old);
+    self.pages[index].path = new.to_string();
+    // Pages renamed before point to newest path
+    for path in self.renamed.values_mut() {
+      if *path == old {
+        *path = new.to_string();
+      }
+    }
+    self.renamed.remove(&new);
+    self.renamed.insert(old.to_string(), new);
+
+    Ok(self.references(&old))
+  }
+
+  /// Find sources which contain path of page, see `Unreact::rename_page`
+  fn references(&self, path: &str) -> Vec<String> {
+    let mut references = Vec::new();
+    let mut find = |section: &str, name: &str, text: &str| {
+      if links_to(text, path) {
+        references.push(format!("{section}/{name}"));
+      }
+    };
+
+    let sorted = |files: &FileMap| {
+      (files.iter())
+        .map(|(name, content)| (name.to_string(), content.to_string()))
+        .collect::<BTreeMap<_, _>>()
+    };
+    for (name, content) in sorted(&self.templates()) {
+      find("templates", &name, &content);
+    }
+    for (name, content) in sorted(&self.partials) {
+      find("partials", &name, &content);
+    }
+    for (name, value) in &self.data {
+      find("data", name, &value.to_string());
+    }
+    for (name, value) in &self.collections {
+      find("collections", name, &value.to_string());
+    }
+    for page in &self.pages {
+      match &page.source {
+        PageSource::Plain => find("pages", &page.path, &page.content),
+        PageSource::Template(_, data) => find("pages", &page.path, &data.to_string()),
+        PageSource::Markdown(content) => find("pages", &page.path, content),
+      }
+    }
+    references
+  }
+
+  /// Register an RSS or Atom feed, written to `path` in build directory
+  ///
+  /// Items are listed in order given, so sort them newest first. Links of items are paths of pages (such as `"posts/hello"`), or absolute urls
+  ///
+  /// Registering a feed with the same path replaces it
+  ///
+  /// Items which link to a page with `noindex` in page data are left out, and items link to `canonical` of page if set
+  ///
+  /// Returns error if date of any item is not valid, see `FeedItem::date`
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use unreact::prelude::*;
+  ///
+  /// fn main() -> UnreactResult<()> {
+  ///   let url = SiteUrl::parse("https://mysite.com")?;
+  ///   let mut app = Unreact::from_vfs(Config::default(), false, url, Vfs::new());
+  ///
+  ///   let posts = [("hello", "Hello world", "2024-01-31")];
+  ///   let items = posts
+  ///     .iter()
+  ///     .map(|(slug, title, date)| FeedItem {
+  ///       title: title.to_string(),
+  ///       link: format!("posts/{slug}"),
+  ///       date: date.to_string(),
+  ///       summary: None,
+  ///     })
+  ///     .collect::<Vec<_>>();
+  ///
+  ///   app.feed("feed.xml", FeedOptions {
+  ///     title: "My blog".to_string(),
+  ///     link: "posts".to_string(),
+  ///     ..FeedOptions::default()
+  ///   }, &items)?;
+  ///
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn feed(
+    &mut self,
+    path: &str,
+    options: FeedOptions,
+    items: &[FeedItem],
+  ) -> UnreactResult<&mut Self> {
+    let path = path.trim_start_matches('/');
+    self.check_path(path)?;
+    // Check dates now, so error is returned by this method
+    if let Err(reason) = create_feed(path, &options, items, &self.url) {
+      return Err(UnreactError::FeedFail(path.to_string(), reason));
+    }
+    self
+      .feeds
+      .insert(path.to_string(), (options, items.to_vec()));
+    Ok(self)
+  }
+
+  /// Set options of registered page in `sitemap.xml`, see `Config::sitemap`
+  ///
+  /// Returns error if no page is registered with path
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use unreact::prelude::*;
+  /// use serde_json::Value;
+  ///
+  /// fn main() -> UnreactResult<()> {
+  ///   let url = SiteUrl::parse("https://mysite.com")?;
+  ///   let mut app = Unreact::from_vfs(Config::default(), false, url, Vfs::new());
+  ///   app.page_plain("blog/index", "Latest posts");
+  ///
+  ///   app.set_sitemap("blog/index", SitemapOptions {
+  ///     priority: Some(0.8),
+  ///     changefreq: Some(ChangeFreq::Daily),
+  ///     ..SitemapOptions::default()
+  ///   })?;
+  ///   assert!(app.set_sitemap("missing", SitemapOptions::default()).is_err());
+  ///
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn set_sitemap(&mut self, path: &str, options: SitemapOptions) -> UnreactResult<&mut Self> {
+    let path = &self.page_path(path);
+    let Some(page) = self.pages.iter_mut().find(|page| page.path == *path) else {
+      return Err(UnreactError::PageNotExist(path.to_string()));
+    };
+    page.sitemap = options;
+    Ok(self)
+  }
+
+  /// Register a page for every Markdown file in a subdirectory of `content` directory, rendered with a template
+  ///
+  /// `./content/posts/hello.md` is registered as page `posts/hello`, when `dir` is `"posts"`. Use `""` for whole directory
+  ///
+  /// Front matter (YAML between `---` lines, or TOML between `+++` lines) is passed to template as data,
+  /// with converted html as `content`, path of page as `path`, and path of file as `source` (such as `content/posts/hello.md`).
+  /// If `repository` is set in config, url to edit file is `edit_url`, see `Config::edit_url`
+  ///
+  /// Front matter values are strings, numbers, booleans, or lists, and can be nested with YAML indentation or TOML tables
+  ///
+  /// Files with a `date` in front matter after the build date are left out, to publish them with a later build, see `Config::build_date`.
+  /// They are shown in dev mode, unless `build_date` is set
+  ///
+  /// # Examples
+  ///
+  /// With `./content/posts/hello.md`:
+  ///
+  /// ```md
+  /// ---
+  /// title: Hello world
+  /// tags: [rust, web]
+  /// ---
+  /// This is my *first* post
+  /// ```
+  ///
+  /// And `./templates/post.hbs`:
+  ///
+  /// ```hbs
+  /// <h1>{{title}}</h1>
+  /// {{{content}}}
+  /// {{#if edit_url}}<a href="{{edit_url}}">Edit this page</a>{{/if}}
+  /// ```
+  ///
+  /// ```no_run
+  /// use unreact::prelude::*;
+  ///
+  /// fn main() -> UnreactResult<()> {
+  ///   let mut app = Unreact::new(Config::default(), false, "https://mysite.com")?;
+  ///
+  ///   // Creates `./build/posts/hello.html`
+  ///   app.pages_from_content("posts", "post")?;
+  ///
+  ///   app.finish()?;
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn pages_from_content(&mut self, dir: &str, template: &str) -> UnreactResult<&mut Self> {
+    let content_dir = format!("{}/{dir}", self.config.content);
+    let content_dir = content_dir.trim_end_matches('/');
+    if !Path::new(&format!("./{content_dir}")).is_dir() {
+      return Err(UnreactError::DirNotExist(format!("./{content_dir}")));
+    }
+
+    let files = list_files(content_dir)?
+      .into_iter()
+      .filter(|file| file.ends_with(".md"))
+      .collect::<Vec<_>>();
+
+    // Render files in parallel, then register pages in order of files
+    let mut pages = vec![None; files.len()];
+    for_each_parallel(
+      &files,
+      self.threads(),
+      |file| self.render_content(content_dir, dir, file, template),
+      |i, result| {
+        pages[i] = Some(result?);
+        Ok(())
+      },
+    )?;
+
+    let pages = pages.into_iter().flatten().flatten().collect::<Vec<_>>();
+    for (path, _, _) in &pages {
+      self.check_path(&format!("{path}.html"))?;
+    }
+    for (path, html, data) in pages {
+      self.pages.push(Page::new(
+        &path,
+        &html,
+        PageSource::Template(template.to_string(), data),
+      ));
+    }
+
+    #[cfg(feature = "dev-server")]
+    (self.content_dirs).push((dir.to_string(), template.to_string()));
+    Ok(self)
+  }
+
+  /// Get date which site is built as of, as RFC 3339 in UTC, such as `2024-01-31T09:30:00Z`
+  ///
+  /// Uses `build_date` from config, or current time. Useful for a global of when the site was last updated
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use std::time::{Duration, SystemTime};
+  /// use unreact::prelude::*;
+  ///
+  /// fn main() -> UnreactResult<()> {
+  ///   let mut config = Config::default();
+  ///   config.build_date = Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1706693400));
+  ///
+  ///   let url = SiteUrl::parse("https://mysite.com")?;
+  ///   let mut app = Unreact::from_vfs(config, false, url, Vfs::new());
+  ///   assert_eq!(app.build_date(), "2024-01-31T09:30:00Z");
+  ///
+  ///   app.global("updated", app.build_date().into());
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn build_date(&self) -> String {
+    DateTime::from_timestamp(self.build_timestamp()).rfc3339()
+  }
+
+  /// Returns `true` if date is after date which site is built as of, see `Unreact::build_date`
+  ///
+  /// Date is `YYYY-MM-DD`, or RFC 3339 date and time. Dates which are not valid are never in the future
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use std::time::{Duration, SystemTime};
+  /// use unreact::prelude::*;
+  ///
+  /// fn main() -> UnreactResult<()> {
+  ///   let mut config = Config::default();
+  ///   config.build_date = Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1706693400));
+  ///
+  ///   let url = SiteUrl::parse("https://mysite.com")?;
+  ///   let app = Unreact::from_vfs(config, false, url, Vfs::new());
+  ///   assert!(app.is_future("2024-02-01"));
+  ///   assert!(!app.is_future("2024-01-31T09:00:00Z"));
+  ///   assert!(!app.is_future("next week"));
+  ///
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn is_future(&self, date: &str) -> bool {
+    DateTime::parse(date).is_ok_and(|date| date.timestamp() > self.build_timestamp())
+  }
+
+  /// Seconds since Unix epoch of date which site is built as of
+  fn build_timestamp(&self) -> i64 {
+    unix_seconds(self.config.build_date.unwrap_or_else(SystemTime::now))
+  }
+
+  /// Get seconds since Unix epoch of `date` in data of content, if in the future, so content is not built yet
+  ///
+  /// Future content is shown in dev mode, unless `build_date` is set in config
+  fn scheduled_date(&self, data: &Value) -> Option<i64> {
+    if self.is_dev && self.config.build_date.is_none() {
+      return None;
+    }
+    let date = DateTime::parse(data.get("date")?.as_str()?).ok()?;
+    Some(date.timestamp()).filter(|timestamp| *timestamp > self.build_timestamp())
+  }
+
+  /// Register a page for each item, with path, template, and data, the same as `Unreact::page`
+  ///
+  /// Unlike `Unreact::page`, items are rendered when registered. Every item is rendered, even after one fails. If any item fails, no pages are registered, and every failure is returned in `UnreactError::PagesFail`, with index and path of item.
+  /// Pages are rendered in parallel, with `threads` from config
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use unreact::prelude::*;
+  /// use serde_json::json;
+  ///
+  /// fn main() -> UnreactResult<()> {
+  ///   let mut vfs = Vfs::new();
+  ///   vfs.templates.insert("post".to_string(), "<h1>{{title}}</h1>".to_string());
+  ///
+  ///   let url = SiteUrl::parse("https://mysite.com")?;
+  ///   let mut app = Unreact::from_vfs(Config::default(), false, url, vfs);
+  ///
+  ///   let posts = vec![
+  ///     ("posts/hello", "post", json!({"title": "Hello"})),
+  ///     ("posts/world", "missing", json!({"title": "World"})),
+  ///     ("posts/aux", "post", json!({"title": "Reserved name"})),
+  ///   ];
+  ///   let Err(UnreactError::PagesFail(failures)) = app.pages_from_iter(posts) else {
+  ///     panic!("Expected failures");
+  ///   };
+  ///   assert_eq!(failures.len(), 2);
+  ///   assert_eq!((failures[0].0, failures[0].1.as_str()), (1, "posts/world"));
+  ///
+  ///   app.pages_from_iter([("posts/hello", "post", json!({"title": "Hello"}))])?;
+  ///   assert_eq!(app.compile()?["posts/hello.html"], "<h1>Hello</h1>");
+  ///
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn pages_from_iter<P, T>(
+    &mut self,
+    items: impl IntoIterator<Item = (P, T, Value)>,
+  ) -> UnreactResult<&mut Self>
+  where
+    P: AsRef<str> + Sync,
+    T: AsRef<str> + Sync,
+  {
+    let items = items.into_iter().collect::<Vec<_>>();
+
+    // Render items in parallel, then register pages in order of items
+    let mut pages = vec![Vec::new(); items.len()];
+    let mut failures = Vec::new();
+    for_each_parallel(
+      &items,
+      self.threads(),
+      |(path, template, data)| self.render_item(path.as_ref(), template.as_ref(), data),
+      |i, result| {
+        match result {
+          Ok(rendered) => pages[i] = rendered,
+          Err(err) => failures.push((i, items[i].0.as_ref().to_string(), err)),
+        }
+        Ok(())
+      },
+    )?;
+    if !failures.is_empty() {
+      failures.sort_by_key(|(i, _, _)| *i);
+      return Err(UnreactError::PagesFail(failures));
+    }
+
+    for ((_, template, _), pages) in items.iter().zip(pages) {
+      for (path, html, data) in pages {
+        self.pages.push(Page::new(
+          &path,
+          &html,
+          PageSource::Template(template.as_ref().to_string(), data),
+        ));
+      }
+    }
+    Ok(self)
+  }
+
+  /// Render item of `Unreact::pages_from_iter`
+  ///
+  /// Returns path of page, rendered html, and data given to template, for each locale
+  fn render_item(
+    &self,
+    path: &str,
+    template: &str,
+    data: &Value,
+  ) -> UnreactResult<Vec<(String, String, Value)>> {
+    let mut pages = Vec::new();
+    for (path, data) in self.locales.localize(&self.register_path(path), data) {
+      self.check_path(&format!("{path}.html"))?;
+      let html = self.or_error_page(&path, self.render_cached(template, &data))?;
+      pages.push((path, html, data));
+    }
+    Ok(pages)
+  }
+
+  /// Register a collection of items, such as blog posts
+  ///
+  /// Every template can use all collections, as `collections.<name>`. Register collections before pages which use them, as pages are rendered when registered
+  ///
+  /// Registering a collection with the same name replaces it, with its options. Use `Unreact::paginate` to create numbered index pages,
+  /// and `Unreact::collection_with` for a feed and sitemap of the collection
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use unreact::prelude::*;
+  /// use serde_json::{json, Value};
+  ///
+  /// fn main() -> UnreactResult<()> {
+  ///   let mut vfs = Vfs::new();
+  ///   vfs.templates.insert("latest".to_string(), "{{#each collections.posts}}{{title}} {{/each}}".to_string());
+  ///
+  ///   let url = SiteUrl::parse("https://mysite.com")?;
+  ///   let mut app = Unreact::from_vfs(Config::default(), false, url, vfs);
+  ///
+  ///   app.collection("posts", vec![json!({"title": "Hello"}), json!({"title": "World"})]);
+  ///   assert_eq!(app.render("latest", &Value::Null)?, "Hello World ");
+  ///
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn collection(&mut self, name: &str, items: Vec<Value>) -> &mut Self {
+    self
+      .collections
+      .insert(name.to_string(), Value::Array(items));
+    self.collection_options.remove(name);
+    self
+  }
+
+  /// Register a collection of items, the same as `Unreact::collection`, with a feed and sitemap in directory of collection, see `CollectionOptions`
+  ///
+  /// Returns error if an item of feed is not valid, see `CollectionOptions::feed`
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use unreact::prelude::*;
+  /// use serde_json::json;
+  ///
+  /// fn main() -> UnreactResult<()> {
+  ///   let config = Config {
+  ///     sitemap: true,
+  ///     ..Config::default()
+  ///   };
+  ///   let url = SiteUrl::parse("https://mysite.com")?;
+  ///   let mut app = Unreact::from_vfs(config, false, url, Vfs::new());
+  ///
+  ///   let posts = vec![json!({"title": "Hello", "path": "blog/hello", "date": "2024-01-31"})];
+  ///   app.collection_with("posts", posts, CollectionOptions {
+  ///     path: "blog".to_string(),
+  ///     feed: Some(FeedOptions {
+  ///       title: "My blog".to_string(),
+  ///       ..FeedOptions::default()
+  ///     }),
+  ///     sitemap: true,
+  ///   })?;
+  ///   app.page_plain("index", "Home");
+  ///   app.page_plain("blog/hello", "Hello");
+  ///
+  ///   let files = app.compile()?;
+  ///   assert!(files["blog/feed.xml"].contains("<link>https://mysite.com/blog/hello</link>"));
+  ///   assert!(files["blog/sitemap.xml"].contains("https://mysite.com/blog/hello"));
+  ///   assert!(!files["sitemap-pages.xml"].contains("https://mysite.com/blog/hello"));
+  ///   assert!(files["sitemap.xml"].contains("<loc>https://mysite.com/blog/sitemap.xml</loc>"));
+  ///
+  ///   // Items of feed need a date
+  ///   let notes = vec![json!({"title": "Note", "path": "notes/a"})];
+  ///   let options = CollectionOptions {
+  ///     path: "notes".to_string(),
+  ///     feed: Some(FeedOptions::default()),
+  ///     ..CollectionOptions::default()
+  ///   };
+  ///   assert!(matches!(app.collection_with("notes", notes, options), Err(UnreactError::FeedFail(..))));
+  ///
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn collection_with(
+    &mut self,
+    name: &str,
+    items: Vec<Value>,
+    options: CollectionOptions,
+  ) -> UnreactResult<&mut Self> {
+    let options = CollectionOptions {
+      path: self.page_path(options.dir()),
+      ..options
+    };
+    if let Some(feed) = options.feed_options() {
+      let path = options.file(COLLECTION_FEED_FILE);
+      self.check_path(&path)?;
+      // Check items now, so error is returned by this method
+      let result =
+        feed_items(name, &items).and_then(|items| create_feed(&path, &feed, &items, &self.url));
+      if let Err(reason) = result {
+        return Err(UnreactError::FeedFail(path, reason));
+      }
+    }
+    if options.has_sitemap() {
+      self.check_path(&options.file(COLLECTION_SITEMAP_FILE))?;
+    }
+
+    self.collection(name, items);
+    self.collection_options.insert(name.to_string(), options);
+    Ok(self)
+  }
+
+  /// Register numbered index pages of a collection, with `per_page` items on each page
+  ///
+  /// The first page is at `path`, and other pages are at `<path>/page/<number>`. Use `""` for root of site (first page is `index`)
+  ///
+  /// Template is rendered with `items` (items of page), `page` (number of page, from `1`), `page_count`,
+  /// and `prev` and `next` (paths of previous and next pages, for `LINK` partial, or null)
+  ///
+  /// `HTML_HEAD` partial adds a canonical link to each page itself (unless `canonical` is in data), and `rel="prev"` and `rel="next"` links, from `pagination` in data
+  ///
+  /// Returns error if collection is not registered with `Unreact::collection`
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use unreact::prelude::*;
+  /// use serde_json::json;
+  ///
+  /// fn main() -> UnreactResult<()> {
+  ///   let mut vfs = Vfs::new();
+  ///   vfs.templates.insert("blog".to_string(), "{{#each items}}{{this}} {{/each}}{{#if next}}{{#>LINK to=next}}Next{{/LINK}}{{/if}}".to_string());
+  ///   vfs.templates.insert("head".to_string(), "{{>HTML_HEAD}}".to_string());
+  ///
+  ///   let url = SiteUrl::parse("https://mysite.com")?;
+  ///   let mut app = Unreact::from_vfs(Config::default(), false, url, vfs);
+  ///
+  ///   app.collection("posts", vec![json!("a"), json!("b"), json!("c")]);
+  ///   app.paginate("posts", "blog", "blog", 2)?;
+  ///
+  ///   assert!(app.has_page("blog"));
+  ///   assert!(app.has_page("blog/page/2"));
+  ///   assert!(!app.has_page("blog/page/3"));
+  ///
+  ///   // Canonical, previous, and next links of page 2
+  ///   let html = app.render("head", &json!({"pagination": {"canonical": "/blog/page/2", "prev": "/blog"}}))?;
+  ///   assert!(html.contains(r#"<link rel="canonical" href="https://mysite.com/blog/page/2" />"#));
+  ///   assert!(html.contains(r#"<link rel="prev" href="https://mysite.com/blog" />"#));
+  ///
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn paginate(
+    &mut self,
+    collection: &str,
+    path: &str,
+    template: &str,
+    per_page: usize,
+  ) -> UnreactResult<&mut Self> {
+    let Some(Value::Array(items)) = self.collections.get(collection) else {
+      return Err(UnreactError::CollectionNotExist(collection.to_string()));
+    };
+
+    let path = path.trim_matches('/');
+    let page_path = |number: usize| match (number, path) {
+      (1, "") => "index".to_string(),
+      (1, path) => path.to_string(),
+      (number, "") => format!("page/{number}"),
+      (number, path) => format!("{path}/page/{number}"),
+    };
+
+    // Empty collection still has a first page
+    let chunks = items
+      .chunks(per_page.max(1))
+      .map(<[Value]>::to_vec)
+      .collect::<Vec<_>>();
+    let page_count = chunks.len().max(1);
+
+    // Url path of page, for links in `HTML_HEAD`
+    let link = |number: usize| format!("/{}", url_path(&page_path(number)).unwrap_or_default());
+
+    for number in 1..=page_count {
+      let data = json!({
+        "items": chunks.get(number - 1).cloned().unwrap_or_default(),
+        "page": number,
+        "page_count": page_count,
+        "prev": (number > 1).then(|| page_path(number - 1)),
+        "next": (number < page_count).then(|| page_path(number + 1)),
+        "pagination": {
+          "canonical": link(number),
+          "prev": (number > 1).then(|| link(number - 1)),
+          "next": (number < page_count).then(|| link(number + 1)),
+        },
+      });
+      self.page(&page_path(number), template, &data)?;
+    }
+
+    Ok(self)
+  }
+
+  /// Read and render Markdown file in content directory, for `Unreact::pages_from_content`
+  ///
+  /// Returns path of page, rendered html, and data given to template, for each locale
+  fn render_content(
+    &self,
+    content_dir: &str,
+    dir: &str,
+    file: &str,
+    template: &str,
+  ) -> UnreactResult<Vec<(String, String, Value)>> {
+    let name = file.strip_suffix(".md").unwrap_or(file);
+
+    // Read file
+    let file_path = format!("./{content_dir}/{file}");
+    let source = match fs::read_to_string(&file_path) {
+      Ok(x) => x,
+      Err(err) => return Err(UnreactError::IoError(err, file_path)),
+    };
+
+    let (mut data, body) = parse_front_matter(&source)
+      .map_err(|reason| UnreactError::FrontMatterFail(file_path.to_string(), reason))?;
+
+    let path = self.register_path(&if dir.is_empty() {
+      name.to_string()
+    } else {
+      format!("{}/{name}", dir.trim_matches('/'))
+    });
+
+    // Scheduled content is published by a later build
+    if let Some(timestamp) = self.scheduled_date(&data) {
+      (self
+        .scheduled
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner))
+      .insert(path, timestamp);
+      return Ok(Vec::new());
+    }
+
+    // Converted content and path are given to template, with front matter
+    if let Value::Object(map) = &mut data {
+      map.insert(
+        "content".to_string(),
+        Value::String(self.render_markdown(body)?),
+      );
+      map.insert("path".to_string(), Value::String(path.to_string()));
+      // Source file, for links to edit page
+      let source = format!("{content_dir}/{file}");
+      if let Some(url) = self.config.edit_url(&source) {
+        map.insert("edit_url".to_string(), Value::String(url));
+      }
+      map.insert("source".to_string(), Value::String(source));
+    }
+
+    let mut pages = Vec::new();
+    for (path, data) in self.locales.localize(&path, &data) {
+      let html = self.or_error_page(&path, self.render_cached(template, &data))?;
+      pages.push((path, html, data));
+    }
+    Ok(pages)
+  }
+
+  /// Create every output file in memory, without writing to build directory
+  ///
+  /// Keys are paths relative to build directory, **with** extension, such as `"index.html"` or `"styles/main.css"`.
+  /// Values are minified or formatted pages, compiled styles, feeds, and sitemap
+  ///
+  /// Public files are not read, so values of `public/...` keys are paths of source files, relative to workspace.
+  /// A missing public directory is treated as empty
+  ///
+  /// Use with `Unreact::from_vfs` to test a site without any directories. Manifest and dev stats page are not included
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use unreact::prelude::*;
+  /// use serde_json::json;
+  ///
+  /// fn main() -> UnreactResult<()> {
+  ///   let mut vfs = Vfs::new();
+  ///   vfs.templates.insert("index".to_string(), "<h1>{{msg}}</h1>".to_string());
+  ///   vfs.styles.insert("main".to_string(), "a { b { color: red; } }".to_string());
+  ///
+  ///   let url = SiteUrl::parse("https://mysite.com")?;
+  ///   let mut app = Unreact::from_vfs(Config::default(), false, url, vfs);
+  ///   app.index("index", &json!({"msg": "Hello!"}))?;
+  ///
+  ///   let files = app.compile()?;
+  ///   assert_eq!(files["index.html"], "<h1>Hello!</h1>");
+  ///   assert_eq!(files["styles/main.css"], "a b{color:red}");
+  ///
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn compile(&self) -> UnreactResult<FileMap> {
+    let mut ignore = |_: BuildProgress| ControlFlow::Continue(());
+    let mut progress = ProgressTracker::new(&mut ignore, 0);
+    let outputs = self.compile_outputs(&mut progress)?;
+
+    Ok(
+      outputs
+        .into_iter()
+        .map(|(file, output)| match output {
+          Output::Content(content) => {
+            let content = self.hooks.before_write(&file, &content);
+            (file, content)
+          }
+          Output::Copy(source) => (file, source),
+        })
+        .collect(),
+    )
+  }
+
+  /// Create all files in production mode
+  ///
+  /// Writes the same files as `Unreact::compile` to build directory, with manifest
+  ///
+  /// # Examples
+  ///
+  /// Compiles to `./build`, in production mode
+  ///
+  /// ```no_run
+  /// use unreact::prelude::*;
+  ///
+  /// fn main() -> UnreactResult<()> {
+  ///   // Note that argument for `is_dev` is `false`
+  ///   let mut app = Unreact::new(Config::default(), false, "https://mysite.com")?;
+  ///
+  ///   app.page_plain("index", "This is my site, in production")
+  ///     .finish()?;
+  ///   Ok(())
+  /// }
+  /// ```
+  ///
+  /// Compiles to a directory in `./target/unreact-dev`, in development mode, and host to `http://127.0.0.1:8080`
+  ///
+  /// ```no_run
+  /// use unreact::prelude::*;
+  ///
+  /// fn main() -> UnreactResult<()> {
+  ///   // Note that argument for `is_dev` is `true`
+  ///   let mut app = Unreact::new(Config::default(), true, "https://mysite.com")?;
+  ///
+  ///   app.page_plain("index", "This is my site, in development")
+  ///     .finish()?;
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn finish(&mut self) -> UnreactResult<&mut Self> {
+    self.finish_with_progress(|_| ControlFlow::Continue(()))
+  }
+
+  /// Create all files, then rebuild when templates, styles, or public files change, in development mode
+  ///
+  /// Local server is hosted to `http://127.0.0.1:8080` (or next free port, see `Config::dev_port`), and keeps running between rebuilds
+  ///
+  /// Only pages which use a changed template (directly or as a partial) are rendered again, using the same data.
+  /// Pages of changed files in content directory are read and rendered again, see `Unreact::pages_from_content`.
+  /// Changes to Rust code still require a restart
+  ///
+  /// Open pages reload only if they were rendered again, or if a style or public file changed
+  ///
+  /// Same as `Unreact::finish` in production mode
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// use unreact::prelude::*;
+  ///
+  /// fn main() -> UnreactResult<()> {
+  ///   let mut app = Unreact::new(Config::default(), is_dev(), "https://mysite.com")?;
+  ///
+  ///   app.page_plain("index", "This is my site")
+  ///     .finish_watch()?;
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn finish_watch(&mut self) -> UnreactResult<&mut Self> {
+    #[cfg(feature = "dev-server")]
+    if self.is_dev {
+      self.build()?;
+      self.watch()?;
+    }
+
+    self.finish()
+  }
+
+  /// Create all files in production mode, reporting progress to `callback` after each step
+  ///
+  /// Build stops with `UnreactError::BuildAborted` if `callback` returns `ControlFlow::Break`.
+  /// Files already written are in a staging directory, which is removed, so the previous build directory is kept as it was.
+  /// With `clean` set in config, build directory is already emptied, and files already written are left in it
+  ///
+  /// Same as `Unreact::finish` otherwise, including local server in dev mode
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// use std::ops::ControlFlow;
+  /// use unreact::prelude::*;
+  ///
+  /// fn main() -> UnreactResult<()> {
+  ///   let mut app = Unreact::new(Config::default(), false, "https://mysite.com")?;
+  ///
+  ///   app.page_plain("index", "This is my site")
+  ///     .finish_with_progress(|progress| {
+  ///       println!("{:.0}% - {}", progress.percent(), progress.path);
+  ///       // Return `ControlFlow::Break(())` to cancel
+  ///       ControlFlow::Continue(())
+  ///     })?;
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn finish_with_progress(
+    &mut self,
+    mut callback: impl FnMut(BuildProgress) -> ControlFlow<()>,
+  ) -> UnreactResult<&mut Self> {
+    self.build_with_progress(&mut callback)?;
+
+    // Open local server if in dev mode
+    #[cfg(feature = "dev-server")]
+    if self.is_dev {
+      self.listen()?;
+    }
+
+    Ok(self)
+  }
+
+  /// Create all files, without opening local server in dev mode
+  ///
+  /// Documents open in browsers connected to a server of `Unreact::listen_with` are reloaded, in dev mode.
+  /// Useful for a custom watch setup, or to build inside an async application, see `Unreact::reload`
+  ///
+  /// Same as `Unreact::finish` in production mode
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// use unreact::prelude::*;
+  ///
+  /// fn main() -> UnreactResult<()> {
+  ///   let mut app = Unreact::new(Config::default(), true, "https://mysite.com")?;
+  ///   app.page_plain("index", "This is my site")
+  ///     .finish_nonblocking()?;
+  ///
+  ///   let server = app.listen_with("127.0.0.1:0")?;
+  ///   println!("Serving at {}", server.url());
+  ///
+  ///   // Rebuild after changes, reloading browsers
+  ///   app.reload()?.finish_nonblocking()?;
+  ///
+  ///   server.shutdown();
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn finish_nonblocking(&mut self) -> UnreactResult<&mut Self> {
+    self.build_with_progress(&mut |_| ControlFlow::Continue(()))?;
+
+    #[cfg(feature = "dev-server")]
+    if self.is_dev {
+      self.on_rebuild(None);
+    }
+
+    Ok(self)
+  }
+
+  /// Open local server in background at `addr`, serving development build directory, and return without blocking
+  ///
+  /// Server is spawned on current Tokio runtime, if called from within one, otherwise on a new runtime in its own thread.
+  /// Server is stopped with `ServerHandle::shutdown`, or when handle is dropped
+  ///
+  /// Use port `0` to let OS choose a free port, such as for tests, and get address with `ServerHandle::addr`
+  ///
+  /// Templates are not rendered for `RENDER_PATH`, as app is not listening for requests.
+  /// Build with `Unreact::finish_nonblocking`, which reloads connected browsers
+  ///
+  /// Returns `UnreactError::ServerFail` if address is not valid, or cannot be bound
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// use unreact::prelude::*;
+  ///
+  /// fn main() -> UnreactResult<()> {
+  ///   let mut app = Unreact::new(Config::default(), true, "https://mysite.com")?;
+  ///   app.page_plain("index", "This is my site")
+  ///     .finish_nonblocking()?;
+  ///
+  ///   let server = app.listen_with("127.0.0.1:0")?;
+  ///   let port = server.addr().port();
+  ///   println!("Port {port}");
+  ///   server.shutdown();
+  ///   Ok(())
+  /// }
+  /// ```
+  #[cfg(feature = "dev-server")]
+  pub fn listen_with(&self, addr: &str) -> UnreactResult<ServerHandle> {
+    // App does not receive events, so render requests respond as not running
+    let (events, _) = std::sync::mpsc::channel();
+    let runtime = tokio::runtime::Handle::try_current().ok();
+    server::listen_with(self.dev_server(events), addr, runtime)
+  }
+
+  /// Create all files in build directory, without reporting progress
+  #[cfg(feature = "dev-server")]
+  fn build(&mut self) -> UnreactResult<()> {
+    self.build_with_progress(&mut |_| ControlFlow::Continue(()))
+  }
+
+  /// Create all files in build directory, reporting progress after each step
+  fn build_with_progress(
+    &mut self,
+    callback: &mut dyn FnMut(BuildProgress) -> ControlFlow<()>,
+  ) -> UnreactResult<()> {
+    self.subscribers.send(|| BuildEvent::BuildStarted {
+      is_dev: self.is_dev,
+    });
+    let result = self.build_outputs(callback);
+    self.send_failure(result)
+  }
+
+  /// Create report of build, with statistics of cache and profile, if enabled
+  fn build_report(&self, files: Vec<String>) -> BuildReport {
+    BuildReport::new(
+      self.is_dev,
+      files,
+      self.cache_stats(),
+      self.config.profile.then(|| self.profile()),
+    )
+  }
+
+  /// Send `BuildEvent::BuildFailed` if build failed
+  fn send_failure<T>(&self, result: UnreactResult<T>) -> UnreactResult<T> {
+    if let Err(err) = &result {
+      self.subscribers.send(|| BuildEvent::BuildFailed {
+        error: err.to_string(),
+      });
+    }
+    result
+  }
+
+  /// Create all files in build directory, for `Unreact::build_with_progress`
+  fn build_outputs(
+    &mut self,
+    callback: &mut dyn FnMut(BuildProgress) -> ControlFlow<()>,
+  ) -> UnreactResult<()> {
+    // Every page and style, then public files and manifest
+    let total = self.pages.len() + self.styles.len() + 2;
+    let mut progress = ProgressTracker::new(callback, total);
+    // Failures of previous build, if it was aborted
+    self.take_write_failures().ok();
+    let lockfile = self.check_lockfile()?;
+    // Pages registered since last build
+    let unrendered = (0..self.pages.len())
+      .filter(|i| !self.pages[*i].rendered)
+      .collect::<Vec<_>>();
+    self.rerender_pages(&unrendered)?;
+
+    let outputs = self.compile_outputs(&mut progress)?;
+    let files = outputs.keys().cloned().collect();
+    self.write_all(outputs, &mut progress)?;
+    self.write_lockfile(lockfile)?;
+
+    // Remove cache entries of old builds
+    if let Some(cache) = &self.cache {
+      cache.remove_unused()?;
+    }
+    let report = self.build_report(files);
+
+    for plugin in &self.plugins {
+      plugin.report(&report);
+    }
+    self
+      .subscribers
+      .send(|| BuildEvent::BuildFinished { report });
+    Ok(())
+  }
+
+  /// Write output files to build directory, or to sink if set, and to directory of each target
+  fn write_all(&mut self, outputs: Outputs, progress: &mut ProgressTracker) -> UnreactResult<()> {
+    let targets = self.target_outputs(&outputs)?;
+
+    // Custom storage, instead of build directory
+    if let Some(sink) = self.sink.clone().filter(|_| !self.is_dev) {
+      self.write_sink(&*sink, &self.config.build, outputs)?;
+      progress.step(BuildStage::Public, "")?;
+      progress.step(BuildStage::Manifest, "")?;
+      for (build, outputs) in targets {
+        self.write_sink(&*sink, &build, outputs)?;
+      }
+      return Ok(());
+    }
+
+    let build = self.config.build.clone();
+    self.write_dir(&build, |app, dir| app.write_build(dir, outputs, progress))?;
+    for (build, outputs) in targets {
+      self.write_dir(&build, |app, dir| app.write_target(dir, outputs))?;
+    }
+    Ok(())
+  }
+
+  /// Get lockfile of current inputs, if `lockfile` is set in config, in production only
+  ///
+  /// Returns error if inputs are different to lockfile, with `locked` set in config
+  fn check_lockfile(&self) -> UnreactResult<Option<Lockfile>> {
+    let Some(path) = self.config.lockfile.as_ref().filter(|_| !self.is_dev) else {
+      return Ok(None);
+    };
+    if self.config.locked {
+      self.verify_lockfile(path)?;
+      return Ok(None);
+    }
+    self.lockfile().map(Some)
+  }
+
+  /// Write lockfile of inputs, after build is written, see `check_lockfile`
+  fn write_lockfile(&self, lockfile: Option<Lockfile>) -> UnreactResult<()> {
+    match (lockfile, &self.config.lockfile) {
+      (Some(lockfile), Some(path)) => lockfile.write(&format!("./{path}")),
+      _ => Ok(()),
+    }
+  }
+
+  /// Get hashes of every input of build, see `Lockfile`
+  ///
+  /// Content, locales, and public files are read from their directories in config
+  pub fn lockfile(&self) -> UnreactResult<Lockfile> {
+    let hash_map = |files: &FileMap| {
+      (files.iter())
+        .map(|(name, content)| (name.to_string(), hash_content(content.as_bytes())))
+        .collect()
+    };
+    let mut styles: BTreeMap<_, _> = hash_map(&self.styles);
+    styles.extend(hash_map(&self.style_partials));
+
+    Ok(Lockfile {
+      templates: hash_map(&self.templates()),
+      partials: hash_map(&self.partials),
+      styles,
+      data: (self.data.iter())
+        .map(|(name, value)| (name.to_string(), hash_content(value.to_string().as_bytes())))
+        .collect(),
+      content: hash_dir(&self.config.content)?,
+      locales: hash_dir(&self.config.locales)?,
+      public: hash_dir(&self.config.public)?,
+    })
+  }
+
+  /// Check that inputs of build are identical to a lockfile of an earlier build
+  ///
+  /// Returns `UnreactError::LockfileMismatch` with every input which was added, changed, or removed
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// use unreact::prelude::*;
+  ///
+  /// fn main() -> UnreactResult<()> {
+  ///   let app = Unreact::new(Config::default(), false, "https://mysite.com")?;
+  ///
+  ///   // Lockfile written by an earlier build, with `lockfile` set in config
+  ///   app.verify_lockfile("unreact.lock")?;
+  ///
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn verify_lockfile(&self, path: &str) -> UnreactResult<()> {
+    let inputs = self.lockfile()?.diff(&Lockfile::read(path)?);
+    if !inputs.is_empty() {
+      return Err(UnreactError::LockfileMismatch(path.to_string(), inputs));
+    }
+    Ok(())
+  }
+
+  /// Get output files for each of `targets` in config, with url of target instead of url of site
+  fn target_outputs(&self, outputs: &Outputs) -> UnreactResult<Vec<(String, Outputs)>> {
+    if self.is_dev {
+      return Ok(Vec::new());
+    }
+
+    let mut targets = Vec::new();
+    for (url, build) in &self.config.targets {
+      let url = SiteUrl::parse(url)?;
+      let outputs = outputs
+        .iter()
+        .map(|(path, output)| {
+          let output = match output {
+            Output::Content(content) => Output::Content(self.url.replace_in(content, &url)),
+            output => output.clone(),
+          };
+          (path.to_string(), output)
+        })
+        .collect();
+      targets.push((build.to_string(), outputs));
+    }
+    Ok(targets)
+  }
+
+  /// Write build to directory with `write`
+  ///
+  /// Writes to a staging directory, which replaces the build directory only if every file is written, unless `clean` is set in config
+  fn write_dir(
+    &mut self,
+    build: &str,
+    write: impl FnOnce(&mut Self, &str) -> UnreactResult<()>,
+  ) -> UnreactResult<()> {
+    if self.config.clean {
+      // Build directory of site is already empty
+      if build != self.config.build {
+        create_build_dir(build)?;
+      }
+      return write(self, build);
+    }
+
+    let staging = staging_dir(build);
+    create_build_dir(&staging)?;
+    match write(self, &staging) {
+      Ok(()) => replace_dir(&staging, build),
+      Err(err) => {
+        fs::remove_dir_all(format!("./{staging}")).ok();
+        Err(err)
+      }
+    }
+  }
+
+  /// Write output files, manifest, and dev stats page to directory
+  fn write_build(
+    &mut self,
+    dir: &str,
+    outputs: Outputs,
+    progress: &mut ProgressTracker,
+  ) -> UnreactResult<()> {
+    self.write_outputs(dir, outputs)?;
+    progress.step(BuildStage::Public, "")?;
+    self.write_manifest(dir)?;
+    self.write_build_manifest(dir)?;
+    self.write_publish_schedule()?;
+    self.write_partial_docs()?;
+    #[cfg(feature = "dev-server")]
+    self.write_stats_page(dir)?;
+    progress.step(BuildStage::Manifest, "")?;
+
+    self.take_write_failures()
+  }
+
+  /// Write output files and manifest of another target to directory, see `Config::targets`
+  fn write_target(&mut self, dir: &str, outputs: Outputs) -> UnreactResult<()> {
+    self.write_outputs(dir, outputs)?;
+    if self.config.manifest {
+      Manifest::from_dir(dir)?.write(&format!("./{dir}/{MANIFEST_FILE}"))?;
+    }
+    self.take_write_failures()
+  }
+
+  /// Normalize path of page, with `normalize_paths` and `lowercase_paths` from config
+  fn page_path(&self, path: &str) -> String {
+    let path = normalize_path(path, self.config.normalize_paths);
+    if self.config.lowercase_paths {
+      path.to_lowercase()
+    } else {
+      path
+    }
+  }
+
+  /// Normalize path of page which is registered, saving its casing with `lowercase_paths` set in config
+  fn register_path(&self, path: &str) -> String {
+    let path = normalize_path(path, self.config.normalize_paths);
+    if !self.config.lowercase_paths {
+      return path;
+    }
+    let lower = path.to_lowercase();
+    (self
+      .page_casings
+      .lock()
+      .unwrap_or_else(PoisonError::into_inner))
+    .entry(lower.clone())
+    .or_default()
+    .insert(path);
+    lower
+  }
+
+  /// Check that no pages differ only by casing, with `lowercase_paths` set in config
+  fn check_casings(&self) -> UnreactResult<()> {
+    let casings = self
+      .page_casings
+      .lock()
+      .unwrap_or_else(PoisonError::into_inner);
+    for (path, casings) in casings.iter() {
+      let mut casings = casings.iter();
+      if let (Some(first), Some(second)) = (casings.next(), casings.next()) {
+        return Err(UnreactError::DuplicateOutput(
+          format!("{path}.html"),
+          format!("pages '{first}' and '{second}'"),
+        ));
+      }
+    }
+    Ok(())
+  }
+
+  /// Check that output path can be used on every OS, if `check_paths` is set in config
+  ///
+  /// `file`: Path relative to build directory, **with** extension
+  fn check_path(&self, file: &str) -> UnreactResult<()> {
+    if !self.config.check_paths {
+      return Ok(());
+    }
+    check_path(file).map_err(|reason| UnreactError::InvalidPath(file.to_string(), reason))
+  }
+
+  /// Ignore write error if `skip_write_errors` is set in config, saving it to return after build
+  fn skip_write_error(&self, result: UnreactResult<()>) -> UnreactResult<()> {
+    match result {
+      Err(UnreactError::IoError(err, path)) if self.config.skip_write_errors => {
+        self
+          .write_failures
+          .lock()
+          .unwrap_or_else(PoisonError::into_inner)
+          .push((path, err));
+        Ok(())
+      }
+      result => result,
+    }
+  }
+
+  /// Returns error with every file which failed to write since last call, if any
+  fn take_write_failures(&self) -> UnreactResult<()> {
+    let failures = std::mem::take(
+      &mut *self
+        .write_failures
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner),
+    );
+    if failures.is_empty() {
+      Ok(())
+    } else {
+      Err(UnreactError::WriteFail(failures))
+    }
+  }
+
+  /// Compile every output file in memory, reporting progress after each page and style
+  fn compile_outputs(&self, progress: &mut ProgressTracker) -> UnreactResult<Outputs> {
+    // Fragments of `cache` helper can depend on anything which changed since last build
+    self.fragments.clear();
+
+    // Plain pages are not checked when registered
+    for page in &self.pages {
+      self.check_path(&format!("{}.html", page.path))?;
+    }
+    self.check_casings()?;
+
+    let mut outputs = Outputs::new();
+    self.compile_pages(&mut outputs, |_| true, progress)?;
+    // Old paths of renamed pages, unless a page was registered there again
+    for (old, new) in &self.renamed {
+      (outputs.entry(format!("{old}.html")))
+        .or_insert_with(|| Output::Content(redirect_page(&self.base_url(), new)));
+    }
+    self.compile_styles(&mut outputs, progress)?;
+    self.compile_public(&mut outputs)?;
+    self.compile_derived(&mut outputs)?;
+
+    self.check_links(&outputs)?;
+    self.audit_assets(&outputs);
+    Ok(outputs)
+  }
+
+  /// Add files which are created from registered pages and collections: feeds, sitemaps, and files for host
+  ///
+  /// Created again by every rebuild in dev mode, as pages can be added or removed
+  fn compile_derived(&self, outputs: &mut Outputs) -> UnreactResult<()> {
+    // Feeds registered with `Unreact::feed` replace feeds of collections
+    for (file, (options, items)) in self.collection_feeds()?.iter().chain(&self.feeds) {
+      let content = create_feed(file, options, &self.feed_items(items), &self.url)
+        .map_err(|reason| UnreactError::FeedFail(file.to_string(), reason))?;
+      outputs.insert(file.to_string(), Output::Content(content));
+    }
+    if self.config.sitemap {
+      self.compile_sitemaps(outputs);
+      outputs.insert(ROBOTS_FILE.to_string(), Output::Content(robots(&self.url)));
+    }
+    if let Some(cname) = &self.config.cname {
+      outputs.insert(
+        CNAME_FILE.to_string(),
+        Output::Content(format!("{cname}\n")),
+      );
+    }
+    // Jekyll would leave out files starting with `_`
+    if self.config.host == Host::GitHubPages {
+      outputs.insert(NOJEKYLL_FILE.to_string(), Output::Content(String::new()));
+    }
+    Ok(())
+  }
+
+  /// Get feed of each collection with a feed, by path of feed
+  fn collection_feeds(&self) -> UnreactResult<BTreeMap<String, (FeedOptions, Vec<FeedItem>)>> {
+    let mut feeds = BTreeMap::new();
+    for (name, options) in &self.collection_options {
+      let Some(feed) = options.feed_options() else {
+        continue;
+      };
+      let path = options.file(COLLECTION_FEED_FILE);
+      let items = match self.collections.get(name) {
+        Some(Value::Array(items)) => items.as_slice(),
+        _ => &[],
+      };
+      let items =
+        feed_items(name, items).map_err(|reason| UnreactError::FeedFail(path.clone(), reason))?;
+      feeds.insert(path, (feed, items));
+    }
+    Ok(feeds)
+  }
+
+  /// Add `sitemap.xml` to outputs, or a sitemap index with a sitemap for each collection with `sitemap` in options
+  fn compile_sitemaps(&self, outputs: &mut Outputs) {
+    let sections = (self.collection_options.values())
+      .filter(|options| options.has_sitemap())
+      .map(|options| options.dir())
+      .collect::<BTreeSet<_>>();
+    if sections.is_empty() {
+      outputs.insert(
+        SITEMAP_FILE.to_string(),
+        Output::Content(sitemap(&self.pages, &self.url)),
+      );
+      return;
+    }
+
+    // Directory of collection which page belongs to, if any
+    let section = |page: &Page| {
+      (sections.iter())
+        .filter(|dir| page.path.starts_with(&format!("{dir}/")))
+        // Nested collection, such as `blog/notes` in `blog`
+        .max_by_key(|dir| dir.len())
+        .copied()
+    };
+    let mut files = vec![PAGES_SITEMAP_FILE.to_string()];
+    outputs.insert(
+      PAGES_SITEMAP_FILE.to_string(),
+      Output::Content(sitemap(
+        self.pages.iter().filter(|page| section(page).is_none()),
+        &self.url,
+      )),
+    );
+    for dir in &sections {
+      let file = format!("{dir}/{COLLECTION_SITEMAP_FILE}");
+      let pages = self.pages.iter().filter(|page| section(page) == Some(dir));
+      outputs.insert(file.clone(), Output::Content(sitemap(pages, &self.url)));
+      files.push(file);
+    }
+    outputs.insert(
+      SITEMAP_FILE.to_string(),
+      Output::Content(sitemap_index(&files, &self.url)),
+    );
+  }
+
+  /// Check internal links of every page, with `check_links` from config
+  ///
+  /// Prints broken links with `LinkCheck::Warn`, and returns `UnreactError::BrokenLinks` with `LinkCheck::Fail`
+  fn check_links(&self, outputs: &Outputs) -> UnreactResult<()> {
+    if self.config.check_links == LinkCheck::Off {
+      return Ok(());
+    }
+    let base_url = self.base_url();
+
+    let mut broken = Vec::new();
+    for (file, output) in outputs {
+      let Output::Content(html) = output else {
+        continue;
+      };
+      if !file.ends_with(".html") {
+        continue;
+      }
+      for link in broken_links(html, file, outputs, &base_url, self.url.base_path()) {
+        broken.push((file.to_string(), link.to_string()));
+      }
+    }
+    if broken.is_empty() {
+      return Ok(());
+    }
+
+    if self.config.check_links == LinkCheck::Fail {
+      return Err(UnreactError::BrokenLinks(broken));
+    }
+    for (page, link) in broken {
+      self.warn(format!("Broken link '{link}' in '{page}'"));
+    }
+    Ok(())
+  }
+
+  /// Check images and assets of build, with `audit` from config, and print warnings
+  ///
+  /// Warnings replace those of previous build, see `Unreact::asset_warnings`
+  fn audit_assets(&self, outputs: &Outputs) {
+    let options = &self.config.audit;
+    let mut images = Vec::new();
+    let mut pages = Vec::new();
+    if options.is_enabled() {
+      for (file, output) in outputs {
+        match output {
+          Output::Copy(source) => images.push((file.as_str(), source.as_str())),
+          Output::Content(html) if file.ends_with(".html") => {
+            pages.push((file.as_str(), html.as_str()))
+          }
+          Output::Content(_) => (),
+        }
+      }
+    }
+
+    let warnings = audit(
+      options,
+      &images,
+      &pages,
+      outputs,
+      &self.base_url(),
+      self.url.base_path(),
+    );
+    for warning in &warnings {
+      self.warn(warning.to_string());
+    }
+    *self
+      .asset_warnings
+      .lock()
+      .unwrap_or_else(PoisonError::into_inner) = warnings;
+  }
+
+  /// Print warning, and send it to subscribers, see `Unreact::subscribe`
+  fn warn(&self, message: String) {
+    eprintln!("Warning: {message}");
+    self
+      .subscribers
+      .send(|| BuildEvent::WarningEmitted { message });
+  }
+
+  /// Get warnings of images and assets from last build, with `audit` set in config, see `AuditOptions`
+  ///
+  /// Warnings are also printed during build
+  pub fn asset_warnings(&self) -> Vec<AssetWarning> {
+    (self
+      .asset_warnings
+      .lock()
+      .unwrap_or_else(PoisonError::into_inner))
+    .clone()
+  }
+
+  /// Get items of feed, without items of `noindex` pages, and with links to canonical urls
+  fn feed_items(&self, items: &[FeedItem]) -> Vec<FeedItem> {
+    let mut listed = Vec::new();
+    for item in items {
+      let link = item.link.trim_matches('/');
+      let page = self.pages.iter().find(|page| {
+        page.path == link
+          || url_path(&page.path).is_some_and(|path| path.trim_end_matches('/') == link)
+      });
+      match page {
+        Some(page) if page.noindex() => continue,
+        Some(page) => listed.push(FeedItem {
+          link: page.canonical().unwrap_or(&item.link).to_string(),
+          ..item.clone()
+        }),
+        None => listed.push(item.clone()),
+      }
+    }
+    listed
+  }
+
+  /// Minify or format registered pages which match `filter`
+  fn compile_pages(
+    &self,
+    outputs: &mut Outputs,
+    filter: impl Fn(&Page) -> bool,
+    progress: &mut ProgressTracker,
+  ) -> UnreactResult<()> {
+    let pages = self
+      .pages
+      .iter()
+      .filter(|page| filter(page))
+      .collect::<Vec<_>>();
+
+    for_each_parallel(
+      &pages,
+      self.threads(),
+      |page| {
+        // Only if not built yet, such as by `Unreact::compile`
+        let content = match page.rendered {
+          true => None,
+          false => self.render_page(page)?,
+        };
+        let content = content.as_ref().unwrap_or(&page.content);
+        let html = self.hooks.page_rendered(&page.path, content);
+        let html = rename_links(
+          &html,
+          &format!("{}.html", page.path),
+          &self.renamed,
+          &self.base_url(),
+          self.url.base_path(),
+        );
+        if self.config.relative_links {
+          let file = format!("{}.html", page.path);
+          self.output_cached(&relative_links(&html, &file, &self.base_url()))
+        } else {
+          self.output_cached(&html)
+        }
+      },
+      |i, result| {
+        let file = format!("{}.html", pages[i].path);
+        let content = result?;
+        progress.step(BuildStage::Pages, &file)?;
+        outputs.insert(file, Output::Content(content));
+        Ok(())
+      },
+    )
+  }
+
+  /// Compile every style from scss to css
+  fn compile_styles(
+    &self,
+    outputs: &mut Outputs,
+    progress: &mut ProgressTracker,
+  ) -> UnreactResult<()> {
+    let mut style_paths = HashMap::new();
+    let mut styles = Vec::new();
+    let assets = self.read_assets();
+    for name in self.styles.keys() {
+      if self.config.inline_only.contains(name) {
+        continue;
+      }
+      let path = assets.style_path(&self.config, name);
+
+      // Check that no other style is written to the same file
+      if let Some(other) = style_paths.insert(path.to_string(), name) {
+        return Err(UnreactError::DuplicateOutput(
+          format!("{path}.css"),
+          format!("styles '{other}' and '{name}'"),
+        ));
+      }
+
+      styles.push((name, format!("{path}.css")));
+    }
+
+    for_each_parallel(
+      &styles,
+      self.threads(),
+      // Convert from scss to css, and minify if enabled
+      |(name, _)| self.or_error_style(name, self.compile_style(name)),
+      |i, result| {
+        let file = &styles[i].1;
+        let content = result?;
+        progress.step(BuildStage::Styles, file)?;
+        self.subscribers.send(|| BuildEvent::StyleCompiled {
+          name: styles[i].0.to_string(),
+          file: file.to_string(),
+        });
+        outputs.insert(file.to_string(), Output::Content(content));
+        Ok(())
+      },
+    )
+  }
+
+  /// Add public files to copy to `public` in build directory, and map of fingerprinted paths
+  ///
+  /// Missing public directory is treated as empty, for apps created with `Unreact::from_vfs`
+  fn compile_public(&self, outputs: &mut Outputs) -> UnreactResult<()> {
+    let public = &self.config.public;
+    let assets = self.read_assets();
+    if Path::new(&format!("./{public}")).is_dir() {
+      let files = (list_public(&self.config)?.into_iter())
+        .filter(|file| !self.config.inline_only.contains(file))
+        .collect::<Vec<_>>();
+
+      // Check that no other file is copied to the same path, with `lowercase_paths` set in config
+      if self.config.lowercase_paths {
+        let mut paths = HashMap::new();
+        for file in &files {
+          let path = assets.public_path(file);
+          if let Some(other) = paths.insert(path.clone(), file) {
+            return Err(UnreactError::DuplicateOutput(
+              format!("public/{path}"),
+              format!("public files '{other}' and '{file}'"),
+            ));
+          }
+        }
+      }
+      let images = &self.config.images;
+      if images.is_enabled() {
+        // Warn once for each format of image
+        let mut formats = Vec::new();
+        for format in files.iter().filter_map(|file| ImageFormat::from_path(file)) {
+          if !formats.contains(&format) {
+            formats.push(format);
+          }
+        }
+        for format in formats {
+          if let Some(warning) = unsupported_warning(images, self.image_processor.as_ref(), format)
+          {
+            self.warn(warning);
+          }
+        }
+      }
+      // Images are processed in parallel, as encoding is slow
+      for_each_parallel(
+        &files,
+        self.threads(),
+        |file| {
+          let source = format!("./{public}/{file}");
+          let path = format!("public/{}", assets.public_path(file));
+          if images.is_enabled() {
+            process_image(images, self.image_processor.as_ref(), &source, &path)
+          } else {
+            Ok(vec![(path, source)])
+          }
+        },
+        |_, result| {
+          for (path, source) in result? {
+            outputs.insert(path, Output::Copy(source));
+          }
+          Ok(())
+        },
+      )?;
+    }
+
+    if let Some(json) = assets.to_json(&self.config, &self.styles) {
+      outputs.insert(ASSETS_FILE.to_string(), Output::Content(json));
+    }
+    Ok(())
+  }
+
+  /// Write output files to directory
+  ///
+  /// Public files are copied with permissions and timestamps from `public_copy` in config
+  fn write_outputs(&self, build: &str, outputs: Outputs) -> UnreactResult<()> {
+    let outputs = outputs.into_iter().collect::<Vec<_>>();
+    let verify = self.config.verify_output;
+    // Hash of each written file, to verify
+    let mut written = Vec::new();
+
+    for_each_parallel(
+      &outputs,
+      self.threads(),
+      |(file, output)| {
+        // Create folder recursively
+        create_dir_all_safe(build, file)?;
+
+        let path = format!("./{build}/{file}");
+        match output {
+          Output::Content(content) => {
+            let content = self.hooks.before_write(file, content);
+            fs::write(&path, &content).map_err(|err| UnreactError::IoError(err, path))?;
+            Ok(verify.then(|| hash_content(content.as_bytes())))
+          }
+          Output::Copy(source) => {
+            copy_file(source, &path, &self.config.public_copy)?;
+            if !verify {
+              return Ok(None);
+            }
+            match fs::read(source) {
+              Ok(content) => Ok(Some(hash_content(&content))),
+              Err(err) => Err(UnreactError::IoError(err, source.to_string())),
+            }
+          }
+        }
+      },
+      |i, result| match result {
+        Ok(hash) => {
+          let file = outputs[i].0.as_str();
+          self.subscribers.send(|| BuildEvent::FileWritten {
+            file: file.to_string(),
+          });
+          written.extend(hash.map(|hash| (file, hash)));
+          Ok(())
+        }
+        Err(err) => self.skip_write_error(Err(err)),
+      },
+    )?;
+
+    if verify {
+      self.verify_outputs(build, &written)?;
+    }
+    Ok(())
+  }
+
+  /// Read written files again, to check that each is identical to output and can be parsed, with `verify_output` set in config
+  ///
+  /// `written`: Path of each file relative to build directory, with hash of output
+  fn verify_outputs(&self, build: &str, written: &[(&str, String)]) -> UnreactResult<()> {
+    for_each_parallel(
+      written,
+      self.threads(),
+      |(file, hash)| {
+        let path = format!("./{build}/{file}");
+        let content = fs::read(&path).map_err(|err| UnreactError::IoError(err, path))?;
+        verify_file(file, &content, hash)
+          .map_err(|reason| UnreactError::VerifyFail(file.to_string(), reason))
+      },
+      |_, result| result,
+    )
+  }
+
+  /// Write output files to custom storage, see `Unreact::set_sink`
+  fn write_sink(&self, sink: &dyn BuildSink, build: &str, outputs: Outputs) -> UnreactResult<()> {
+    let outputs = outputs.into_iter().collect::<Vec<_>>();
+
+    for_each_parallel(
+      &outputs,
+      self.threads(),
+      |(file, output)| match output {
+        Output::Content(content) => {
+          let content = self.hooks.before_write(file, content);
+          sink.write(build, file, content.as_bytes())
+        }
+        Output::Copy(source) => match fs::read(source) {
+          Ok(content) => sink.write(build, file, &content),
+          Err(err) => Err(UnreactError::IoError(err, source.to_string())),
+        },
+      },
+      |i, result| {
+        if result.is_ok() {
+          self.subscribers.send(|| BuildEvent::FileWritten {
+            file: outputs[i].0.to_string(),
+          });
+        }
+        self.skip_write_error(result)
+      },
+    )?;
+
+    sink.finish(build)?;
+    self.take_write_failures()
+  }
+
+  /// Get fingerprinted asset paths
+  fn read_assets(&self) -> std::sync::RwLockReadGuard<'_, Assets> {
+    self.assets.read().unwrap_or_else(PoisonError::into_inner)
+  }
+
+  /// Get fingerprinted asset paths, to change
+  fn write_assets(&self) -> std::sync::RwLockWriteGuard<'_, Assets> {
+    self.assets.write().unwrap_or_else(PoisonError::into_inner)
+  }
+
+  /// Create manifest of output files, and write if enabled in config
+  fn write_manifest(&mut self, dir: &str) -> UnreactResult<()> {
+    self.manifest = Manifest::from_dir(dir)?;
+    if self.config.manifest {
+      self.manifest.write(&format!("./{dir}/{MANIFEST_FILE}"))?;
+    }
+
+    Ok(())
+  }
+
+  /// Write build manifest of output files from manifest, with template and source file of each, if enabled in config
+  fn write_build_manifest(&self, dir: &str) -> UnreactResult<()> {
+    if !self.config.build_manifest {
+      return Ok(());
+    }
+
+    let sources = self.output_sources();
+    let files = (self.manifest.files.iter())
+      .map(|(file, entry)| {
+        let (template, source) = sources.get(file).cloned().unwrap_or_default();
+        let entry = BuildManifestEntry {
+          size: entry.size,
+          hash: entry.hash.to_string(),
+          template,
+          source,
+        };
+        (file.to_string(), entry)
+      })
+      .collect();
+    BuildManifest { files }.write(&format!("./{dir}/{BUILD_MANIFEST_FILE}"))
+  }
+
+  /// Write publish schedule of content left out until its date, or remove it if no content is scheduled, in production only
+  fn write_publish_schedule(&self) -> UnreactResult<()> {
+    let Some(path) = self
+      .config
+      .publish_schedule
+      .as_ref()
+      .filter(|_| !self.is_dev)
+    else {
+      return Ok(());
+    };
+    let path = format!("./{path}");
+
+    let scheduled = self
+      .scheduled
+      .lock()
+      .unwrap_or_else(PoisonError::into_inner);
+    if scheduled.is_empty() {
+      return match fs::remove_file(&path) {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => {
+          Err(UnreactError::IoError(err, path))
+        }
+        _ => Ok(()),
+      };
+    }
+    fs::write(&path, publish_schedule(&scheduled)).map_err(|err| UnreactError::IoError(err, path))
+  }
+
+  /// Write documentation of templates and partials to file, if `partial_docs` is set in config
+  fn write_partial_docs(&self) -> UnreactResult<()> {
+    let Some(path) = &self.config.partial_docs else {
+      return Ok(());
+    };
+    let path = format!("./{path}");
+    fs::write(&path, partial_docs_json(&self.partial_docs()))
+      .map_err(|err| UnreactError::IoError(err, path))
+  }
+
+  /// Get template and source file of output files, by path relative to build directory
+  ///
+  /// Generated files, such as feeds and sitemaps, are not included
+  fn output_sources(&self) -> HashMap<String, (Option<String>, Option<String>)> {
+    let mut sources = HashMap::new();
+    for page in &self.pages {
+      let template = match &page.source {
+        PageSource::Template(name, _) => Some(name.to_string()),
+        _ => None,
+      };
+      let source = page
+        .data("source")
+        .and_then(Value::as_str)
+        .map(String::from);
+      sources.insert(format!("{}.html", page.path), (template, source));
+    }
+
+    let assets = self.read_assets();
+    for name in self.styles.keys() {
+      let extension = match self.plain_styles.contains(name) {
+        true => "css",
+        false => "scss",
+      };
+      sources.insert(
+        format!("{}.css", assets.style_path(&self.config, name)),
+        (
+          None,
+          Some(format!("{}/{name}.{extension}", self.config.styles)),
+        ),
+      );
+    }
+    let public = &self.config.public;
+    for file in list_public(&self.config).unwrap_or_default() {
+      sources.insert(
+        format!("public/{}", assets.public_path(&file)),
+        (None, Some(format!("{public}/{file}"))),
+      );
+    }
+    sources
+  }
+
+  /// Write stats page of build to `/__unreact/index.html`, and reference of partials to `/__unreact/partials/index.html`, in dev mode only
+  #[cfg(feature = "dev-server")]
+  fn write_stats_page(&self, dir: &str) -> UnreactResult<()> {
+    if !self.is_dev {
+      return Ok(());
+    }
+
+    let mut styles = self
+      .styles
+      .keys()
+      .map(|name| (name.to_string(), self.config.style_path(name)))
+      .collect::<Vec<_>>();
+    styles.sort();
+    let content = stats_page(&self.pages, &self.templates(), &styles);
+
+    create_dir_all_safe(dir, &format!("{STATS_DIR}/index.html"))?;
+    let path = format!("./{dir}/{STATS_DIR}/index.html");
+    if let Err(err) = fs::write(&path, content) {
+      return Err(UnreactError::IoError(err, path));
+    }
+
+    // Reference of partials, linked from stats page
+    create_dir_all_safe(dir, &format!("{STATS_DIR}/partials/index.html"))?;
+    let path = format!("./{dir}/{STATS_DIR}/partials/index.html");
+    if let Err(err) = fs::write(&path, partial_docs_page(&self.partial_docs())) {
+      return Err(UnreactError::IoError(err, path));
+    }
+    Ok(())
+  }
+
+  /// Read templates, styles, and data files again from directories, and render every page again with the same data
+  ///
+  /// Call `Unreact::finish` afterwards to write files. Useful for a custom watch setup, see `Unreact::finish_watch`
+  pub fn reload(&mut self) -> UnreactResult<&mut Self> {
+    let vfs = self.read_vfs()?;
+    self.templates = vfs.templates;
+    // Every template is read again when rendered
+    if self.lazy.is_some() {
+      self.lazy = Some(LazyTemplates::list(&self.config)?);
+    }
+    self.styles = vfs.styles;
+    self.plain_styles = vfs.plain_styles;
+    self.style_partials = vfs.style_partials;
+    self.data = vfs.data;
+    if !self.locales.is_empty() {
+      self.locales = Arc::new(Locales::read(&self.config.locales, &self.locales.names)?);
+    }
+    let mut assets = self.write_assets();
+    assets.set_styles(&self.config, &self.styles);
+    assets.read_public(&self.config);
+    drop(assets);
+    self.clear_registry();
+
+    let all = (0..self.pages.len()).collect::<Vec<_>>();
+    self.rerender_pages(&all)?;
+
+    Ok(self)
+  }
+
+  /// Render pages at indexes again from their source, using `threads` from config
+  fn rerender_pages(&mut self, indexes: &[usize]) -> UnreactResult<()> {
+    let mut rendered = Vec::new();
+    for_each_parallel(
+      indexes,
+      self.threads(),
+      |i| self.render_page(&self.pages[*i]),
+      |i, result: UnreactResult<Option<String>>| {
+        if let Some(content) = result? {
+          rendered.push((indexes[i], content));
+        }
+        Ok(())
+      },
+    )?;
+
+    for (i, content) in rendered {
+      self.pages[i].content = content;
+      self.pages[i].rendered = true;
+    }
+    Ok(())
+  }
+
+  /// Render page from its source
+  ///
+  /// Returns `None` for plain pages, which have no source
+  fn render_page(&self, page: &Page) -> UnreactResult<Option<String>> {
+    let result = match &page.source {
+      PageSource::Plain => return Ok(None),
+      PageSource::Template(name, data) => self.render_cached(name, data),
+      PageSource::Markdown(content) => self.render_markdown(content),
+    };
+    self.or_error_page(&page.path, result).map(Some)
+  }
+
+  /// Start server in background, then rebuild on every change until program is stopped
+  ///
+  /// Sends `BuildEvent::Watching`, then events of each rebuild. Errors while rebuilding are printed, and do not stop watching
+  #[cfg(feature = "dev-server")]
+  fn watch(&mut self) -> UnreactResult<()> {
+    // Server keeps running in background
+    let (events, receiver) = std::sync::mpsc::channel();
+    let _server = self.spawn_server(events.clone())?;
+
+    let mut dirs = vec![
+      self.config.templates.as_str(),
+      self.config.styles.as_str(),
+      self.config.public.as_str(),
+    ];
+    // Data and content directories are optional
+    if Path::new(&self.config.data).is_dir() {
+      dirs.push(self.config.data.as_str());
+    }
+    if Path::new(&self.config.content).is_dir() {
+      dirs.push(self.config.content.as_str());
+    }
+    if !self.locales.is_empty() {
+      dirs.push(self.config.locales.as_str());
+    }
+    // Keep server running without watching, if failed
+    let _watcher = match watch_dirs(&dirs, events) {
+      Ok(watcher) => {
+        self.subscribers.send(|| BuildEvent::Watching {
+          dirs: dirs.iter().map(|dir| dir.to_string()).collect(),
+        });
+        Some(watcher)
+      }
+      Err(err) => {
+        self.warn(format!("Failed to watch files: {err}"));
+        None
+      }
+    };
+    self.handle_events(receiver);
+    Ok(())
+  }
+
+  /// Start dev server at address from `dev_port` in config, in its own thread, which sends events to app
+  ///
+  /// Own runtime is always used, as app blocks current thread while handling events
+  #[cfg(feature = "dev-server")]
+  fn spawn_server(&self, events: std::sync::mpsc::Sender<DevEvent>) -> UnreactResult<ServerHandle> {
+    server::listen_with(self.dev_server(events), &self.dev_addr, None)
+  }
+
+  /// Values for every request to dev server, which sends events to app
+  #[cfg(feature = "dev-server")]
+  fn dev_server(&self, events: std::sync::mpsc::Sender<DevEvent>) -> DevServer {
+    DevServer {
+      host: self.config.host.clone(),
+      reload: self.reload.clone(),
+      base_path: self.url.base_path().to_string(),
+      events,
+      directories: self.config.dev_directories,
+      log: self.config.dev_log,
+      build: self.config.build.to_string(),
+    }
+  }
+
+  /// Rebuild on changes, and render templates for dev server, until server stops
+  #[cfg(feature = "dev-server")]
+  fn handle_events(&mut self, events: std::sync::mpsc::Receiver<DevEvent>) {
+    for event in events {
+      match event {
+        // Success and failure are sent to subscribers by rebuild
+        DevEvent::Changed(paths) => match self.rebuild(&paths) {
+          Ok(pages) => self.on_rebuild(pages),
+          Err(err) => eprintln!("Rebuild failed: {err}"),
+        },
+        DevEvent::Render(request) => {
+          // Missing data renders same as an empty object
+          let data = match request.data {
+            Value::Null => json!({}),
+            data => data,
+          };
+          let result = (self.render(&request.template, &data)).map_err(|err| err.to_string());
+          // Request could have been cancelled
+          let _ = request.reply.send(result);
+        }
+      }
+    }
+  }
+
+  /// Called after new output is written by a rebuild
+  ///
+  /// Reloads documents open in browser of `pages`, or every document if `None`, see `DEV_SCRIPT`
+  #[cfg(feature = "dev-server")]
+  fn on_rebuild(&self, pages: Option<Vec<String>>) {
+    match pages {
+      Some(pages) => self.reload.send_pages(
+        pages
+          .iter()
+          .map(|path| format!("{}/{path}", self.url.base_path()))
+          .collect(),
+      ),
+      None => self.reload.send(),
+    }
+  }
+
+  /// Rebuild parts of site affected by changed files
+  ///
+  /// Returns paths of pages which were rendered again or removed, or `None` if every page could have changed, such as by a style
+  #[cfg(feature = "dev-server")]
+  fn rebuild(&mut self, paths: &[std::path::PathBuf]) -> UnreactResult<Option<Vec<String>>> {
+    self.subscribers.send(|| BuildEvent::BuildStarted {
+      is_dev: self.is_dev,
+    });
+    let result = self.rebuild_changed(paths);
+    self.send_failure(result)
+  }
+
+  /// Rebuild parts of site affected by changed files, for `Unreact::rebuild`
+  #[cfg(feature = "dev-server")]
+  fn rebuild_changed(
+    &mut self,
+    paths: &[std::path::PathBuf],
+  ) -> UnreactResult<Option<Vec<String>>> {
+    // Get names of changed files, relative to directory, without extension
+    let changed_in = |dir: &str| -> Vec<String> {
+      let Ok(dir) = Path::new(dir).canonicalize() else {
+        return Vec::new();
+      };
+      paths
+        .iter()
+        .filter_map(|path| {
+          // Removed files can not be canonicalized, so use parent directory
+          let parent = path.parent()?.canonicalize().ok()?;
+          let path = parent.join(path.file_name()?);
+          let relative = path.strip_prefix(&dir).ok()?.with_extension("");
+          Some(relative.to_str()?.replace('\\', "/"))
+        })
+        .collect()
+    };
+    let templates = changed_in(&self.config.templates);
+    let styles = changed_in(&self.config.styles);
+    let public = changed_in(&self.config.public);
+    let data = changed_in(&self.config.data);
+    let locales = changed_in(&self.config.locales);
+    let content = changed_in(&self.config.content);
+
+    // Pages of changed content files, before indexes of pages are found
+    let content_pages = self.reload_content(&content)?;
+
+    // Pages which use changed templates
+    let mut affected = Vec::new();
+    if !templates.is_empty() {
+      let vfs = self.read_vfs()?;
+      self.templates = vfs.templates;
+      // Changed templates are read again when rendered
+      if let Some(lazy) = &mut self.lazy {
+        lazy.refresh(&self.config, &templates)?;
+      }
+      self.clear_registry();
+
+      // Find and render pages which use changed templates
+      let sources = self.templates();
+      for (i, page) in self.pages.iter().enumerate() {
+        let uses_changed = match &page.source {
+          PageSource::Plain => false,
+          // Shortcodes can use any template
+          PageSource::Markdown(_) => true,
+          PageSource::Template(name, _) => match template_dependencies(&sources, name) {
+            Some(deps) => templates.iter().any(|x| deps.contains(x)),
+            None => true,
+          },
+        };
+        if uses_changed {
+          affected.push(i);
+        }
+      }
+    }
+    if !styles.is_empty() {
+      let old_names = self.styles.keys().cloned().collect::<BTreeSet<_>>();
+      let vfs = self.read_vfs()?;
+      self.styles = vfs.styles;
+      self.plain_styles = vfs.plain_styles;
+      self.style_partials = vfs.style_partials;
+
+      // Templates can list styles, as `unreact.styles`, or embed them
+      if self.styles.keys().collect::<BTreeSet<_>>() != old_names.iter().collect()
+        || self.uses_helper("inline_style")
+      {
+        self.clear_registry();
+        affected = (0..self.pages.len())
+          .filter(|i| !matches!(self.pages[*i].source, PageSource::Plain))
+          .collect();
+      }
+    }
+    if !public.is_empty() && self.uses_helper("inline_script") {
+      self.clear_registry();
+      affected = (0..self.pages.len())
+        .filter(|i| !matches!(self.pages[*i].source, PageSource::Plain))
+        .collect();
+    }
+    // Every template can use data files
+    if !data.is_empty() {
+      self.data = self.read_vfs()?.data;
+      affected = (0..self.pages.len())
+        .filter(|i| !matches!(self.pages[*i].source, PageSource::Plain))
+        .collect();
+    }
+    if !locales.is_empty() && !self.locales.is_empty() {
+      self.locales = Arc::new(Locales::read(&self.config.locales, &self.locales.names)?);
+      self.clear_registry();
+      affected = (0..self.pages.len())
+        .filter(|i| !matches!(self.pages[*i].source, PageSource::Plain))
+        .collect();
+    }
+    self.rerender_pages(&affected)?;
+
+    // Write only changed files
+    let mut outputs = Outputs::new();
+    let mut ignore = |_: BuildProgress| ControlFlow::Continue(());
+    let mut progress = ProgressTracker::new(&mut ignore, 0);
+    if !affected.is_empty() || !content_pages.is_empty() {
+      self.compile_pages(
+        &mut outputs,
+        |page| {
+          content_pages.contains(&page.path)
+            || affected.iter().any(|i| self.pages[*i].path == page.path)
+        },
+        &mut progress,
+      )?;
+    }
+    if !styles.is_empty() {
+      self.compile_styles(&mut outputs, &mut progress)?;
+    }
+    if !public.is_empty() {
+      self.compile_public(&mut outputs)?;
+    }
+    // Feeds and sitemaps list pages, which may have changed
+    self.compile_derived(&mut outputs)?;
+    let build = self.config.build.clone();
+    let report = self.build_report(outputs.keys().cloned().collect());
+    self.write_outputs(&build, outputs)?;
+
+    self.write_manifest(&build)?;
+    self.write_stats_page(&build)?;
+    self.take_write_failures()?;
+    self
+      .subscribers
+      .send(|| BuildEvent::BuildFinished { report });
+
+    // Styles and public files can be used by any page
+    if !styles.is_empty() || !public.is_empty() {
+      return Ok(None);
+    }
+    let mut pages = content_pages;
+    for i in affected {
+      if !pages.contains(&self.pages[i].path) {
+        pages.push(self.pages[i].path.clone());
+      }
+    }
+    Ok(Some(pages))
+  }
+
+  /// Read changed files in content directory again, and render their pages, for directories of `Unreact::pages_from_content`
+  ///
+  /// `changed`: Names of files, relative to content directory, without extension
+  ///
+  /// Pages of removed files are removed, with their output files. Returns paths of pages which were rendered or removed
+  #[cfg(feature = "dev-server")]
+  fn reload_content(&mut self, changed: &[String]) -> UnreactResult<Vec<String>> {
+    let mut paths = Vec::new();
+    for (dir, template) in self.content_dirs.clone() {
+      let prefix = dir.trim_matches('/');
+      let content_dir = format!("{}/{dir}", self.config.content);
+      let content_dir = content_dir.trim_end_matches('/');
+
+      for name in changed {
+        let name = match prefix.is_empty() {
+          true => Some(name.as_str()),
+          false => name
+            .strip_prefix(prefix)
+            .and_then(|name| name.strip_prefix('/')),
+        };
+        let Some(name) = name else {
+          continue;
+        };
+        let file = format!("{name}.md");
+        let source = format!("{content_dir}/{file}");
+
+        // Pages of file, one for each locale
+        let is_source = |page: &Page| page.data("source").and_then(Value::as_str) == Some(&source);
+        let position = self.pages.iter().position(is_source);
+        let old = (self.pages.iter())
+          .filter(|page| is_source(page))
+          .map(|page| page.path.clone())
+          .collect::<Vec<_>>();
+
+        let new = match Path::new(&format!("./{source}")).is_file() {
+          true => self.render_content(content_dir, &dir, &file, &template)?,
+          false => Vec::new(),
+        };
+        self.pages.retain(|page| !is_source(page));
+        for (path, _, _) in &new {
+          self.check_path(&format!("{path}.html"))?;
+        }
+
+        // Removed pages are not served
+        for path in &old {
+          if !new.iter().any(|(new, _, _)| new == path) {
+            fs::remove_file(format!("./{}/{path}.html", self.config.build)).ok();
+            (self.page_casings.get_mut())
+              .unwrap_or_else(PoisonError::into_inner)
+              .remove(path);
+          }
+        }
+
+        let mut pages = Vec::new();
+        for (path, html, data) in new {
+          paths.push(path.clone());
+          pages.push(Page::new(
+            &path,
+            &html,
+            PageSource::Template(template.to_string(), data),
+          ));
+        }
+        let position = position.unwrap_or(self.pages.len());
+        self.pages.splice(position..position, pages);
+        for path in old {
+          if !paths.contains(&path) {
+            paths.push(path);
+          }
+        }
+      }
+    }
+    Ok(paths)
+  }
+
+  /// Archive build directory to a file, after `Unreact::finish`
+  ///
+  /// Format is chosen by extension of `path`: `.tar`, `.tar.gz` (or `.tgz`), or `.zip`
+  ///
+  /// Archive is deterministic, so the same build produces an identical file
+  ///
+  /// Uses default compression level, see `Unreact::package_with_level`
+  ///
+  /// Note that `Unreact::finish` does not return in dev mode, as the server is started
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// use unreact::prelude::*;
+  ///
+  /// fn main() -> UnreactResult<()> {
+  ///   let mut app = Unreact::new(Config::default(), false, "https://mysite.com")?;
+  ///
+  ///   app
+  ///     .page_plain("index", "This is my site")
+  ///     .finish()?
+  ///     .package("site.tar.gz")?;
+  ///
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn package(&mut self, path: &str) -> UnreactResult<&mut Self> {
+    self.package_with_level(path, DEFAULT_PACKAGE_LEVEL)
+  }
+
+  /// Archive build directory to a file, with compression level
+  ///
+  /// See `Unreact::package`
+  ///
+  /// `level`: Compression level from `0` (none) to `9` (best). Ignored for `.tar`
+  pub fn package_with_level(&mut self, path: &str, level: u32) -> UnreactResult<&mut Self> {
+    package_dir(&self.config.build, path, level)?;
+    Ok(self)
+  }
+
+  /// Get manifest of every output file, with size and hash
+  ///
+  /// Empty until `Unreact::finish` is called
+  pub fn manifest(&self) -> &Manifest {
+    &self.manifest
+  }
+
+  /// Build (but do not send) IndexNow request for pages, and write key file to build directory
+  ///
+  /// Call after `Unreact::finish`, then deploy site before sending request with an HTTP client, so search engines can verify the key file
+  ///
+  /// `old`: Manifest of previously deployed build. If given and `changed_only` is enabled, only added, changed, and removed pages are submitted
+  ///
+  /// Returns `None` if there are no pages to submit. This crate includes no HTTP client, so nothing is sent to search engines
+  ///
+  /// Requires the `indexnow` feature
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// use unreact::prelude::*;
+  ///
+  /// fn main() -> UnreactResult<()> {
+  ///   let old = Manifest::read("./build/manifest.json").ok();
+  ///
+  ///   let mut app = Unreact::new(Config::default(), false, "https://mysite.com")?;
+  ///   app.page_plain("index", "This is my site").finish()?;
+  ///
+  ///   let options = IndexNow::new("4f2a9c1e8b7d4e3f");
+  ///   if let Some(request) = app.build_indexnow_request(&options, old.as_ref())? {
+  ///     // Send after deploying, with any HTTP client
+  ///     println!("POST {}\n{}", request.endpoint, request.body);
+  ///   }
+  ///
+  ///   Ok(())
+  /// }
+  /// ```
+  #[cfg(feature = "indexnow")]
+  pub fn build_indexnow_request(
+    &self,
+    options: &IndexNow,
+    old: Option<&Manifest>,
+  ) -> UnreactResult<Option<IndexNowRequest>> {
+    options.check_key()?;
+
+    // Write key file at root of site
+    let path = format!("./{}/{}", self.config.build, key_file(options));
+    if let Err(err) = fs::write(&path, &options.key) {
+      return Err(UnreactError::IoError(err, path));
+    }
+
+    Ok(create_request(options, &self.url, &self.manifest, old))
+  }
+
+  /// Compare output files with manifest of an older build
+  ///
+  /// Call after `Unreact::finish`
+  ///
+  /// # Examples
+  ///
+  /// Prints files to upload to a host, since the last deploy
+  ///
+  /// ```no_run
+  /// use unreact::prelude::*;
+  ///
+  /// fn main() -> UnreactResult<()> {
+  ///   // Read before `Unreact::finish`, as the build directory is replaced
+  ///   let old = Manifest::read("./build/manifest.json")?;
+  ///
+  ///   let mut app = Unreact::new(Config::default(), false, "https://mysite.com")?;
+  ///   app.page_plain("index", "This is my site").finish()?;
+  ///
+  ///   let diff = app.diff_manifest(&old);
+  ///   println!("Upload: {:?}", [diff.added, diff.changed].concat());
+  ///   println!("Delete: {:?}", diff.removed);
+  ///
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn diff_manifest(&self, old: &Manifest) -> ManifestDiff {
+    self.manifest.diff(old)
+  }
+
+  /// Run `f` with registry of every template, partial, and helper
+  ///
+  /// Registry is created on first render, and reused until templates, partials, or helpers change
+  fn with_registry<T>(&self, f: impl FnOnce(&Handlebars<'static>) -> T) -> UnreactResult<T> {
+    // Already created
+    if let Some(reg) = self
+      .compiled
+      .read()
+      .unwrap_or_else(PoisonError::into_inner)
+      .as_ref()
+    {
+      return Ok(f(reg));
+    }
+
+    let mut compiled = self
+      .compiled
+      .write()
+      .unwrap_or_else(PoisonError::into_inner);
+    // Could have been created by another thread while waiting for lock
+    let reg = match (compiled.take(), &self.shared) {
+      (Some(reg), _) => reg,
+      // Profile helpers record to this instance only
+      (None, Some(shared)) if !self.config.profile => {
+        shared.get_or_insert(&self.shared_key(), || self.create_registry())?
+      }
+      (None, _) => Arc::new(self.create_registry()?),
+    };
+    Ok(f(compiled.insert(reg)))
+  }
+
+  /// Get key of registry in shared template cache
+  ///
+  /// Config used by inline helpers is not in fingerprint
+  fn shared_key(&self) -> String {
+    let input = json!({
+      "fingerprint": self.fingerprint(),
+      "inline": [self.config.minify.to_string(), self.config.styles.to_string(), self.config.public.to_string()],
+      "guards": self.config.limits.guards_partials(),
+    });
+    hash_content(input.to_string().as_bytes())
+  }
+
+  /// Remove created registry, so it is created again with changed templates, partials, or helpers
+  fn clear_registry(&self) {
+    *self
+      .compiled
+      .write()
+      .unwrap_or_else(PoisonError::into_inner) = None;
+    self.clear_fingerprints();
+  }
+
+  /// Remove fingerprints of templates, so they are hashed again for keys of `cache`
+  fn clear_fingerprints(&self) {
+    *self
+      .fingerprint
+      .lock()
+      .unwrap_or_else(PoisonError::into_inner) = None;
+    self
+      .template_fingerprints
+      .lock()
+      .unwrap_or_else(PoisonError::into_inner)
+      .clear();
+  }
+
+  /// Get every template, including templates which are read, with `lazy_templates` set in config
+  fn templates(&self) -> Cow<'_, FileMap> {
+    let Some(lazy) = &self.lazy else {
+      return Cow::Borrowed(&self.templates);
+    };
+    // Templates of plugins replace files, like `Unreact::read_vfs`
+    let mut templates = lazy.templates();
+    templates.extend(self.templates.clone());
+    Cow::Owned(templates)
+  }
+
+  /// Get amount of threads to build with, from `threads` in config
+  ///
+  /// Always `1` when profiling, as partials are timed on a single stack, and with lazy templates, as templates are read during render
+  fn threads(&self) -> usize {
+    if self.config.profile || self.lazy.is_some() {
+      1
+    } else {
+      thread_count(self.config.threads)
+    }
+  }
+
+  /// Get hash of every template and partial, and config they depend on, for keys of `cache`
+  fn fingerprint(&self) -> String {
+    let mut fingerprint = self
+      .fingerprint
+      .lock()
+      .unwrap_or_else(PoisonError::into_inner);
+    fingerprint
+      .get_or_insert_with(|| self.hash_templates(|_| true))
+      .to_string()
+  }
+
+  /// Get hash of templates and partials which template depends on, including through other partials, and config they depend on
+  ///
+  /// So changing a template only renders pages which use it again, when `cache` is set in config.
+  /// Same as `Unreact::fingerprint` if dependencies cannot be known, such as with a dynamic partial or a template engine
+  fn template_fingerprint(&self, name: &str) -> String {
+    if let Some(fingerprint) = (self.template_fingerprints.lock())
+      .unwrap_or_else(PoisonError::into_inner)
+      .get(name)
+    {
+      return fingerprint.to_string();
+    }
+
+    // Custom partials replace templates with the same name
+    let mut sources = self.templates().into_owned();
+    sources.extend(self.partials.clone());
+    let fingerprint = match template_dependencies(&sources, name) {
+      Some(deps) if self.engine.is_none() => self.hash_templates(|name| deps.contains(name)),
+      _ => self.fingerprint(),
+    };
+
+    (self.template_fingerprints.lock())
+      .unwrap_or_else(PoisonError::into_inner)
+      .insert(name.to_string(), fingerprint.to_string());
+    fingerprint
+  }
+
+  /// Get hash of templates and partials with names which match filter, and config they depend on
+  fn hash_templates(&self, filter: impl Fn(&String) -> bool) -> String {
+    // Sorted, so hash is the same every build
+    let templates = self.templates();
+    let templates = (templates.iter())
+      .filter(|(name, _)| filter(name))
+      .collect::<BTreeMap<_, _>>();
+    let partials = (self.partials.iter())
+      .filter(|(name, _)| filter(name))
+      .collect::<BTreeMap<_, _>>();
+    let style_paths = self.config.style_paths.iter().collect::<BTreeMap<_, _>>();
+
+    let input = json!({
+      "templates": templates,
+      "partials": partials,
+      "inbuilt": self.inbuilt_partials(),
+      "styles": [style_paths, self.config.styles_output, self.config.flatten_styles, self.config.lowercase_paths],
+      "profile": self.config.profile,
+      "engine": self.engine.is_some(),
+      "locales": self.locales.fingerprint(),
+      "assets": self.read_assets().fingerprint(),
+      "inline": self.inline_fingerprint(),
+      "images": self.images_fingerprint(),
+      // Code of helpers, hooks, and plugins cannot be hashed, so `cache_version` is changed with it
+      "code": {
+        "helpers": self.helper_names,
+        "hooks": format!("{:?}", self.hooks),
+        "plugins": self.plugins.iter().map(|plugin| plugin.name()).collect::<Vec<_>>(),
+        "version": self.config.cache_version,
+      },
+    });
+    hash_content(input.to_string().as_bytes())
+  }
+
+  /// Hash input of `IMAGE` partial, as `srcset` depends on size of each image, and formats of processor
+  fn images_fingerprint(&self) -> String {
+    let images = &self.config.images;
+    if !images.is_enabled() {
+      return String::new();
+    }
+    let sizes = (list_public(&self.config).unwrap_or_default().into_iter())
+      .filter(|file| ImageFormat::from_path(file).is_some())
+      .filter_map(|file| {
+        let image = fs::read(format!("./{}/{file}", self.config.public)).ok()?;
+        Some((file, image_size(&image)?))
+      })
+      .collect::<Vec<_>>();
+    format!("{images:?} {:?} {sizes:?}", self.image_processor)
+  }
+
+  /// Merge data of page over globals
+  ///
+  /// Data which is `null` uses globals only
+  fn layer_data(&self, data: &Value) -> Value {
+    let mut layered = self.globals.clone();
+    if !data.is_null() {
+      merge_json(&mut layered, data.clone());
+    }
+    layered
+  }
+
+  /// Get data of site for templates, as `unreact`
+  ///
+  /// `styles` is a list of every style, sorted by name, with `name`, and `path` of output file **without** `.css` extension.
+  /// `page_styles` is the same, with only styles used by `template`, see `Config::page_styles`
+  fn app_data(&self, template: &str) -> Value {
+    let assets = self.read_assets();
+    let list = |names: Vec<&String>| {
+      names
+        .into_iter()
+        .map(|name| json!({ "name": name, "path": assets.style_path(&self.config, name) }))
+        .collect::<Vec<_>>()
+    };
+
+    // Embedded styles have no file to link
+    let mut names = self
+      .styles
+      .keys()
+      .filter(|name| !self.config.inline_only.contains(name))
+      .collect::<Vec<_>>();
+    names.sort();
+    let page_styles = self.page_styles(template, names.clone());
+    json!({ "styles": list(names), "page_styles": list(page_styles) })
+  }
+
+  /// Get styles used by template, from sorted names of every style, see `Config::page_styles`
+  ///
+  /// Every style is used if a dependency of template cannot be known
+  fn page_styles<'a>(&self, template: &str, mut names: Vec<&'a String>) -> Vec<&'a String> {
+    if !self.config.page_styles {
+      return names;
+    }
+    let templates = self.templates();
+    let Some(deps) = template_dependencies(&templates, template) else {
+      return names;
+    };
+
+    // Styles which belong to any template, and styles which belong to dependencies
+    let mut owned = HashSet::new();
+    let mut used = HashSet::new();
+    for (name, content) in templates.iter() {
+      let mut styles = styles_declared(content);
+      styles.push(name.to_string());
+      if deps.contains(name) {
+        used.extend(styles.iter().cloned());
+      }
+      owned.extend(styles);
+    }
+
+    names.retain(|name| used.contains(*name) || !owned.contains(*name));
+    names
+  }
+
+  /// If any template or partial uses helper, such as `inline_style`
+  fn uses_helper(&self, name: &str) -> bool {
+    (self.templates().values())
+      .chain(self.partials.values())
+      .any(|template| template.contains(name))
+  }
+
+  /// Hash of styles and public files which can be embedded, if `inline_style` or `inline_script` are used
+  fn inline_fingerprint(&self) -> String {
+    let mut input = String::new();
+    if self.uses_helper("inline_style") {
+      input += &format!(
+        "{:?} {:?} {:?}",
+        self.styles.iter().collect::<BTreeMap<_, _>>(),
+        self.style_partials.iter().collect::<BTreeMap<_, _>>(),
+        self.plain_styles,
+      );
+    }
+    if self.uses_helper("inline_script") {
+      for file in list_public(&self.config).unwrap_or_default() {
+        if let Ok(content) = fs::read(format!("./{}/{file}", self.config.public)) {
+          input += &format!("{file} {}\n", hash_content(&content));
+        }
+      }
+    }
+    hash_content(input.as_bytes())
+  }
+
+  /// Get key of rendered page in build cache, see `Config::cache`
+  ///
+  /// Key is a hash of everything the page depends on: the template and partials it uses, values of inbuilt partials (such as `URL` and `DEV_SCRIPT`),
+  /// page data, globals, collections, site data, url, dev mode, names of custom helpers, hooks, and plugins, and `cache_version` in config.
+  /// A page is rendered again if its key changes
+  ///
+  /// Same key is returned if cache is not enabled, for debugging
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use unreact::prelude::*;
+  /// use serde_json::json;
+  ///
+  /// fn main() -> UnreactResult<()> {
+  ///   let mut vfs = Vfs::new();
+  ///   vfs.templates.insert("index".to_string(), "<h1>{{title}}</h1>".to_string());
+  ///
+  ///   let url = SiteUrl::parse("https://mysite.com")?;
+  ///   let mut app = Unreact::from_vfs(Config::default(), false, url, vfs.clone());
+  ///   let key = app.cache_key("index", &json!({"title": "Home"}));
+  ///   assert_eq!(key, app.cache_key("index", &json!({"title": "Home"})));
+  ///
+  ///   app.set_globals(json!({"year": 2024}));
+  ///   assert_ne!(key, app.cache_key("index", &json!({"title": "Home"})));
+  ///
+  ///   // Code of helpers is not part of key, so change `cache_version` when it changes
+  ///   let config = Config { cache_version: "2".to_string(), ..Config::default() };
+  ///   let app = Unreact::from_vfs(config, false, SiteUrl::parse("https://mysite.com")?, vfs.clone());
+  ///   assert_ne!(key, app.cache_key("index", &json!({"title": "Home"})));
+  ///
+  ///   let url = SiteUrl::parse("https://other.com")?;
+  ///   let app = Unreact::from_vfs(Config::default(), false, url, vfs);
+  ///   assert_ne!(key, app.cache_key("index", &json!({"title": "Home"})));
+  ///
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn cache_key(&self, template: &str, data: &Value) -> String {
+    BuildCache::key(
+      CacheKind::Render,
+      &[
+        &self.template_fingerprint(template),
+        template,
+        &data.to_string(),
+        &self.globals.to_string(),
+        &serde_json::to_string(&self.collections).unwrap_or_default(),
+        &serde_json::to_string(&self.data).unwrap_or_default(),
+        &self.app_data(template).to_string(),
+        // Used by helpers, such as `canonical_url`
+        self.url.as_str(),
+        &self.is_dev.to_string(),
+      ],
+    )
+  }
+
+  /// Render template, or get from cache if enabled
+  fn render_cached(&self, name: &str, data: &Value) -> UnreactResult<String> {
+    let Some(cache) = &self.cache else {
+      return self.render(name, data);
+    };
+    let key = self.cache_key(name, data);
+    cache.get_or_insert(CacheKind::Render, key, || self.render(name, data))
+  }
+
+  /// In dev mode, print error of rendering page, and show error page in its place, so build continues
+  ///
+  /// Error page is served at path of page, until the error is fixed, see `error_page`
+  fn or_error_page(&self, path: &str, result: UnreactResult<String>) -> UnreactResult<String> {
+    match result {
+      Err(err) if self.is_dev => {
+        let message = format!("Failed to render page '{path}': {err}");
+        eprintln!("{message}");
+        self
+          .subscribers
+          .send(|| BuildEvent::WarningEmitted { message });
+        Ok(error_page(path, &err, &self.templates()))
+      }
+      Ok(html) => {
+        self.subscribers.send(|| BuildEvent::PageRendered {
+          path: path.to_string(),
+        });
+        Ok(html)
+      }
+      result => result,
+    }
+  }
+
+  /// Minify or format page, or get from cache if enabled
+  fn output_cached(&self, content: &str) -> UnreactResult<String> {
+    let Some(cache) = &self.cache else {
+      return Ok(self.output_page(content));
+    };
+
+    let options = format!("{} {:?}", self.config.minify, self.config.format);
+    let key = BuildCache::key(CacheKind::Output, &[&options, content]);
+    cache.get_or_insert(CacheKind::Output, key, || Ok(self.output_page(content)))
+  }
+
+  /// Get statistics of build cache, if `cache` is set in config
+  pub fn cache_stats(&self) -> Option<CacheStats> {
+    self.cache.as_ref().map(BuildCache::stats)
+  }
+
+  /// Create registry with every template, inbuilt partial, custom partial, and helper
+  fn create_registry(&self) -> UnreactResult<Handlebars<'static>> {
+    // Registry with inbuilt and custom helpers
+    let mut reg = self.registry.clone();
+    self.register_inline_helpers(&mut reg);
+    if !self.locales.is_empty() {
+      register_translate_helper(&mut reg, self.locales.clone());
+    }
+
+    // Register all other templates as partials
+    for (name, part) in self.templates().iter() {
+      let part = self.wrap_partial(name, part);
+      if let Err(err) = reg.register_partial(name, part) {
+        return Err(UnreactError::RegisterPartialFail(
+          name.to_string(),
+          Box::new(err),
+        ));
+      }
+      self.subscribers.send(|| BuildEvent::TemplateLoaded {
+        name: name.to_string(),
+      });
+    }
+
+    // Register inbuilt partials
+    for (name, part) in self.inbuilt_partials() {
+      if let Err(err) = reg.register_partial(name, part) {
+        return Err(UnreactError::RegisterInbuiltPartialFail(
+          name.to_string(),
+          Box::new(err),
+        ));
+      }
+    }
+
+    // Register custom partials, which replace templates and inbuilt partials
+    for (name, part) in &self.partials {
+      let part = self.wrap_partial(name, part);
+      if let Err(err) = reg.register_partial(name, part) {
+        return Err(UnreactError::RegisterPartialFail(
+          name.to_string(),
+          Box::new(err),
+        ));
+      }
+    }
+
+    Ok(reg)
+  }
+
+  /// Wrap source of template or custom partial with helpers, to check render limits, and to record time of each partial if profiling
+  fn wrap_partial(&self, name: &str, part: &str) -> String {
+    let part = if self.config.limits.guards_partials() {
+      wrap_guard(name, part)
+    } else {
+      part.to_string()
+    };
+    if self.config.profile {
+      wrap_partial(name, &part)
+    } else {
+      part
+    }
+  }
+
+  /// Get JSON schema of data given to each template, as union of every page rendered with it
   ///
-  /// `content`: Raw text content to write to file, without template
+  /// Includes global variables. Pages registered with `Unreact::page_plain` or `Unreact::page_markdown` are not included
   ///
   /// # Examples
   ///
-  /// Renders two files with raw text
-  ///
   /// ```
   /// use unreact::prelude::*;
+  /// use serde_json::json;
   ///
   /// fn main() -> UnreactResult<()> {
-  ///   let mut app = Unreact::new(Config::default(), false, "https://mysite.com")?;
+  ///   let mut vfs = Vfs::new();
+  ///   vfs.templates.insert("post".to_string(), "<h1>{{title}}</h1>".to_string());
   ///
-  ///   // Renders to `./build/index.html`
-  ///   app.page_plain("index", "This is my site");
-  ///   // Renders to `./build/path/file.html`
-  ///   app.page_plain("path/file", "This file is in ./build/path/file.html");
+  ///   let url = SiteUrl::parse("https://mysite.com")?;
+  ///   let mut app = Unreact::from_vfs(Config::default(), false, url, vfs);
+  ///
+  ///   app.page("a", "post", &json!({"title": "A"}))?;
+  ///   app.page("b", "post", &json!({"title": "B", "draft": true}))?;
+  ///
+  ///   assert_eq!(
+  ///     app.context_schema()["post"],
+  ///     json!({
+  ///       "type": "object",
+  ///       "properties": {
+  ///         "title": {"type": "string"},
+  ///         "draft": {"type": "boolean"},
+  ///       },
+  ///     })
+  ///   );
   ///
-  ///   app.finish()?;
   ///   Ok(())
   /// }
   /// ```
-  pub fn page_plain(&mut self, path: &str, content: &str) -> &mut Self {
-    self.pages.push(File::new(path, content));
-    self
+  pub fn context_schema(&self) -> Value {
+    let mut schemas = Map::new();
+
+    for page in &self.pages {
+      let PageSource::Template(name, data) = &page.source else {
+        continue;
+      };
+
+      // Same data as `Unreact::render`
+      let data = self.layer_data(data);
+
+      let schema = schemas
+        .entry(name)
+        .or_insert_with(|| Value::Object(Map::new()));
+      if let Value::Object(schema) = schema {
+        merge_schema(schema, &data);
+      }
+    }
+
+    Value::Object(schemas)
   }
 
-  /// Register new page (file) with any path, with template
-  ///
-  /// `path`: Output path in build directory, **without** `.html` extension
-  ///
-  /// `template`: Name of template to render, **without** `.hbs` extension
+  /// Write JSON schema of data given to each template to a file, for autocomplete in editors
   ///
-  /// `data`: JSON data to render with (use `serde_json::json!` macro)
+  /// See `Unreact::context_schema`
   ///
   /// # Examples
   ///
-  /// Renders two files with templates
-  ///
-  /// ```
+  /// ```no_run
   /// use unreact::prelude::*;
-  /// use serde_json::{json, Value};
+  /// use serde_json::json;
   ///
   /// fn main() -> UnreactResult<()> {
   ///   let mut app = Unreact::new(Config::default(), false, "https://mysite.com")?;
   ///
-  ///   // Renders to `./build/help.html`, using `./templates/help_template.hbs`, with no data
-  ///   app.page("help", "help_template", Value::Null);
-  ///
-  ///   // Renders to `./build/path/file.html`, using `./templates/other/template.hbs`, with a custom message
-  ///   app.page("path/file", "other/template", &json!({"msg": "Hello!"}));
-  ///
-  ///   app.finish()?;
+  ///   app.page("index", "index", &json!({"msg": "Hello!"}))?
+  ///     .export_context_schema("contexts.json")?
+  ///     .finish()?;
   ///   Ok(())
   /// }
   /// ```
-  pub fn page(&mut self, path: &str, template: &str, data: &Value) -> UnreactResult<&mut Self> {
-    self.page_plain(path, &self.render(template, data)?);
+  pub fn export_context_schema(&mut self, path: &str) -> UnreactResult<&mut Self> {
+    // Pretty print
+    let content = format!("{:#}", self.context_schema());
+    if let Err(err) = fs::write(path, content) {
+      return Err(UnreactError::IoError(err, path.to_string()));
+    }
     Ok(self)
   }
 
-  /// Register index page (`./index.html`), with template
-  ///
-  /// Alias of `app.page("index", ...)`
-  ///
-  /// `path`: Output path in build directory, **without** `.html` extension
+  /// Render a template with data
   ///
   /// `template`: Name of template to render, **without** `.hbs` extension
   ///
   /// `data`: JSON data to render with (use `serde_json::json!` macro)
   ///
+  /// Data is merged over globals (see `Unreact::set_globals`), so page data overrides globals with the same key, and `null` values hide globals.
+  /// Collections (see `Unreact::collection`) are added to data as `collections`, and data files (see `Config::data`) as `data`.
+  /// Every style is listed as `unreact.styles`, with `name` and `path`, so a layout can link every stylesheet
+  ///
   /// # Examples
   ///
-  /// Renders an index page with a custom message
+  /// Prints a template to standard output, completed with a custom message
   ///
-  /// ```
+  /// ```no_run
   /// use unreact::prelude::*;
-  /// use serde_json::{json};
+  /// use serde_json::json;
   ///
   /// fn main() -> UnreactResult<()> {
-  ///   let mut app = Unreact::new(Config::default(), false, "https://mysite.com")?;
+  ///   let app = Unreact::new(Config::default(), false, "https://mysite.com")?;
   ///
-  ///   // Renders to `./build/index.html`, using `./templates/standard.hbs`, with a custom message
-  ///   app.index("standard", &json!({"msg": "Hello!"}));
+  ///   println!("{}", app.render("index", &json!({"msg": "Hello!"}))?);
   ///
-  ///   app.finish()?;
   ///   Ok(())
   /// }
   /// ```
-  pub fn index(&mut self, template: &str, data: &Value) -> UnreactResult<&mut Self> {
-    self.page("index", template, data)
-  }
-
-  /// Register 404 (not found) page (`./404.html`)
-  ///
-  /// Alias of `app.page("404", ...)`
-  ///
-  /// `path`: Output path in build directory, **without** `.html` extension
-  ///
-  /// `template`: Name of template to render, **without** `.hbs` extension
-  ///
-  /// `data`: JSON data to render with (use `serde_json::json!` macro)
-  ///
-  /// # Examples
   ///
-  /// Renders a 404 page
+  /// Links every style
   ///
   /// ```
   /// use unreact::prelude::*;
-  /// use serde_json::{Value};
+  /// use serde_json::Value;
   ///
   /// fn main() -> UnreactResult<()> {
-  ///   let mut app = Unreact::new(Config::default(), false, "https://mysite.com")?;
+  ///   let mut vfs = Vfs::new();
+  ///   vfs.templates.insert("head".to_string(), "{{#each unreact.styles}}{{>STYLE name=name}}{{/each}}".to_string());
+  ///   vfs.styles.insert("main".to_string(), "".to_string());
+  ///   vfs.styles.insert("blog".to_string(), "".to_string());
   ///
-  ///   // Renders to `./build/404.html`, using `./templates/errors/not_found.hbs`, with no data
-  ///   app.not_found("errors/not_found", Value::Null);
+  ///   let app = Unreact::from_vfs(Config::default(), false, SiteUrl::parse("https://mysite.com")?, vfs);
+  ///   assert_eq!(
+  ///     app.render("head", &Value::Null)?,
+  ///     concat!(
+  ///       r#"<link rel="stylesheet" href="https://mysite.com/styles/blog.css" />"#,
+  ///       r#"<link rel="stylesheet" href="https://mysite.com/styles/main.css" />"#,
+  ///     ),
+  ///   );
   ///
-  ///   app.finish()?;
   ///   Ok(())
   /// }
   /// ```
-  pub fn not_found(&mut self, template: &str, data: &Value) -> UnreactResult<&mut Self> {
-    self.page("404", template, data)
+  pub fn render(&self, name: &str, data: &Value) -> UnreactResult<String> {
+    // Read template and partials it uses, if not read yet
+    if let Some(lazy) = &self.lazy {
+      if lazy.load(name)? {
+        self.clear_registry();
+      }
+    }
+
+    // Get template string from name
+    let templates = self.templates();
+    let template = match templates.get(name) {
+      Some(s) => s,
+      None => return Err(UnreactError::TemplateNotExist(name.to_string())),
+    };
+
+    let mut data = self.layer_data(data);
+    if !self.collections.is_empty() {
+      merge_json(
+        &mut data,
+        json!({ "collections": Value::Object(self.collections.clone()) }),
+      );
+    }
+    if !self.data.is_empty() {
+      merge_json(
+        &mut data,
+        json!({ "data": Value::Object(self.data.clone()) }),
+      );
+    }
+    merge_json(&mut data, json!({ "unreact": self.app_data(name) }));
+
+    if let Some(engine) = &self.engine {
+      return engine
+        .render(name, &templates, &data, &self.inbuilt())
+        .map_err(|err| UnreactError::EngineFail(name.to_string(), err));
+    }
+
+    // Render template
+    // Only timed when profiling, as `Instant::now` panics on `wasm32-unknown-unknown`
+    let start = self.config.profile.then(Instant::now);
+    let (result, exceeded) = self.with_registry(|reg| {
+      with_fragments(&self.fragments, || {
+        guarded(self.config.limits, || {
+          let mut output = LimitedOutput::default();
+          let result = if self.config.profile {
+            // Template is registered wrapped with profile helpers, so render source instead, to not count as partial
+            reg.render_template_to_write(template, &data, &mut output)
+          } else {
+            reg.render_to_write(name, &data, &mut output)
+          };
+          result.map(|()| output.into_string())
+        })
+      })
+    })?;
+    if let Some(start) = start {
+      if let Ok(mut profile) = self.profile.lock() {
+        profile.record_render(name, start.elapsed());
+        profile.clear_stack();
+      }
+    }
+
+    if let Some(reason) = exceeded {
+      return Err(UnreactError::RenderLimit(name.to_string(), reason));
+    }
+    match result {
+      Ok(x) => Ok(x),
+      Err(err) => Err(UnreactError::HandlebarsFail(
+        name.to_string(),
+        Box::new(err),
+      )),
+    }
   }
 
-  /// Create all files in production mode
+  /// Get render times and call counts of templates, if `profile` is enabled in config
   ///
   /// # Examples
   ///
-  /// Compiles to `./build`, in production mode
-  ///
   /// ```
   /// use unreact::prelude::*;
+  /// use serde_json::json;
   ///
   /// fn main() -> UnreactResult<()> {
-  ///   // Note that argument for `is_dev` is `false`
-  ///   let mut app = Unreact::new(Config::default(), false, "https://mysite.com")?;
+  ///   let mut vfs = Vfs::new();
+  ///   vfs.templates.insert("index".to_string(), "{{#each items}}{{>item}}{{/each}}".to_string());
+  ///   vfs.templates.insert("item".to_string(), "<li>{{this}}</li>".to_string());
+  ///
+  ///   let config = Config {
+  ///     profile: true,
+  ///     ..Config::default()
+  ///   };
+  ///   let url = SiteUrl::parse("https://mysite.com")?;
+  ///   let app = Unreact::from_vfs(config, false, url, vfs);
+  ///
+  ///   let html = app.render("index", &json!({"items": [1, 2, 3]}))?;
+  ///   assert_eq!(html, "<li>1</li><li>2</li><li>3</li>");
+  ///
+  ///   let profile = app.profile();
+  ///   assert_eq!(profile.templates["index"].renders, 1);
+  ///   assert_eq!(profile.templates["item"].partial_calls, 3);
+  ///
+  ///   // Slowest template first
+  ///   println!("{profile}");
   ///
-  ///   app.page_plain("index", "This is my site, in production")
-  ///     .finish()?;
   ///   Ok(())
   /// }
   /// ```
+  pub fn profile(&self) -> Profile {
+    match self.profile.lock() {
+      Ok(profile) => profile.clone(),
+      Err(_) => Profile::default(),
+    }
+  }
+
+  /// Compile a style from `.scss` to `.css`, minified if enabled in config
   ///
-  /// Compiles to `./.devbuild`, in development mode, and host to `http://127.0.0.1:8080`
+  /// `name`: Name of style to compile, **without** `.scss` extension
   ///
-  /// ```
+  /// # Examples
+  ///
+  /// ```no_run
   /// use unreact::prelude::*;
   ///
   /// fn main() -> UnreactResult<()> {
-  ///   // Note that argument for `is_dev` is `true`
-  ///   let mut app = Unreact::new(Config::default(), true, "https://mysite.com")?;
+  ///   let app = Unreact::new(Config::default(), false, "https://mysite.com")?;
+  ///
+  ///   // Using `./styles/main.scss`
+  ///   println!("{}", app.render_style("main")?);
   ///
-  ///   app.page_plain("index", "This is my site, in development")
-  ///     .finish()?;
   ///   Ok(())
   /// }
   /// ```
-  pub fn finish(&mut self) -> UnreactResult<&mut Self> {
-    // Create pages
-    for file in &self.pages {
-      let parent = &self.config.build;
-      // Create folder recursively
-      create_dir_all_safe(parent, &file.path)?;
-
-      // Minify if enabled
-      let output = if self.config.minify {
-        // Minified html
-        use minify_html::{minify, Cfg};
-        String::from_utf8_lossy(&minify(
-          &file.content.as_bytes(),
-          &Cfg {
-            do_not_minify_doctype: true,
-            keep_comments: true,
-            ..Cfg::default()
-          },
-        ))
-        .to_string()
-      } else {
-        // Un-minified file
-        file.content.to_string()
-      };
+  pub fn render_style(&self, name: &str) -> UnreactResult<String> {
+    if !self.styles.contains_key(name) {
+      return Err(UnreactError::StyleNotExist(name.to_string()));
+    }
+    self.compile_style(name)
+  }
 
-      // Create file
-      if let Err(err) = fs::write(format!("./{parent}/{}.html", file.path), &output) {
-        return Err(UnreactError::IoError(
-          err,
-          format!("./{parent}/{}.html", file.path),
-        ));
+  /// Convert style from scss to css, and minify if enabled in config
+  fn compile_style(&self, name: &str) -> UnreactResult<String> {
+    compile_scss(&self.config, self.style_files(), name)
+  }
+
+  /// In dev mode, print error of compiling style, and show error on every page with the style instead, so build continues
+  fn or_error_style(&self, name: &str, result: UnreactResult<String>) -> UnreactResult<String> {
+    match result {
+      Err(err) if self.is_dev => {
+        eprintln!("Failed to compile style '{name}': {err}");
+        Ok(error_style(name, &err))
       }
+      result => result,
     }
+  }
 
-    // Create styles
-    for (path, content) in &self.styles {
-      let parent = format!("{}/{}", self.config.build, self.config.styles);
-      // Create folder recursively
-      create_dir_all_safe(&parent, &path)?;
-
-      // Convert from scss to css
-      let parsed = match grass::from_string(content.to_string(), &grass::Options::default()) {
-        Ok(x) => x,
-        Err(err) => {
-          return Err(UnreactError::ScssConvertFail(
-            path.to_string(),
-            err.to_string(),
-          ))
-        }
-      };
+  /// Get styles and partials, for `@use` and `@import` in styles
+  fn style_files(&self) -> StyleFiles<'_> {
+    StyleFiles {
+      dir: &self.config.styles,
+      styles: &self.styles,
+      plain: &self.plain_styles,
+      partials: &self.style_partials,
+    }
+  }
 
-      // Minify if enabled
-      let output = if self.config.minify {
-        // Minified css
-        use css_minify::optimizations::{Level, Minifier};
-
-        match Minifier::default().minify(&parsed, Level::Two) {
-          Ok(x) => x,
-          Err(err) => {
-            return Err(UnreactError::MinifyCssFail(
-              path.to_string(),
-              err.to_string(),
-            ))
-          }
-        }
-      } else {
-        // Un-minified file
-        parsed
-      };
+  /// Register helpers which embed styles and scripts, with current styles
+  ///
+  /// Registered when registry is created, so changed styles are used
+  fn register_inline_helpers(&self, reg: &mut Handlebars) {
+    use handlebars::{Context, Helper, HelperResult, Output, RenderContext, RenderError};
 
-      // Create file - Convert from `scss` to `css` with `grass`
-      if let Err(err) = fs::write(format!("./{parent}/{path}.css"), output) {
-        return Err(UnreactError::IoError(err, format!("./{parent}/{path}.css")));
-      }
-    }
+    // Embed compiled style, such as `{{inline_style "critical"}}`
+    // Each style is compiled once for every page
+    let config = self.config.clone();
+    let styles = self.styles.clone();
+    let plain_styles = self.plain_styles.clone();
+    let style_partials = self.style_partials.clone();
+    let compiled = Arc::new(Mutex::new(HashMap::<String, String>::new()));
+    reg.register_helper(
+      "inline_style",
+      Box::new(
+        move |h: &Helper,
+              _: &Handlebars,
+              _: &Context,
+              _: &mut RenderContext,
+              out: &mut dyn Output|
+              -> HelperResult {
+          let Some(name) = h.param(0).and_then(|x| x.value().as_str()) else {
+            return Err(RenderError::new(
+              "Helper `inline_style` requires a style name",
+            ));
+          };
+          let mut compiled = compiled.lock().unwrap_or_else(PoisonError::into_inner);
+          let css = match compiled.get(name) {
+            Some(css) => css.to_string(),
+            None => {
+              if !styles.contains_key(name) {
+                return Err(RenderError::new(format!(
+                  "Helper `inline_style` requires an existing style, not `{name}`"
+                )));
+              }
+              let files = StyleFiles {
+                dir: &config.styles,
+                styles: &styles,
+                plain: &plain_styles,
+                partials: &style_partials,
+              };
+              let css = compile_scss(&config, files, name)
+                .map_err(|err| RenderError::new(err.to_string()))?;
+              compiled.insert(name.to_string(), css.to_string());
+              css
+            }
+          };
+          out.write(&format!(
+            "<style>{}</style>",
+            css.replace("</style", "<\\/style")
+          ))?;
+          Ok(())
+        },
+      ),
+    );
 
-    // Copy public files
-    if let Err(err) = dircpy::copy_dir(
-      format!("./{}", &self.config.public),
-      format!("./{}/public", self.config.build),
-    ) {
-      return Err(UnreactError::IoError(
-        err,
-        format!("./{}", &self.config.public),
-      ));
-    };
+    // Embed public file, such as `{{inline_script "js/boot.js"}}`
+    let public = self.config.public.clone();
+    reg.register_helper(
+      "inline_script",
+      Box::new(
+        move |h: &Helper,
+              _: &Handlebars,
+              _: &Context,
+              _: &mut RenderContext,
+              out: &mut dyn Output|
+              -> HelperResult {
+          let Some(path) = h.param(0).and_then(|x| x.value().as_str()) else {
+            return Err(RenderError::new(
+              "Helper `inline_script` requires a path of a public file",
+            ));
+          };
+          let path = format!("./{public}/{}", path.trim_start_matches('/'));
+          let script = fs::read_to_string(&path).map_err(|err| {
+            RenderError::new(format!(
+              "Helper `inline_script` failed to read '{path}' - {err}"
+            ))
+          })?;
+          out.write(&format!(
+            "<script>{}</script>",
+            script.replace("</script", "<\\/script")
+          ))?;
+          Ok(())
+        },
+      ),
+    );
+  }
 
-    // Open local server if in dev mode
-    if self.is_dev {
-      Self::listen();
+  /// Minify html of page if enabled in config, otherwise format with options in config
+  fn output_page(&self, content: &str) -> String {
+    // Un-minified file
+    if !self.config.minify {
+      return format_html(content, &self.config.format);
     }
 
-    Ok(self)
+    // Minified html
+    use minify_html::{minify, Cfg};
+    String::from_utf8_lossy(&minify(
+      content.as_bytes(),
+      &Cfg {
+        do_not_minify_doctype: true,
+        keep_comments: true,
+        ..Cfg::default()
+      },
+    ))
+    .to_string()
   }
 
-  /// Render a template with data
+  /// Convert Markdown to html, with extensions from `markdown` property in config
   ///
-  /// `template`: Name of template to render, **without** `.hbs` extension
+  /// Shortcodes are expanded to the template (partial) with the same name, such as `{{< youtube dQw4w9WgXcQ start="30" >}}`
   ///
-  /// `data`: JSON data to render with (use `serde_json::json!` macro)
+  /// Positional arguments are passed as `args` array, and named arguments with their key.
+  /// The shortcode above renders template `youtube` with data `{"args": ["dQw4w9WgXcQ"], "start": "30"}`
   ///
-  /// # Examples
+  /// A shortcode alone on a line is not wrapped in a paragraph. Shortcodes in code spans and code blocks (fenced or indented) are not expanded,
+  /// so write `` `{{< youtube id >}}` `` to show shortcode syntax as text
   ///
-  /// Prints a template to standard output, completed with a custom message
+  /// # Examples
   ///
-  /// ```
+  /// ```no_run
   /// use unreact::prelude::*;
   ///
   /// fn main() -> UnreactResult<()> {
-  ///   let mut app = Unreact::new(Config::default(), false, "https://mysite.com");
+  ///   let app = Unreact::new(Config::default(), false, "https://mysite.com")?;
+  ///
+  ///   assert_eq!(app.render_markdown("~~old~~ new")?, "<p><del>old</del> new</p>\n");
   ///
-  ///   println!("{}", app.render("index", &json!({"msg": "Hello!"})));  
+  ///   // Using `./templates/youtube.hbs`
+  ///   println!("{}", app.render_markdown("# My video\n\n{{< youtube dQw4w9WgXcQ >}}")?);
   ///
   ///   Ok(())
   /// }
   /// ```
-  pub fn render(&self, name: &str, data: &Value) -> UnreactResult<String> {
-    // Get template string from name
-    let template = match self.templates.get(name) {
-      Some(s) => s,
-      None => return Err(UnreactError::TemplateNotExist(name.to_string())),
-    };
-
-    // Create handlebars registry
-    let mut reg = Handlebars::new();
+  pub fn render_markdown(&self, content: &str) -> UnreactResult<String> {
+    // Replace shortcodes with placeholders, so they are not converted
+    let (content, shortcodes) = extract_shortcodes(content)?;
 
-    // Register all other templates as partials
-    for (name, part) in &self.templates {
-      if let Err(err) = reg.register_partial(name, part) {
-        return Err(UnreactError::RegisterPartialFail(name.to_string(), err));
-      }
+    // Render each shortcode with its partial
+    let mut rendered = Vec::new();
+    for shortcode in &shortcodes {
+      rendered.push(self.render(&shortcode.name, &shortcode.data)?);
     }
 
-    // Register inbuilt partials
-    for (name, part) in self.inbuilt_partials() {
-      if let Err(err) = reg.register_partial(name, part) {
-        return Err(UnreactError::RegisterInbuiltPartialFail(
-          name.to_string(),
-          err,
-        ));
-      }
-    }
+    let html = markdown_to_html(&content, &self.config.markdown);
+    Ok(insert_shortcodes(&html, &rendered))
+  }
 
-    // ? Remove `.clone` (2x) ? how ?
-    let mut data = data.clone();
-    if !self.globals.is_null() {
-      merge_json(&mut data, self.globals.clone());
+  /// Get base url of site, or of dev server in dev mode, with base path of site
+  fn base_url(&self) -> String {
+    if self.is_dev {
+      format!("http://{}{}", self.dev_addr, self.url.base_path())
+    } else {
+      self.url.to_string()
     }
+  }
 
-    // Render template
-    match reg.render_template(template, &data) {
-      Ok(x) => Ok(x),
-      Err(err) => Err(UnreactError::HandlebarsFail(name.to_string(), err)),
-    }
+  /// Get values of inbuilt partials, for template engine
+  fn inbuilt(&self) -> Inbuilt {
+    let assets = self.read_assets();
+    let style_paths = (self.styles.keys())
+      .map(|name| (name.to_string(), assets.style_path(&self.config, name)))
+      .collect();
+    Inbuilt::new(self.base_url(), style_paths)
   }
 
   /// Get inbuilt partials to register in `Unreact::render`
   fn inbuilt_partials(&self) -> Vec<(&'static str, String)> {
     vec![
-      (
-        // Base url for site
-        "URL",
-        if self.is_dev {
-          format!("http://{}", server::ADDRESS)
-        } else {
-          self.url.to_string()
-        },
-      ),
+      // Base url for site
+      ("URL", self.base_url()),
       // Script for development
       // Is not registered if `dev_warning` in config is false
       (
@@ -495,21 +5098,346 @@ impl Unreact {
         },
       ),
       // Simple link
+      // Path is lowercase if `lowercase_paths` is set in config, like paths of pages
       (
         "LINK",
-        r#"<a href="{{>URL}}/{{to}}"> {{>@partial-block}} </a>"#.to_string(),
+        if self.config.lowercase_paths {
+          r#"<a href="{{>URL}}/{{lowercase to}}"> {{>@partial-block}} </a>"#.to_string()
+        } else {
+          r#"<a href="{{>URL}}/{{to}}"> {{>@partial-block}} </a>"#.to_string()
+        },
       ),
       // Simple style tag
       (
         "STYLE",
-        r#"<link rel="stylesheet" href="{{>URL}}/styles/{{name}}.css" />"#.to_string(),
+        r#"<link rel="stylesheet" href="{{>URL}}/{{style_path name}}.css" />"#.to_string(),
+      ),
+      // Style tags of styles used by page, see `Config::page_styles`
+      (
+        "STYLES",
+        "{{#each @root.unreact.page_styles}}{{>STYLE name=name}}{{/each}}".to_string(),
+      ),
+      // Url of asset in build directory, such as `public/logo.png`, fingerprinted if enabled
+      ("ASSET", "{{>URL}}/{{asset_path path}}".to_string()),
+      // Charset, viewport, title, and meta tags, from `head` in config
+      ("HTML_HEAD", self.config.head.to_partial()),
+      // Link to skip navigation, for keyboard and screen reader users
+      // Should be first element in body, and link to `MAIN`
+      (
+        "SKIP_LINK",
+        concat!(
+          r##"<a class="skip-link" href="#{{#if to}}{{to}}{{else}}main{{/if}}">"##,
+          r#"{{#if text}}{{text}}{{else}}Skip to content{{/if}}</a>"#,
+        )
+        .to_string(),
+      ),
+      // Main landmark, target of `SKIP_LINK`
+      (
+        "MAIN",
+        r#"<main id="{{#if id}}{{id}}{{else}}main{{/if}}" tabindex="-1"> {{>@partial-block}} </main>"#
+          .to_string(),
+      ),
+      // Image with alternative sources, such as `webp`
+      // `alt` should always be given - empty `alt` marks image as decorative
+      (
+        "PICTURE",
+        concat!(
+          "<picture>",
+          r#"{{#each sources}}<source srcset="{{>URL}}/{{this.src}}"{{#if this.type}} type="{{this.type}}"{{/if}} />{{/each}}"#,
+          r#"<img src="{{>URL}}/{{src}}" alt="{{alt}}""#,
+          r#"{{#if width}} width="{{width}}"{{/if}}{{#if height}} height="{{height}}"{{/if}} loading="lazy" />"#,
+          "</picture>",
+        )
+        .to_string(),
+      ),
+      // Image with resized and WebP variants, see `Config::images`
+      // `src` is path of image, such as `public/cat.png`, and `sizes` is passed to `srcset`
+      (
+        "IMAGE",
+        concat!(
+          "<picture>",
+          r#"{{#if (image_srcset src "webp")}}<source type="image/webp" srcset="{{image_srcset src "webp"}}""#,
+          r#"{{#if sizes}} sizes="{{sizes}}"{{/if}} />{{/if}}"#,
+          r#"<img src="{{>URL}}/{{asset_path src}}""#,
+          r#"{{#if (image_srcset src)}} srcset="{{image_srcset src}}"{{#if sizes}} sizes="{{sizes}}"{{/if}}{{/if}}"#,
+          r#" alt="{{alt}}"{{#if width}} width="{{width}}"{{/if}}{{#if height}} height="{{height}}"{{/if}} loading="lazy" />"#,
+          "</picture>",
+        )
+        .to_string(),
       ),
     ]
   }
 
-  /// Open local server and listen
-  fn listen() {
-    server::listen();
+  /// Register `image_srcset` helper, which outputs `srcset` of an image in public directory, with format of image or another format
+  ///
+  /// Used by `IMAGE` partial. Registered again when image processor changes
+  fn register_image_helper(&self, reg: &mut Handlebars) {
+    use handlebars::{Context, Helper, HelperResult, Output, RenderContext, RenderError};
+
+    let options = self.config.images.clone();
+    let processor = Arc::clone(&self.image_processor);
+    let public = self.config.public.clone();
+    let assets = Arc::clone(&self.assets);
+    let base_url = self.base_url();
+    reg.register_helper(
+      "image_srcset",
+      Box::new(
+        move |h: &Helper,
+              _: &Handlebars,
+              _: &Context,
+              _: &mut RenderContext,
+              out: &mut dyn Output|
+              -> HelperResult {
+          let Some(path) = h.param(0).and_then(|x| x.value().as_str()) else {
+            return Err(RenderError::new("Helper `image_srcset` requires a path"));
+          };
+          // Format such as `webp`
+          let format = match h.param(1).and_then(|x| x.value().as_str()) {
+            Some(format) => match ImageFormat::from_path(&format!(".{format}")) {
+              Some(format) => Some(format),
+              None => {
+                return Err(RenderError::new(format!(
+                  "Helper `image_srcset` does not support format `{format}`"
+                )))
+              }
+            },
+            None => None,
+          };
+
+          let path = path.trim_start_matches('/');
+          let Some(file) = path.strip_prefix("public/") else {
+            return Ok(());
+          };
+          let url = {
+            let assets = assets.read().unwrap_or_else(PoisonError::into_inner);
+            format!("{base_url}/{}", assets.asset_path(path))
+          };
+          let source = format!("./{public}/{file}");
+          out.write(&srcset(&options, processor.as_ref(), &source, &url, format))?;
+          Ok(())
+        },
+      ),
+    );
+  }
+
+  /// Register inbuilt helpers to registry, when app is created
+  fn register_inbuilt_helpers(&self, reg: &mut Handlebars) {
+    use handlebars::{Context, Helper, HelperResult, Output, RenderContext, RenderError};
+
+    // Output path of style, without `.css` extension, fingerprinted if enabled
+    // Used by `STYLE` partial
+    let config = self.config.clone();
+    let assets = Arc::clone(&self.assets);
+    reg.register_helper(
+      "style_path",
+      Box::new(
+        move |h: &Helper,
+              _: &Handlebars,
+              _: &Context,
+              _: &mut RenderContext,
+              out: &mut dyn Output|
+              -> HelperResult {
+          match h.param(0).and_then(|x| x.value().as_str()) {
+            Some(name) => {
+              let assets = assets.read().unwrap_or_else(PoisonError::into_inner);
+              out.write(&assets.style_path(&config, name))?;
+              Ok(())
+            }
+            None => Err(RenderError::new(
+              "Helper `style_path` requires a style name",
+            )),
+          }
+        },
+      ),
+    );
+
+    // Output path of asset, fingerprinted if enabled
+    // Used by `ASSET` partial
+    let assets = Arc::clone(&self.assets);
+    reg.register_helper(
+      "asset_path",
+      Box::new(
+        move |h: &Helper,
+              _: &Handlebars,
+              _: &Context,
+              _: &mut RenderContext,
+              out: &mut dyn Output|
+              -> HelperResult {
+          match h.param(0).and_then(|x| x.value().as_str()) {
+            Some(path) => {
+              let assets = assets.read().unwrap_or_else(PoisonError::into_inner);
+              out.write(&assets.asset_path(path))?;
+              Ok(())
+            }
+            None => Err(RenderError::new("Helper `asset_path` requires a path")),
+          }
+        },
+      ),
+    );
+
+    self.register_image_helper(reg);
+
+    // Absolute url of path or url, for canonical links
+    // Optional second parameter is prepended to path, such as `locale_path`
+    // Used by `HTML_HEAD` partial
+    let url = self.url.clone();
+    reg.register_helper(
+      "canonical_url",
+      Box::new(
+        move |h: &Helper,
+              _: &Handlebars,
+              _: &Context,
+              _: &mut RenderContext,
+              out: &mut dyn Output|
+              -> HelperResult {
+          let prefix = (h.param(1).and_then(|x| x.value().as_str())).unwrap_or_default();
+          match h.param(0).and_then(|x| x.value().as_str()) {
+            Some(link) if link.contains("://") => {
+              out.write(&handlebars::html_escape(&url.resolve(link)))?;
+              Ok(())
+            }
+            Some(link) => {
+              let link = format!("{prefix}/{}", link.trim_start_matches('/'));
+              out.write(&handlebars::html_escape(&url.resolve(&link)))?;
+              Ok(())
+            }
+            None => Err(RenderError::new(
+              "Helper `canonical_url` requires a path or url",
+            )),
+          }
+        },
+      ),
+    );
+
+    // Record start and end of partials, see `wrap_partial`
+    if self.config.profile {
+      let profile = Arc::clone(&self.profile);
+      reg.register_helper(
+        PROFILE_START,
+        Box::new(
+          move |h: &Helper,
+                _: &Handlebars,
+                _: &Context,
+                _: &mut RenderContext,
+                _: &mut dyn Output|
+                -> HelperResult {
+            if let (Some(name), Ok(mut profile)) =
+              (h.param(0).and_then(|x| x.value().as_str()), profile.lock())
+            {
+              profile.start_partial(name);
+            }
+            Ok(())
+          },
+        ),
+      );
+
+      let profile = Arc::clone(&self.profile);
+      reg.register_helper(
+        PROFILE_END,
+        Box::new(
+          move |_: &Helper,
+                _: &Handlebars,
+                _: &Context,
+                _: &mut RenderContext,
+                _: &mut dyn Output|
+                -> HelperResult {
+            if let Ok(mut profile) = profile.lock() {
+              profile.end_partial();
+            }
+            Ok(())
+          },
+        ),
+      );
+    }
+
+    // Check render limits at start and end of partials, see `wrap_guard`
+    reg.register_helper(
+      GUARD_START,
+      Box::new(
+        |h: &Helper,
+         _: &Handlebars,
+         _: &Context,
+         _: &mut RenderContext,
+         _: &mut dyn Output|
+         -> HelperResult {
+          let name = h
+            .param(0)
+            .and_then(|x| x.value().as_str())
+            .unwrap_or_default();
+          start_partial(name).map_err(RenderError::new)
+        },
+      ),
+    );
+    reg.register_helper(
+      GUARD_END,
+      Box::new(
+        |_: &Helper,
+         _: &Handlebars,
+         _: &Context,
+         _: &mut RenderContext,
+         _: &mut dyn Output|
+         -> HelperResult {
+          end_partial();
+          Ok(())
+        },
+      ),
+    );
+
+    // Render block once for each key in a build, such as `{{#cache "nav"}}`
+    reg.register_helper("cache", Box::new(CacheHelper));
+
+    // Text in lowercase
+    // Used by `LINK` partial, with `lowercase_paths` set in config
+    reg.register_helper(
+      "lowercase",
+      Box::new(
+        |h: &Helper,
+         _: &Handlebars,
+         _: &Context,
+         _: &mut RenderContext,
+         out: &mut dyn Output|
+         -> HelperResult {
+          match h.param(0).and_then(|x| x.value().as_str()) {
+            Some(text) => {
+              out.write(&handlebars::html_escape(&text.to_lowercase()))?;
+              Ok(())
+            }
+            None => Err(RenderError::new("Helper `lowercase` requires text")),
+          }
+        },
+      ),
+    );
+
+    // Structured data script tag
+    reg.register_helper(
+      "jsonld",
+      Box::new(
+        |h: &Helper,
+         _: &Handlebars,
+         _: &Context,
+         _: &mut RenderContext,
+         out: &mut dyn Output|
+         -> HelperResult {
+          let hash = h
+            .hash()
+            .iter()
+            .map(|(key, value)| (*key, value.value().clone()))
+            .collect();
+          let schema = schema_from_args(h.param(0).map(|x| x.value()), hash);
+          out.write(&jsonld(&schema))?;
+          Ok(())
+        },
+      ),
+    );
+  }
+
+  /// Open local server and listen, rendering templates for dev server
+  #[cfg(feature = "dev-server")]
+  fn listen(&mut self) -> UnreactResult<()> {
+    let (events, receiver) = std::sync::mpsc::channel();
+    let _server = self.spawn_server(events)?;
+    self.handle_events(receiver);
+    Ok(())
   }
 
   /// Returns as error if any value of `config` are not valid directories
@@ -531,35 +5459,26 @@ impl Unreact {
       }
     }
 
-    // Remove build directory if exists
-    if Path::new(&format!("./{}", config.build)).exists() {
-      if let Err(err) = fs::remove_dir_all(format!("./{}", config.build)) {
-        return Err(UnreactError::IoError(err, config.build.to_string()));
-      };
-    }
-
-    // Create new build directory and generic subfolders
-    let dirs = vec!["", "/styles", "/public"];
-    for dir in dirs {
-      if let Err(err) = fs::create_dir(format!("./{}{}", config.build, dir)) {
-        return Err(UnreactError::IoError(err, config.build.to_string()));
-      }
+    // Otherwise build directory is replaced after build
+    if config.clean {
+      create_build_dir(&config.build)?;
     }
 
     Ok(())
   }
 
-  /// Load all templates in directory of `templates` property in `config`
-  fn load_templates(config: &Config) -> UnreactResult<FileMap> {
-    let mut templates = FileMap::new();
-    load_filemap(&mut templates, &config.templates, "")?;
-    Ok(templates)
-  }
-
-  /// Import all scss files in directory of `styles` property in `config`
-  fn load_styles(config: &Config) -> UnreactResult<FileMap> {
-    let mut styles = FileMap::new();
-    load_filemap(&mut styles, &config.styles, "")?;
-    Ok(styles)
+  /// Check that url of each target is valid, and that build directories are different
+  fn check_targets(config: &Config) -> UnreactResult<()> {
+    let mut builds = HashSet::from([config.build.trim_end_matches('/')]);
+    for (url, build) in &config.targets {
+      SiteUrl::parse(url)?;
+      if !builds.insert(build.trim_end_matches('/')) {
+        return Err(UnreactError::DuplicateOutput(
+          format!("./{build}"),
+          format!("target '{url}' and another target"),
+        ));
+      }
+    }
+    Ok(())
   }
 }
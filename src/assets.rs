@@ -0,0 +1,171 @@
+use std::{collections::BTreeMap, fs};
+
+use crate::{hash_content, public::list_public, Config, FileMap};
+
+/// File name of asset map, in root of build directory
+pub(crate) const ASSETS_FILE: &str = "assets.json";
+
+/// Length of content hash in fingerprinted file names
+const HASH_LENGTH: usize = 10;
+
+/// Fingerprinted paths of styles and public files, see `Config::fingerprint_assets`
+///
+/// Paths are unchanged if fingerprinting is disabled
+#[derive(Debug, Default)]
+pub(crate) struct Assets {
+  /// If paths are fingerprinted
+  enabled: bool,
+  /// If paths are lowercase, see `Config::lowercase_paths`
+  lowercase: bool,
+  /// Hash of every style, as styles can import each other
+  styles_hash: String,
+  /// Hash of every public file, relative to public directory
+  public_hashes: BTreeMap<String, String>,
+}
+
+impl Assets {
+  /// Create asset paths for styles, without public files
+  ///
+  /// Fingerprinting is disabled in dev mode, so rebuilt pages do not link to old paths
+  pub fn new(config: &Config, is_dev: bool, styles: &FileMap) -> Self {
+    let mut assets = Assets {
+      enabled: config.fingerprint_assets && !is_dev,
+      lowercase: config.lowercase_paths,
+      ..Assets::default()
+    };
+    assets.set_styles(config, styles);
+    assets
+  }
+
+  /// Hash styles again, after they change
+  pub fn set_styles(&mut self, config: &Config, styles: &FileMap) {
+    if !self.enabled {
+      return;
+    }
+    let styles = styles.iter().collect::<BTreeMap<_, _>>();
+    let input = format!("{:?} {:?}", styles, config.minify);
+    self.styles_hash = hash_content(input.as_bytes());
+  }
+
+  /// Read and hash every public file again
+  ///
+  /// Missing public directory is treated as empty
+  pub fn read_public(&mut self, config: &Config) {
+    self.public_hashes.clear();
+    if !self.enabled {
+      return;
+    }
+    for file in list_public(config).unwrap_or_default() {
+      if let Ok(content) = fs::read(format!("./{}/{file}", config.public)) {
+        self.public_hashes.insert(file, hash_content(&content));
+      }
+    }
+  }
+
+  /// Get output path of style, relative to build directory, **without** `.css` extension
+  ///
+  /// `styles/main` is `styles/main.0123456789`
+  pub fn style_path(&self, config: &Config, name: &str) -> String {
+    let path = config.style_path(name);
+    if !self.enabled {
+      return path;
+    }
+    let hash = hash_content(format!("{}\n{name}", self.styles_hash).as_bytes());
+    format!("{path}.{}", &hash[..HASH_LENGTH])
+  }
+
+  /// Get output path of public file, relative to public directory
+  ///
+  /// `img/logo.png` is `img/logo.0123456789.png`
+  ///
+  /// Files without extension, and files starting with `.` or `_` (such as `_headers`), are unchanged, as hosts read them by name
+  ///
+  /// Path is lowercase if `lowercase_paths` is set in config, and `file` can then have any casing
+  pub fn public_path(&self, file: &str) -> String {
+    let path = self.fingerprinted_path(file);
+    if self.lowercase {
+      path.to_lowercase()
+    } else {
+      path
+    }
+  }
+
+  /// Get output path of public file, with original casing
+  fn fingerprinted_path(&self, file: &str) -> String {
+    let hash = match self.public_hashes.get(file) {
+      Some(hash) => hash,
+      // Links can have other casing than file, when paths are lowercase
+      None if self.lowercase => match (self.public_hashes.iter())
+        .find(|(other, _)| other.to_lowercase() == file.to_lowercase())
+      {
+        Some((_, hash)) => hash,
+        None => return file.to_string(),
+      },
+      None => return file.to_string(),
+    };
+
+    let (dir, name) = match file.rsplit_once('/') {
+      Some((dir, name)) => (format!("{dir}/"), name),
+      None => (String::new(), file),
+    };
+    if name.starts_with(['.', '_']) {
+      return file.to_string();
+    }
+    match name.rsplit_once('.') {
+      Some((stem, extension)) => format!("{dir}{stem}.{}.{extension}", &hash[..HASH_LENGTH]),
+      None => file.to_string(),
+    }
+  }
+
+  /// Get output path of any asset, relative to build directory
+  ///
+  /// Paths in `public/` are resolved with `Assets::public_path`, other paths are unchanged, except for `lowercase_paths`
+  pub fn asset_path(&self, path: &str) -> String {
+    let path = path.trim_start_matches('/');
+    match path.strip_prefix("public/") {
+      Some(file) => format!("public/{}", self.public_path(file)),
+      None if self.lowercase => path.to_lowercase(),
+      None => path.to_string(),
+    }
+  }
+
+  /// Create JSON map of original paths to fingerprinted paths, relative to build directory
+  ///
+  /// Returns `None` if fingerprinting is disabled
+  pub fn to_json(&self, config: &Config, styles: &FileMap) -> Option<String> {
+    if !self.enabled {
+      return None;
+    }
+
+    let mut map = BTreeMap::new();
+    // Embedded styles and files are not written
+    let styles = styles
+      .keys()
+      .filter(|name| !config.inline_only.contains(name));
+    for name in styles {
+      map.insert(
+        format!("{}.css", config.style_path(name)),
+        format!("{}.css", self.style_path(config, name)),
+      );
+    }
+    for file in self
+      .public_hashes
+      .keys()
+      .filter(|file| !config.inline_only.contains(file))
+    {
+      map.insert(
+        format!("public/{file}"),
+        format!("public/{}", self.public_path(file)),
+      );
+    }
+    serde_json::to_string_pretty(&map).ok()
+  }
+
+  /// Hash of every asset, for keys of build cache
+  pub fn fingerprint(&self) -> String {
+    format!(
+      "{} {} {} {:?}",
+      self.enabled, self.lowercase, self.styles_hash, self.public_hashes
+    )
+  }
+}
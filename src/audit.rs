@@ -0,0 +1,200 @@
+use std::{collections::BTreeMap, fmt, fs};
+
+use crate::{images::image_size, links::missing_assets, ImageFormat};
+
+/// Checks of images and assets in build, which are printed as warnings, see `Config::audit`
+///
+/// Use `AuditOptions::default()` for no checks
+///
+/// # Examples
+///
+/// ```
+/// use unreact::prelude::*;
+/// use serde_json::json;
+///
+/// fn main() -> UnreactResult<()> {
+///   let mut vfs = Vfs::new();
+///   vfs.templates.insert("index".to_string(), r#"<img src="{{>URL}}/public/missing.png" />"#.to_string());
+///
+///   let config = Config {
+///     audit: AuditOptions {
+///       missing_assets: true,
+///       ..AuditOptions::default()
+///     },
+///     ..Config::default()
+///   };
+///   let url = SiteUrl::parse("https://mysite.com")?;
+///   let mut app = Unreact::from_vfs(config, false, url, vfs);
+///   app.index("index", &json!({}))?;
+///   app.compile()?;
+///
+///   assert_eq!(
+///     app.asset_warnings(),
+///     vec![AssetWarning::MissingAsset {
+///       page: "index.html".to_string(),
+///       link: "https://mysite.com/public/missing.png".to_string(),
+///     }]
+///   );
+///
+///   Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AuditOptions {
+  /// Largest width or height of an image, in pixels
+  ///
+  /// Default: `None`
+  pub max_dimension: Option<u32>,
+  /// Largest size of an image file, in bytes
+  ///
+  /// Default: `None`
+  pub max_bytes: Option<u64>,
+  /// If PNG, JPEG, and GIF images without a WebP or AVIF copy (such as `img/cat.webp` for `img/cat.png`) are reported
+  ///
+  /// Default: `false`
+  pub modern_formats: bool,
+  /// If `src` and `srcset` attributes of pages which point to no file in build are reported
+  ///
+  /// Only exact files are found, unlike `Config::check_links`, which also finds pages without `.html`
+  ///
+  /// Default: `false`
+  pub missing_assets: bool,
+}
+
+impl AuditOptions {
+  /// If any checks are enabled
+  pub(crate) fn is_enabled(&self) -> bool {
+    self.max_dimension.is_some()
+      || self.max_bytes.is_some()
+      || self.modern_formats
+      || self.missing_assets
+  }
+}
+
+/// Issue with an image or asset in build, found by checks of `Config::audit`
+///
+/// Paths are relative to build directory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AssetWarning {
+  /// Image is wider or taller than `max_dimension`
+  LargeDimensions {
+    /// Path of image
+    path: String,
+    /// Width in pixels
+    width: u32,
+    /// Height in pixels
+    height: u32,
+  },
+  /// Image file is bigger than `max_bytes`
+  LargeFile {
+    /// Path of image
+    path: String,
+    /// Size of file in bytes
+    bytes: u64,
+  },
+  /// Image has no WebP or AVIF copy, with `modern_formats`
+  NoModernFormat {
+    /// Path of image
+    path: String,
+  },
+  /// Page links to an asset which is not in build, with `missing_assets`
+  MissingAsset {
+    /// Output file of page, such as `blog/post.html`
+    page: String,
+    /// Link as written in html
+    link: String,
+  },
+}
+
+impl fmt::Display for AssetWarning {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      AssetWarning::LargeDimensions {
+        path,
+        width,
+        height,
+      } => write!(f, "Image '{path}' is {width}x{height} pixels"),
+      AssetWarning::LargeFile { path, bytes } => {
+        write!(f, "Image '{path}' is {:.1} KB", *bytes as f64 / 1000.0)
+      }
+      AssetWarning::NoModernFormat { path } => {
+        write!(f, "Image '{path}' has no WebP or AVIF copy")
+      }
+      AssetWarning::MissingAsset { page, link } => {
+        write!(f, "Missing asset '{link}' in '{page}'")
+      }
+    }
+  }
+}
+
+/// Check images and pages in build with `options`
+///
+/// `images`: Path relative to build directory of each copied file, with its source file
+///
+/// `pages`: Output file and html of each page
+///
+/// `base_url`: Url of site (or dev server), without trailing slash
+///
+/// `base_path`: Path of url, such as `/repo`, or empty
+pub(crate) fn audit<T>(
+  options: &AuditOptions,
+  images: &[(&str, &str)],
+  pages: &[(&str, &str)],
+  outputs: &BTreeMap<String, T>,
+  base_url: &str,
+  base_path: &str,
+) -> Vec<AssetWarning> {
+  let mut warnings = Vec::new();
+
+  for (path, source) in images {
+    let Some(format) = ImageFormat::from_path(path) else {
+      continue;
+    };
+
+    if let Some(max) = options.max_dimension {
+      let size = fs::read(source).ok().and_then(|image| image_size(&image));
+      if let Some((width, height)) = size.filter(|(width, height)| *width > max || *height > max) {
+        warnings.push(AssetWarning::LargeDimensions {
+          path: path.to_string(),
+          width,
+          height,
+        });
+      }
+    }
+
+    if let Some(max) = options.max_bytes {
+      let bytes = fs::metadata(source).map_or(0, |metadata| metadata.len());
+      if bytes > max {
+        warnings.push(AssetWarning::LargeFile {
+          path: path.to_string(),
+          bytes,
+        });
+      }
+    }
+
+    if options.modern_formats && format != ImageFormat::WebP {
+      let stem = path.rsplit_once('.').map_or(*path, |(stem, _)| stem);
+      let has_copy = ["webp", "avif"]
+        .iter()
+        .any(|extension| outputs.contains_key(&format!("{stem}.{extension}")));
+      if !has_copy {
+        warnings.push(AssetWarning::NoModernFormat {
+          path: path.to_string(),
+        });
+      }
+    }
+  }
+
+  if options.missing_assets {
+    for (page, html) in pages {
+      for link in missing_assets(html, page, outputs, base_url, base_path) {
+        warnings.push(AssetWarning::MissingAsset {
+          page: page.to_string(),
+          link: link.to_string(),
+        });
+      }
+    }
+  }
+
+  warnings
+}
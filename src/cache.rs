@@ -0,0 +1,236 @@
+use handlebars::Handlebars;
+use std::{
+  collections::{HashMap, HashSet},
+  fmt, fs,
+  path::Path,
+  sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc, Mutex, PoisonError, RwLock, RwLockReadGuard,
+  },
+};
+
+use crate::{hash_content, list_files, UnreactError, UnreactResult};
+
+/// Amount of cache hits and misses in a build, see `Config::cache`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+  /// Pages rendered, taken from cache
+  pub render_hits: u64,
+  /// Pages rendered, not found in cache
+  pub render_misses: u64,
+  /// Pages minified or formatted, taken from cache
+  pub output_hits: u64,
+  /// Pages minified or formatted, not found in cache
+  pub output_misses: u64,
+  /// Unused entries removed from cache after build
+  pub removed: u64,
+}
+
+impl fmt::Display for CacheStats {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "Cache: {} of {} renders, {} of {} outputs reused, {} unused entries removed",
+      self.render_hits,
+      self.render_hits + self.render_misses,
+      self.output_hits,
+      self.output_hits + self.output_misses,
+      self.removed,
+    )
+  }
+}
+
+/// Kind of cached content, with separate statistics
+#[derive(Clone, Copy)]
+pub(crate) enum CacheKind {
+  /// Rendered template
+  Render,
+  /// Minified or formatted page
+  Output,
+}
+
+/// Directory of cached content, keyed by hash of everything the content depends on
+///
+/// Can be kept between builds on different machines, such as with a CI cache
+#[derive(Debug, Default)]
+pub(crate) struct BuildCache {
+  /// Directory of cache entries
+  dir: String,
+  /// Keys read or written in this build, so others can be removed
+  used: Mutex<HashSet<String>>,
+  render_hits: AtomicU64,
+  render_misses: AtomicU64,
+  output_hits: AtomicU64,
+  output_misses: AtomicU64,
+  removed: AtomicU64,
+}
+
+impl BuildCache {
+  /// Use cache in directory
+  ///
+  /// Directory is created when first entry is written
+  pub fn new(dir: &str) -> Self {
+    BuildCache {
+      dir: dir.to_string(),
+      ..BuildCache::default()
+    }
+  }
+
+  /// Get key of entry, as hash of every part the content depends on
+  pub fn key(kind: CacheKind, parts: &[&str]) -> String {
+    let prefix = match kind {
+      CacheKind::Render => "render",
+      CacheKind::Output => "output",
+    };
+
+    // Length of each part is included, so parts cannot run together
+    let mut input = format!("{prefix}\n{}\n", env!("CARGO_PKG_VERSION"));
+    for part in parts {
+      input.push_str(&format!("{}\n{part}\n", part.len()));
+    }
+    format!("{prefix}-{}", hash_content(input.as_bytes()))
+  }
+
+  /// Get cached content, or create and cache it if not found
+  pub fn get_or_insert(
+    &self,
+    kind: CacheKind,
+    key: String,
+    create: impl FnOnce() -> UnreactResult<String>,
+  ) -> UnreactResult<String> {
+    let path = format!("./{}/{key}", self.dir);
+    self
+      .used
+      .lock()
+      .unwrap_or_else(PoisonError::into_inner)
+      .insert(key);
+
+    let (hits, misses) = match kind {
+      CacheKind::Render => (&self.render_hits, &self.render_misses),
+      CacheKind::Output => (&self.output_hits, &self.output_misses),
+    };
+
+    // Unreadable entry is treated as missing
+    if let Ok(content) = fs::read_to_string(&path) {
+      hits.fetch_add(1, Ordering::Relaxed);
+      return Ok(content);
+    }
+    misses.fetch_add(1, Ordering::Relaxed);
+
+    let content = create()?;
+    if let Err(err) = fs::create_dir_all(&self.dir) {
+      return Err(UnreactError::IoError(err, self.dir.to_string()));
+    }
+    if let Err(err) = fs::write(&path, &content) {
+      return Err(UnreactError::IoError(err, path));
+    }
+    Ok(content)
+  }
+
+  /// Remove entries which were not used since cache was created, so cache does not grow forever
+  ///
+  /// Other files in directory are kept
+  pub fn remove_unused(&self) -> UnreactResult<()> {
+    if !Path::new(&self.dir).is_dir() {
+      return Ok(());
+    }
+
+    let used = self.used.lock().unwrap_or_else(PoisonError::into_inner);
+    for file in list_files(&self.dir)? {
+      let is_entry = file.starts_with("render-") || file.starts_with("output-");
+      if is_entry && !file.contains('/') && !used.contains(&file) {
+        let path = format!("./{}/{file}", self.dir);
+        if let Err(err) = fs::remove_file(&path) {
+          return Err(UnreactError::IoError(err, path));
+        }
+        self.removed.fetch_add(1, Ordering::Relaxed);
+      }
+    }
+    Ok(())
+  }
+
+  /// Get statistics since cache was created
+  pub fn stats(&self) -> CacheStats {
+    CacheStats {
+      render_hits: self.render_hits.load(Ordering::Relaxed),
+      render_misses: self.render_misses.load(Ordering::Relaxed),
+      output_hits: self.output_hits.load(Ordering::Relaxed),
+      output_misses: self.output_misses.load(Ordering::Relaxed),
+      removed: self.removed.load(Ordering::Relaxed),
+    }
+  }
+}
+
+/// Compiled templates, which every `Unreact` with the same templates and config shares, see `Unreact::share_templates`
+///
+/// Cloning shares the same templates. Templates are parsed once for each different set of templates, partials, and config
+#[derive(Clone, Default)]
+pub struct TemplateCache {
+  /// Registries with every template and partial registered, by fingerprint of templates and config
+  registries: Arc<RwLock<HashMap<String, Arc<Handlebars<'static>>>>>,
+}
+
+impl fmt::Debug for TemplateCache {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("TemplateCache")
+      .field("len", &self.len())
+      .finish()
+  }
+}
+
+impl TemplateCache {
+  /// Create empty cache
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Amount of different sets of compiled templates
+  pub fn len(&self) -> usize {
+    self.read().len()
+  }
+
+  /// If no templates are compiled yet
+  pub fn is_empty(&self) -> bool {
+    self.read().is_empty()
+  }
+
+  /// Remove every compiled set of templates, such as after templates change
+  pub fn clear(&self) {
+    self
+      .registries
+      .write()
+      .unwrap_or_else(PoisonError::into_inner)
+      .clear();
+  }
+
+  /// Get registry with key, or create and insert it
+  pub(crate) fn get_or_insert(
+    &self,
+    key: &str,
+    create: impl FnOnce() -> UnreactResult<Handlebars<'static>>,
+  ) -> UnreactResult<Arc<Handlebars<'static>>> {
+    if let Some(reg) = self.read().get(key) {
+      return Ok(reg.clone());
+    }
+
+    let mut registries = self
+      .registries
+      .write()
+      .unwrap_or_else(PoisonError::into_inner);
+    // Could have been created by another thread while waiting for lock
+    if let Some(reg) = registries.get(key) {
+      return Ok(reg.clone());
+    }
+    let reg = Arc::new(create()?);
+    registries.insert(key.to_string(), reg.clone());
+    Ok(reg)
+  }
+
+  /// Lock registries to read
+  fn read(&self) -> RwLockReadGuard<'_, HashMap<String, Arc<Handlebars<'static>>>> {
+    self
+      .registries
+      .read()
+      .unwrap_or_else(PoisonError::into_inner)
+  }
+}
@@ -0,0 +1,88 @@
+use serde_json::Value;
+
+use crate::{FeedItem, FeedOptions};
+
+/// Feed and sitemap of a collection, written to directory of its pages, see `Unreact::collection_with`
+///
+/// Default: No feed or sitemap
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CollectionOptions {
+  /// Directory of pages of collection, such as `"blog"`
+  ///
+  /// Default: `""` (Root of site)
+  pub path: String,
+  /// Options of feed of collection, written to `<path>/feed.xml`, or `None` for no feed
+  ///
+  /// Each item is an object with `title`, `link` (or `path`, of page of item), `date`, and optional `summary`, see `FeedItem`. Items are listed in order of collection.
+  /// `link` of options is `path` if empty
+  ///
+  /// Default: `None`
+  pub feed: Option<FeedOptions>,
+  /// If pages in `path` are written to `<path>/sitemap.xml` instead of `sitemap.xml`, if `sitemap` is set in config
+  ///
+  /// `sitemap.xml` becomes a sitemap index, listing `sitemap-pages.xml` (every other page) and the sitemap of each collection.
+  /// Ignored for root of site
+  ///
+  /// Default: `false`
+  pub sitemap: bool,
+}
+
+impl CollectionOptions {
+  /// Get directory of collection, without slashes
+  pub(crate) fn dir(&self) -> &str {
+    self.path.trim_matches('/')
+  }
+
+  /// Get path of file in directory of collection, such as `blog/feed.xml`
+  pub(crate) fn file(&self, name: &str) -> String {
+    match self.dir() {
+      "" => name.to_string(),
+      dir => format!("{dir}/{name}"),
+    }
+  }
+
+  /// If collection has its own sitemap
+  pub(crate) fn has_sitemap(&self) -> bool {
+    self.sitemap && !self.dir().is_empty()
+  }
+
+  /// Get options of feed, with `path` as `link` if not set
+  pub(crate) fn feed_options(&self) -> Option<FeedOptions> {
+    let mut options = self.feed.clone()?;
+    if options.link.is_empty() {
+      options.link = self.dir().to_string();
+    }
+    Some(options)
+  }
+}
+
+/// File name of feed of each collection
+pub(crate) const COLLECTION_FEED_FILE: &str = "feed.xml";
+
+/// File name of sitemap of each collection, and of sitemap index
+pub(crate) const COLLECTION_SITEMAP_FILE: &str = "sitemap.xml";
+
+/// File name of sitemap of pages in no collection, when collections have sitemaps
+pub(crate) const PAGES_SITEMAP_FILE: &str = "sitemap-pages.xml";
+
+/// Get feed items from items of collection
+///
+/// Returns reason if an item has no `title`, `link` or `path`, or `date`
+pub(crate) fn feed_items(name: &str, items: &[Value]) -> Result<Vec<FeedItem>, String> {
+  (items.iter().enumerate())
+    .map(|(i, item)| {
+      let text = |keys: &[&str]| {
+        (keys.iter())
+          .find_map(|key| item.get(key).and_then(Value::as_str))
+          .map(str::to_string)
+      };
+      let missing = |key: &str| format!("Item {i} of collection `{name}` has no `{key}` for feed");
+      Ok(FeedItem {
+        title: text(&["title"]).ok_or_else(|| missing("title"))?,
+        link: text(&["link", "path"]).ok_or_else(|| missing("link"))?,
+        date: text(&["date"]).ok_or_else(|| missing("date"))?,
+        summary: text(&["summary"]),
+      })
+    })
+    .collect()
+}
@@ -0,0 +1,542 @@
+use serde_json::Value;
+use std::{
+  fs,
+  path::Path,
+  time::{Duration, SystemTime},
+};
+
+use crate::{
+  date::DateTime, front_matter::parse_file, workspace::manifest_path, Config, DirectoryMode, Host,
+  LinkCheck, PathNormalization, PermissionMode, TimestampMode, UnreactError, UnreactResult,
+};
+
+/// Top-level keys of config file, with a single value
+const KEYS: &[&str] = &[
+  "url",
+  "build",
+  "github_base_path",
+  "base_path",
+  "relative_links",
+  "clean",
+  "templates",
+  "public",
+  "styles",
+  "content",
+  "repository",
+  "repository_branch",
+  "repository_dir",
+  "data",
+  "locales",
+  "styles_output",
+  "flatten_styles",
+  "page_styles",
+  "inline_only",
+  "ignore_files",
+  "dev_warning",
+  "lazy_templates",
+  "dev_build",
+  "dev_port",
+  "manifest_dir",
+  "dev_directories",
+  "dev_log",
+  "minify",
+  "host",
+  "manifest",
+  "build_manifest",
+  "cname",
+  "sitemap",
+  "build_date",
+  "publish_schedule",
+  "partial_docs",
+  "lockfile",
+  "locked",
+  "threads",
+  "normalize_paths",
+  "lowercase_paths",
+  "fingerprint_assets",
+  "check_paths",
+  "check_links",
+  "skip_write_errors",
+  "verify_output",
+  "cache",
+  "cache_version",
+  "profile",
+];
+
+/// Prefixes of dotted keys, for nested options
+const PREFIXES: &[&str] = &[
+  "style_paths",
+  "format",
+  "head",
+  "markdown",
+  "public_copy",
+  "limits",
+  "images",
+  "audit",
+];
+
+impl Config {
+  /// Read config from a TOML (`.toml`) or YAML (`.yaml`, `.yml`) file, such as `unreact.toml`
+  ///
+  /// Keys are the names of fields of `Config`. Missing keys use the default value
+  ///
+  /// Fields of nested options are set with tables (`[markdown]` in TOML, or indented keys in YAML), or with dotted keys, such as `markdown.tables = false` or `style_paths.main = "css/site"`.
+  /// Lists can be written over several lines. Inline tables (`{ a = 1 }`) and multi-line strings are not supported
+  ///
+  /// `host` is one of `"generic"`, `"github-pages"`, or `"netlify"`. `Host::Custom`, `head.meta`, and `targets` must be set in code
+  ///
+  /// Returns error if file contains an unknown key, or a value of the wrong type
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use unreact::prelude::*;
+  ///
+  /// fn main() -> UnreactResult<()> {
+  ///   let path = std::env::temp_dir().join("unreact-config-example.toml");
+  ///   std::fs::write(&path, r#"
+  ///     url = "https://mysite.com"
+  ///     build = "dist"
+  ///     minify = false
+  ///     host = "netlify"
+  ///     inline_only = [
+  ///       "critical",
+  ///     ]
+  ///
+  ///     [markdown]
+  ///     tables = false
+  ///
+  ///     [style_paths]
+  ///     main = "css/site"
+  ///   "#).unwrap();
+  ///
+  ///   let config = Config::from_file(path.to_str().unwrap())?;
+  ///   assert_eq!(config.url.as_deref(), Some("https://mysite.com"));
+  ///   assert_eq!(config.build, "dist");
+  ///   assert_eq!(config.templates, "templates");
+  ///   assert_eq!(config.host, Host::Netlify);
+  ///   assert!(!config.minify);
+  ///   assert_eq!(config.inline_only, ["critical"]);
+  ///   assert!(!config.markdown.tables);
+  ///   assert_eq!(config.style_paths["main"], "css/site");
+  ///
+  ///   // Unknown keys are an error
+  ///   std::fs::write(&path, "biuld = \"dist\"").unwrap();
+  ///   assert!(Config::from_file(path.to_str().unwrap()).is_err());
+  ///
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn from_file(path: &str) -> UnreactResult<Self> {
+    let is_toml = match Path::new(path).extension().and_then(|x| x.to_str()) {
+      Some("toml") => true,
+      Some("yaml" | "yml") => false,
+      _ => {
+        return Err(UnreactError::ConfigFail(
+          path.to_string(),
+          "File must have extension `.toml`, `.yaml`, or `.yml`".to_string(),
+        ))
+      }
+    };
+
+    // Relative to crate manifest, if not in current directory
+    let content = match fs::read_to_string(manifest_path(path)) {
+      Ok(content) => content,
+      Err(err) => return Err(UnreactError::IoError(err, path.to_string())),
+    };
+
+    let fail = |reason: String| UnreactError::ConfigFail(path.to_string(), reason);
+    let Value::Object(data) = parse_file(&content, is_toml).map_err(fail)? else {
+      return Err(fail("File must be keys, not a list".to_string()));
+    };
+
+    let mut config = Config::default();
+    for (key, value) in data {
+      set_nested(&mut config, key, value).map_err(fail)?;
+    }
+    Ok(config)
+  }
+}
+
+/// Set fields of config from key of config file, with tables of nested options as dotted keys
+fn set_nested(config: &mut Config, key: String, value: Value) -> Result<(), String> {
+  match value {
+    Value::Object(map) => {
+      for (field, value) in map {
+        set_nested(config, format!("{key}.{field}"), value)?;
+      }
+      Ok(())
+    }
+    value => set_option(config, &key, value),
+  }
+}
+
+/// Set field of config from key of config file
+fn set_option(config: &mut Config, key: &str, value: Value) -> Result<(), String> {
+  // Dotted key of nested option
+  if let Some((prefix, field)) = key.split_once('.') {
+    return match prefix {
+      "style_paths" => {
+        config
+          .style_paths
+          .insert(field.to_string(), string(key, value)?);
+        Ok(())
+      }
+      "format" => set_format(config, key, field, value),
+      "head" => set_head(config, key, field, value),
+      "markdown" => set_markdown(config, key, field, value),
+      "public_copy" => set_public_copy(config, key, field, value),
+      "limits" => set_limits(config, key, field, value),
+      "images" => set_images(config, key, field, value),
+      "audit" => set_audit(config, key, field, value),
+      _ => Err(unknown_key(key)),
+    };
+  }
+
+  match key {
+    "url" => config.url = optional_string(key, value)?,
+    "build" => config.build = string(key, value)?,
+    "github_base_path" => config.github_base_path = boolean(key, value)?,
+    "base_path" => config.base_path = optional_string(key, value)?,
+    "relative_links" => config.relative_links = boolean(key, value)?,
+    "clean" => config.clean = boolean(key, value)?,
+    "templates" => config.templates = string(key, value)?,
+    "public" => config.public = string(key, value)?,
+    "styles" => config.styles = string(key, value)?,
+    "content" => config.content = string(key, value)?,
+    "repository" => config.repository = optional_string(key, value)?,
+    "repository_branch" => config.repository_branch = string(key, value)?,
+    "repository_dir" => config.repository_dir = string(key, value)?,
+    "data" => config.data = string(key, value)?,
+    "locales" => config.locales = string(key, value)?,
+    "styles_output" => config.styles_output = string(key, value)?,
+    "flatten_styles" => config.flatten_styles = boolean(key, value)?,
+    "page_styles" => config.page_styles = boolean(key, value)?,
+    "inline_only" => config.inline_only = string_list(key, value)?,
+    "ignore_files" => config.ignore_files = boolean(key, value)?,
+    "dev_warning" => config.dev_warning = boolean(key, value)?,
+    "lazy_templates" => config.lazy_templates = boolean(key, value)?,
+    "dev_build" => config.dev_build = string(key, value)?,
+    "dev_port" => {
+      config.dev_port = match value.as_u64().and_then(|x| u16::try_from(x).ok()) {
+        Some(port) => port,
+        None => return Err(wrong_type(key, "a port number", &value)),
+      }
+    }
+    "manifest_dir" => config.manifest_dir = boolean(key, value)?,
+    "dev_directories" => {
+      config.dev_directories = match string(key, value)?.as_str() {
+        "redirect" => DirectoryMode::Redirect,
+        "serve" => DirectoryMode::Serve,
+        "list" => DirectoryMode::List,
+        other => {
+          return Err(format!(
+            "Key `dev_directories` must be one of `redirect`, `serve`, or `list`, not `{other}`"
+          ))
+        }
+      }
+    }
+    "dev_log" => config.dev_log = boolean(key, value)?,
+    "minify" => config.minify = boolean(key, value)?,
+    "manifest" => config.manifest = boolean(key, value)?,
+    "build_manifest" => config.build_manifest = boolean(key, value)?,
+    "cname" => config.cname = optional_string(key, value)?,
+    "sitemap" => config.sitemap = boolean(key, value)?,
+    "build_date" => {
+      let seconds = match value {
+        Value::Null => {
+          config.build_date = None;
+          return Ok(());
+        }
+        Value::Number(_) => integer(key, value)? as u64,
+        value => {
+          let date = DateTime::parse(&string(key, value)?)?;
+          u64::try_from(date.timestamp()).map_err(|_| format!("Key `{key}` must be after 1970"))?
+        }
+      };
+      config.build_date = Some(SystemTime::UNIX_EPOCH + Duration::from_secs(seconds));
+    }
+    "threads" => config.threads = integer(key, value)?,
+    "normalize_paths" => {
+      config.normalize_paths = match string(key, value)?.as_str() {
+        "keep" => PathNormalization::Keep,
+        "nfc" => PathNormalization::Nfc,
+        "nfd" => PathNormalization::Nfd,
+        "ascii" => PathNormalization::Ascii,
+        other => {
+          return Err(format!(
+            "Key `normalize_paths` must be one of `keep`, `nfc`, `nfd`, or `ascii`, not `{other}`"
+          ))
+        }
+      }
+    }
+    "lowercase_paths" => config.lowercase_paths = boolean(key, value)?,
+    "fingerprint_assets" => config.fingerprint_assets = boolean(key, value)?,
+    "check_paths" => config.check_paths = boolean(key, value)?,
+    "check_links" => {
+      config.check_links = match string(key, value)?.as_str() {
+        "off" => LinkCheck::Off,
+        "warn" => LinkCheck::Warn,
+        "fail" => LinkCheck::Fail,
+        other => {
+          return Err(format!(
+            "Key `check_links` must be one of `off`, `warn`, or `fail`, not `{other}`"
+          ))
+        }
+      }
+    }
+    "skip_write_errors" => config.skip_write_errors = boolean(key, value)?,
+    "verify_output" => config.verify_output = boolean(key, value)?,
+    "cache" => config.cache = optional_string(key, value)?,
+    "publish_schedule" => config.publish_schedule = optional_string(key, value)?,
+    "partial_docs" => config.partial_docs = optional_string(key, value)?,
+    "lockfile" => config.lockfile = optional_string(key, value)?,
+    "locked" => config.locked = boolean(key, value)?,
+    "cache_version" => config.cache_version = string(key, value)?,
+    "profile" => config.profile = boolean(key, value)?,
+
+    "host" => {
+      config.host = match string(key, value)?.as_str() {
+        "generic" => Host::Generic,
+        "github-pages" => Host::GitHubPages,
+        "netlify" => Host::Netlify,
+        other => {
+          return Err(format!(
+            "Key `host` must be one of `generic`, `github-pages`, or `netlify`, not `{other}`"
+          ))
+        }
+      }
+    }
+
+    _ => return Err(unknown_key(key)),
+  }
+  Ok(())
+}
+
+/// Set field of `Config::format`
+fn set_format(config: &mut Config, key: &str, field: &str, value: Value) -> Result<(), String> {
+  let format = &mut config.format;
+  match field {
+    "indent" => format.indent = optional_integer(key, value)?,
+    "trim_trailing_whitespace" => format.trim_trailing_whitespace = boolean(key, value)?,
+    "collapse_blank_lines" => format.collapse_blank_lines = boolean(key, value)?,
+    "final_newline" => format.final_newline = boolean(key, value)?,
+    _ => return Err(unknown_field(key)),
+  }
+  Ok(())
+}
+
+/// Set field of `Config::head`
+fn set_head(config: &mut Config, key: &str, field: &str, value: Value) -> Result<(), String> {
+  let head = &mut config.head;
+  match field {
+    "title" => head.title = string(key, value)?,
+    "title_separator" => head.title_separator = string(key, value)?,
+    "description" => head.description = optional_string(key, value)?,
+    "charset" => head.charset = string(key, value)?,
+    "viewport" => head.viewport = string(key, value)?,
+    _ => return Err(unknown_field(key)),
+  }
+  Ok(())
+}
+
+/// Set field of `Config::markdown`
+fn set_markdown(config: &mut Config, key: &str, field: &str, value: Value) -> Result<(), String> {
+  let markdown = &mut config.markdown;
+  let value = boolean(key, value)?;
+  match field {
+    "tables" => markdown.tables = value,
+    "task_lists" => markdown.task_lists = value,
+    "footnotes" => markdown.footnotes = value,
+    "strikethrough" => markdown.strikethrough = value,
+    "autolinks" => markdown.autolinks = value,
+    "heading_ids" => markdown.heading_ids = value,
+    _ => return Err(unknown_field(key)),
+  }
+  Ok(())
+}
+
+/// Set field of `Config::limits`
+///
+/// `time` is in seconds, and `null` or `false` removes a limit
+fn set_limits(config: &mut Config, key: &str, field: &str, value: Value) -> Result<(), String> {
+  let limits = &mut config.limits;
+  match field {
+    "time" => {
+      limits.time = match value {
+        Value::Null | Value::Bool(false) => None,
+        value => match value.as_f64() {
+          Some(seconds) if seconds >= 0.0 && seconds.is_finite() => {
+            Some(Duration::from_secs_f64(seconds))
+          }
+          _ => return Err(wrong_type(key, "a non-negative number of seconds", &value)),
+        },
+      }
+    }
+    "output_size" => limits.output_size = optional_integer(key, value)?,
+    "partial_depth" => limits.partial_depth = optional_integer(key, value)?,
+    _ => return Err(unknown_field(key)),
+  }
+  Ok(())
+}
+
+/// Set field of `Config::images`
+///
+/// `widths` is a list of integers, or a single integer
+fn set_images(config: &mut Config, key: &str, field: &str, value: Value) -> Result<(), String> {
+  let images = &mut config.images;
+  match field {
+    "widths" => {
+      let values = match value {
+        Value::Array(values) => values,
+        value => vec![value],
+      };
+      images.widths = (values.into_iter())
+        .map(
+          |value| match value.as_u64().and_then(|x| u32::try_from(x).ok()) {
+            Some(width) => Ok(width),
+            None => Err(wrong_type(key, "a list of widths in pixels", &value)),
+          },
+        )
+        .collect::<Result<_, _>>()?;
+    }
+    "compress" => images.compress = boolean(key, value)?,
+    "webp" => images.webp = boolean(key, value)?,
+    "dir" => images.dir = string(key, value)?,
+    _ => return Err(unknown_field(key)),
+  }
+  Ok(())
+}
+
+/// Set field of `Config::audit`
+fn set_audit(config: &mut Config, key: &str, field: &str, value: Value) -> Result<(), String> {
+  let audit = &mut config.audit;
+  match field {
+    "max_dimension" => {
+      audit.max_dimension = optional_integer(key, value)?.map(|x| x.min(u32::MAX as usize) as u32)
+    }
+    "max_bytes" => audit.max_bytes = optional_integer(key, value)?.map(|x| x as u64),
+    "modern_formats" => audit.modern_formats = boolean(key, value)?,
+    "missing_assets" => audit.missing_assets = boolean(key, value)?,
+    _ => return Err(unknown_field(key)),
+  }
+  Ok(())
+}
+
+/// Set field of `Config::public_copy`
+///
+/// `timestamps` is `"now"`, `"preserve"`, or seconds since Unix epoch
+fn set_public_copy(
+  config: &mut Config,
+  key: &str,
+  field: &str,
+  value: Value,
+) -> Result<(), String> {
+  let public_copy = &mut config.public_copy;
+  match field {
+    "permissions" => {
+      public_copy.permissions = match string(key, value)?.as_str() {
+        "preserve" => PermissionMode::Preserve,
+        "normalize" => PermissionMode::Normalize,
+        other => {
+          return Err(format!(
+            "Key `{key}` must be `preserve` or `normalize`, not `{other}`"
+          ))
+        }
+      }
+    }
+    "timestamps" => {
+      public_copy.timestamps = match value {
+        Value::Number(_) => {
+          let seconds = integer(key, value)? as u64;
+          TimestampMode::Fixed(SystemTime::UNIX_EPOCH + Duration::from_secs(seconds))
+        }
+        value => match string(key, value)?.as_str() {
+          "now" => TimestampMode::Now,
+          "preserve" => TimestampMode::Preserve,
+          other => {
+            return Err(format!(
+              "Key `{key}` must be `now`, `preserve`, or seconds since Unix epoch, not `{other}`"
+            ))
+          }
+        },
+      }
+    }
+    _ => return Err(unknown_field(key)),
+  }
+  Ok(())
+}
+
+/// Error message for key which is not an option, with list of valid keys
+fn unknown_key(key: &str) -> String {
+  let prefixes = PREFIXES
+    .iter()
+    .map(|prefix| format!("{prefix}.*"))
+    .collect::<Vec<_>>();
+  format!(
+    "Unknown key `{key}`, expected one of: {}, {}",
+    KEYS.join(", "),
+    prefixes.join(", "),
+  )
+}
+
+/// Error message for dotted key which is not a field of nested option
+fn unknown_field(key: &str) -> String {
+  let (prefix, field) = key.split_once('.').unwrap_or((key, ""));
+  format!("Unknown key `{key}`, `{prefix}` has no option `{field}`")
+}
+
+/// Get string value
+fn string(key: &str, value: Value) -> Result<String, String> {
+  match value {
+    Value::String(value) => Ok(value),
+    // Unquoted YAML numbers are read as numbers
+    Value::Number(value) => Ok(value.to_string()),
+    other => Err(wrong_type(key, "a string", &other)),
+  }
+}
+
+/// Get string value, or `None` for null or `false`
+fn optional_string(key: &str, value: Value) -> Result<Option<String>, String> {
+  match value {
+    Value::Null | Value::Bool(false) => Ok(None),
+    value => string(key, value).map(Some),
+  }
+}
+
+/// Get list of strings, or a single string as a list
+fn string_list(key: &str, value: Value) -> Result<Vec<String>, String> {
+  match value {
+    Value::Array(values) => values.into_iter().map(|value| string(key, value)).collect(),
+    value => string(key, value).map(|value| vec![value]),
+  }
+}
+
+/// Get boolean value
+fn boolean(key: &str, value: Value) -> Result<bool, String> {
+  match value {
+    Value::Bool(value) => Ok(value),
+    other => Err(wrong_type(key, "`true` or `false`", &other)),
+  }
+}
+
+/// Get non-negative integer value
+fn integer(key: &str, value: Value) -> Result<usize, String> {
+  match value.as_u64().and_then(|x| usize::try_from(x).ok()) {
+    Some(value) => Ok(value),
+    None => Err(wrong_type(key, "a non-negative integer", &value)),
+  }
+}
+
+/// Get non-negative integer value, or `None` for null or `false`
+fn optional_integer(key: &str, value: Value) -> Result<Option<usize>, String> {
+  match value {
+    Value::Null | Value::Bool(false) => Ok(None),
+    value => integer(key, value).map(Some),
+  }
+}
+
+/// Error message for value of wrong type
+fn wrong_type(key: &str, expected: &str, value: &Value) -> String {
+  format!("Key `{key}` must be {expected}, not `{value}`")
+}
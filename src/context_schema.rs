@@ -0,0 +1,74 @@
+use serde_json::{Map, Value};
+
+/// Add shape of `value` to JSON schema, as union with any shapes already added
+///
+/// Records `type` of every value, `properties` of objects, and `items` of arrays
+pub(crate) fn merge_schema(schema: &mut Map<String, Value>, value: &Value) {
+  add_type(schema, type_name(value));
+
+  match value {
+    Value::Object(map) => {
+      let properties = schema
+        .entry("properties")
+        .or_insert_with(|| Value::Object(Map::new()));
+      if let Value::Object(properties) = properties {
+        for (key, value) in map {
+          let property = properties
+            .entry(key)
+            .or_insert_with(|| Value::Object(Map::new()));
+          if let Value::Object(property) = property {
+            merge_schema(property, value);
+          }
+        }
+      }
+    }
+
+    Value::Array(list) => {
+      for item in list {
+        let items = schema
+          .entry("items")
+          .or_insert_with(|| Value::Object(Map::new()));
+        if let Value::Object(items) = items {
+          merge_schema(items, item);
+        }
+      }
+    }
+
+    _ => (),
+  }
+}
+
+/// Add type to `type` of schema
+///
+/// Single type is a string, multiple types are a list
+fn add_type(schema: &mut Map<String, Value>, name: &str) {
+  let name = Value::String(name.to_string());
+  match schema.get_mut("type") {
+    None => {
+      schema.insert("type".to_string(), name);
+    }
+    Some(Value::Array(types)) => {
+      if !types.contains(&name) {
+        types.push(name);
+      }
+    }
+    Some(existing) => {
+      if *existing != name {
+        *existing = Value::Array(vec![existing.take(), name]);
+      }
+    }
+  }
+}
+
+/// Get JSON schema type name of value
+fn type_name(value: &Value) -> &'static str {
+  match value {
+    Value::Null => "null",
+    Value::Bool(_) => "boolean",
+    Value::Number(x) if x.is_f64() => "number",
+    Value::Number(_) => "integer",
+    Value::String(_) => "string",
+    Value::Array(_) => "array",
+    Value::Object(_) => "object",
+  }
+}
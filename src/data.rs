@@ -0,0 +1,62 @@
+use serde_json::{Map, Value};
+use std::{fs, path::Path};
+
+use crate::{front_matter::parse_file, list_files, UnreactError, UnreactResult};
+
+/// Read every data file in directory, see `Config::data`
+///
+/// `./data/nav.json` is key `nav`, and `./data/authors/main.yaml` is key `main` of object `authors`.
+/// Files with other extensions are ignored
+///
+/// Missing directory is treated as empty
+///
+/// Returns error if a file cannot be parsed, or two files have the same key
+pub(crate) fn load_data(dir: &str) -> UnreactResult<Map<String, Value>> {
+  let mut data = Map::new();
+  if !Path::new(&format!("./{dir}")).is_dir() {
+    return Ok(data);
+  }
+
+  for file in list_files(dir)? {
+    let Some((name, extension)) = file.rsplit_once('.') else {
+      continue;
+    };
+    if !matches!(extension, "json" | "yaml" | "yml" | "toml") {
+      continue;
+    }
+
+    let path = format!("./{dir}/{file}");
+    let fail = |reason: String| UnreactError::DataFail(path.to_string(), reason);
+    let content = match fs::read_to_string(&path) {
+      Ok(x) => x,
+      Err(err) => return Err(UnreactError::IoError(err, path)),
+    };
+    let value = match extension {
+      "json" => serde_json::from_str(&content).map_err(|err| fail(err.to_string()))?,
+      extension => parse_file(&content, extension == "toml").map_err(fail)?,
+    };
+
+    // Create object of each directory
+    let mut object = &mut data;
+    let mut keys = name.split('/').peekable();
+    while let Some(key) = keys.next() {
+      if keys.peek().is_none() {
+        if object.insert(key.to_string(), value).is_some() {
+          return Err(fail(format!("Key `{name}` is used by another data file")));
+        }
+        break;
+      }
+      let entry = object
+        .entry(key.to_string())
+        .or_insert_with(|| Value::Object(Map::new()));
+      let Value::Object(child) = entry else {
+        return Err(fail(format!(
+          "Directory `{key}` has the same key as a data file"
+        )));
+      };
+      object = child;
+    }
+  }
+
+  Ok(data)
+}
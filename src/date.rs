@@ -0,0 +1,206 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Date and time, with offset from UTC in minutes
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct DateTime {
+  year: i64,
+  month: u32,
+  day: u32,
+  hour: u32,
+  minute: u32,
+  second: u32,
+  offset: i64,
+}
+
+impl DateTime {
+  /// Parse `YYYY-MM-DD`, or RFC 3339 date and time
+  pub fn parse(date: &str) -> Result<Self, String> {
+    let invalid = || {
+      format!("Date `{date}` is not valid, expected `YYYY-MM-DD` or `YYYY-MM-DDTHH:MM:SS+HH:MM`")
+    };
+    let number = |text: &str| text.parse::<u32>().map_err(|_| invalid());
+
+    let date = date.trim();
+    let (day_part, time_part) = match date.find(['T', 't', ' ']) {
+      Some(i) => (&date[..i], Some(&date[i + 1..])),
+      None => (date, None),
+    };
+
+    let mut parts = day_part.splitn(3, '-');
+    let (Some(year), Some(month), Some(day)) = (parts.next(), parts.next(), parts.next()) else {
+      return Err(invalid());
+    };
+    if year.len() != 4 {
+      return Err(invalid());
+    }
+    let mut datetime = DateTime {
+      year: number(year)?.into(),
+      month: number(month)?,
+      day: number(day)?,
+      hour: 0,
+      minute: 0,
+      second: 0,
+      offset: 0,
+    };
+
+    if let Some(time) = time_part {
+      // Split offset from time
+      let (time, offset) = match time.find(['Z', 'z', '+', '-']) {
+        Some(i) => (&time[..i], &time[i..]),
+        None => (time, ""),
+      };
+
+      let mut parts = time.split(':');
+      datetime.hour = number(parts.next().unwrap_or_default())?;
+      datetime.minute = number(parts.next().ok_or_else(invalid)?)?;
+      if let Some(second) = parts.next() {
+        // Fractions of seconds are ignored
+        datetime.second = number(second.split('.').next().unwrap_or_default())?;
+      }
+
+      if let Some(rest) = offset.strip_prefix(['+', '-']) {
+        let (hours, minutes) = rest.split_once(':').ok_or_else(invalid)?;
+        let minutes = i64::from(number(hours)?) * 60 + i64::from(number(minutes)?);
+        datetime.offset = if offset.starts_with('-') {
+          -minutes
+        } else {
+          minutes
+        };
+      }
+    }
+
+    let valid = (1..=12).contains(&datetime.month)
+      && (1..=days_in_month(datetime.year, datetime.month)).contains(&datetime.day)
+      && datetime.hour < 24
+      && datetime.minute < 60
+      && datetime.second < 61;
+    if valid {
+      Ok(datetime)
+    } else {
+      Err(invalid())
+    }
+  }
+
+  /// Date and time in UTC, from seconds since Unix epoch
+  pub fn from_timestamp(timestamp: i64) -> Self {
+    let (year, month, day) = civil_from_days(timestamp.div_euclid(86400));
+    let seconds = timestamp.rem_euclid(86400) as u32;
+    DateTime {
+      year,
+      month,
+      day,
+      hour: seconds / 3600,
+      minute: seconds / 60 % 60,
+      second: seconds % 60,
+      offset: 0,
+    }
+  }
+
+  /// Seconds since Unix epoch, for comparing dates in different offsets
+  pub fn timestamp(&self) -> i64 {
+    days_from_civil(self.year, self.month, self.day) * 86400
+      + i64::from(self.hour * 3600 + self.minute * 60 + self.second)
+      - self.offset * 60
+  }
+
+  /// Format as RFC 3339, for Atom
+  pub fn rfc3339(&self) -> String {
+    let offset = if self.offset == 0 {
+      "Z".to_string()
+    } else {
+      let sign = if self.offset < 0 { '-' } else { '+' };
+      let minutes = self.offset.abs();
+      format!("{sign}{:02}:{:02}", minutes / 60, minutes % 60)
+    };
+    format!(
+      "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}{offset}",
+      self.year, self.month, self.day, self.hour, self.minute, self.second
+    )
+  }
+
+  /// Format as cron schedule of minute, hour, day, and month, which runs every year, such as `30 9 31 1 *`
+  ///
+  /// Cron schedules have no offset, so date should be in UTC
+  pub fn cron(&self) -> String {
+    format!(
+      "{} {} {} {} *",
+      self.minute, self.hour, self.day, self.month
+    )
+  }
+
+  /// Format as RFC 822, for RSS
+  pub fn rfc822(&self) -> String {
+    const WEEKDAYS: [&str; 7] = ["Thu", "Fri", "Sat", "Sun", "Mon", "Tue", "Wed"];
+    const MONTHS: [&str; 12] = [
+      "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+
+    // Unix epoch was a Thursday
+    let weekday = WEEKDAYS[days_from_civil(self.year, self.month, self.day).rem_euclid(7) as usize];
+    let sign = if self.offset < 0 { '-' } else { '+' };
+    let minutes = self.offset.abs();
+    format!(
+      "{weekday}, {:02} {} {:04} {:02}:{:02}:{:02} {sign}{:02}{:02}",
+      self.day,
+      MONTHS[self.month as usize - 1],
+      self.year,
+      self.hour,
+      self.minute,
+      self.second,
+      minutes / 60,
+      minutes % 60,
+    )
+  }
+}
+
+/// Amount of days in month of year
+fn days_in_month(year: i64, month: u32) -> u32 {
+  match month {
+    2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+    2 => 28,
+    4 | 6 | 9 | 11 => 30,
+    _ => 31,
+  }
+}
+
+/// Days since Unix epoch of date, in proleptic Gregorian calendar
+///
+/// See [Howard Hinnant's algorithm](http://howardhinnant.github.io/date_algorithms.html#days_from_civil)
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+  let year = if month <= 2 { year - 1 } else { year };
+  let era = year.div_euclid(400);
+  let year_of_era = year - era * 400;
+  let month = i64::from(month);
+  let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + i64::from(day) - 1;
+  let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+  era * 146097 + day_of_era - 719468
+}
+
+/// Date of days since Unix epoch, in proleptic Gregorian calendar, as year, month, and day
+///
+/// See [Howard Hinnant's algorithm](http://howardhinnant.github.io/date_algorithms.html#civil_from_days)
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+  let days = days + 719468;
+  let era = days.div_euclid(146097);
+  let day_of_era = days - era * 146097;
+  let year_of_era =
+    (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+  let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+  let month_index = (5 * day_of_year + 2) / 153;
+  let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+  let month = if month_index < 10 {
+    month_index + 3
+  } else {
+    month_index - 9
+  } as u32;
+  let year = year_of_era + era * 400 + i64::from(month <= 2);
+  (year, month, day)
+}
+
+/// Seconds since Unix epoch of system time, negative if before epoch
+pub(crate) fn unix_seconds(time: SystemTime) -> i64 {
+  match time.duration_since(UNIX_EPOCH) {
+    Ok(duration) => duration.as_secs() as i64,
+    Err(err) => -(err.duration().as_secs() as i64),
+  }
+}
@@ -0,0 +1,86 @@
+use std::{
+  fs::{self, File},
+  path::Path,
+  sync::atomic::{AtomicUsize, Ordering},
+};
+
+use crate::{workspace::package_name, UnreactError, UnreactResult};
+
+/// Amount of dev builds claimed by this process, so each app of a process has its own directory
+static CLAIMED: AtomicUsize = AtomicUsize::new(0);
+
+/// Lock of a dev build directory, held until app is dropped, or process ends (including a crash), see `Config::dev_build`
+#[derive(Debug)]
+pub(crate) struct DevBuildLock {
+  /// Lock file, which is unlocked by OS when closed
+  _file: File,
+}
+
+/// Claim a new dev build directory in `root`, such as `target/unreact-dev/blog-1234-0`, and remove stale dev builds
+///
+/// Returns path of directory, relative to workspace, and lock which must be kept while it is used.
+/// Directory itself is created by build
+pub(crate) fn claim_dev_build(root: &str) -> UnreactResult<(String, DevBuildLock)> {
+  let root = root.trim_end_matches('/');
+  let io_error = |err, path: &str| UnreactError::IoError(err, path.to_string());
+  fs::create_dir_all(format!("./{root}")).map_err(|err| io_error(err, root))?;
+  remove_stale(root);
+
+  let mut id = format!(
+    "{}-{}",
+    std::process::id(),
+    CLAIMED.fetch_add(1, Ordering::Relaxed)
+  );
+  // Crates of a workspace can share a dev build directory
+  if let Some(package) = package_name() {
+    id = format!("{package}-{id}");
+  }
+  let path = format!("./{root}/{id}.lock");
+  let file = loop {
+    let file = File::create(&path).map_err(|err| io_error(err, &path))?;
+    file.lock().map_err(|err| io_error(err, &path))?;
+    // Another process can remove the file as stale, after it is created and before it is locked
+    // Removing process holds its own lock until file is removed, so file is only missing if it was removed
+    if Path::new(&path).exists() {
+      break file;
+    }
+  };
+
+  // Left over from an earlier process with the same id
+  remove_build(root, &id);
+  Ok((format!("{root}/{id}"), DevBuildLock { _file: file }))
+}
+
+/// Remove dev builds in `root` which are not locked, as the process which built them has ended
+///
+/// Errors are ignored, as another process may be removing the same build
+fn remove_stale(root: &str) {
+  let Ok(entries) = fs::read_dir(format!("./{root}")) else {
+    return;
+  };
+  for entry in entries.flatten() {
+    let name = entry.file_name().to_string_lossy().to_string();
+    let Some(id) = name.strip_suffix(".lock") else {
+      continue;
+    };
+    let Ok(file) = File::open(entry.path()) else {
+      continue;
+    };
+    // Locked by a running process
+    if file.try_lock().is_err() {
+      continue;
+    }
+    remove_build(root, id);
+    fs::remove_file(entry.path()).ok();
+  }
+}
+
+/// Remove dev build directory, with staging and old directories of an interrupted build
+fn remove_build(root: &str, id: &str) {
+  for suffix in ["", ".staging", ".old"] {
+    let path = format!("./{root}/{id}{suffix}");
+    if Path::new(&path).exists() {
+      fs::remove_dir_all(path).ok();
+    }
+  }
+}
@@ -0,0 +1,107 @@
+use serde_json::Value;
+use std::{collections::BTreeMap, fmt};
+
+use crate::FileMap;
+
+/// Template language which renders pages instead of Handlebars, see `Unreact::set_engine`
+///
+/// Handlebars is used if no engine is set, and is the only engine included. Implement for another crate (such as `tera`) in the site, to use its templates.
+/// No Tera engine or `tera` feature is provided, see the README for an adapter
+///
+/// Engine should provide `Inbuilt` to templates as functions, in place of the inbuilt `URL`, `LINK`, and `STYLE` partials
+///
+/// # Examples
+///
+/// ```
+/// use unreact::prelude::*;
+/// use serde_json::{json, Value};
+///
+/// // Replaces `[[key]]` with value of key, and `[[url]]` with url of site
+/// #[derive(Debug)]
+/// struct Brackets;
+///
+/// impl TemplateEngine for Brackets {
+///   fn render(&self, name: &str, templates: &FileMap, data: &Value, inbuilt: &Inbuilt) -> Result<String, String> {
+///     let mut output = templates.get(name).ok_or("No template")?.replace("[[url]]", inbuilt.url());
+///     if let Value::Object(data) = data {
+///       for (key, value) in data {
+///         output = output.replace(&format!("[[{key}]]"), value.as_str().unwrap_or_default());
+///       }
+///     }
+///     Ok(output)
+///   }
+/// }
+///
+/// fn main() -> UnreactResult<()> {
+///   let mut vfs = Vfs::new();
+///   vfs.templates.insert("index".to_string(), "<a href=\"[[url]]\">[[msg]]</a>".to_string());
+///
+///   let url = SiteUrl::parse("https://mysite.com")?;
+///   let mut app = Unreact::from_vfs(Config::default(), false, url, vfs);
+///   app.set_engine(Brackets);
+///
+///   assert_eq!(app.render("index", &json!({"msg": "Home"}))?, "<a href=\"https://mysite.com\">Home</a>");
+///
+///   Ok(())
+/// }
+/// ```
+pub trait TemplateEngine: fmt::Debug + Send + Sync {
+  /// Render template with data
+  ///
+  /// `templates`: Every template, by name **without** extension, such as `"index"` or `"blog/post"`, for includes and layouts
+  ///
+  /// `data`: Globals, page data, `collections`, `data`, and `unreact`, as given to Handlebars templates
+  ///
+  /// Returns message of error if template cannot be rendered
+  fn render(
+    &self,
+    name: &str,
+    templates: &FileMap,
+    data: &Value,
+    inbuilt: &Inbuilt,
+  ) -> Result<String, String>;
+}
+
+/// Values of inbuilt partials, for a `TemplateEngine`
+#[derive(Debug, Clone)]
+pub struct Inbuilt {
+  /// Url of site, or of dev server in dev mode
+  url: String,
+  /// Output paths of styles, by name
+  style_paths: BTreeMap<String, String>,
+}
+
+impl Inbuilt {
+  /// Create inbuilt values
+  pub(crate) fn new(url: String, style_paths: BTreeMap<String, String>) -> Self {
+    Inbuilt { url, style_paths }
+  }
+
+  /// Base url of site, like `{{>URL}}`
+  pub fn url(&self) -> &str {
+    &self.url
+  }
+
+  /// Link to path in site, like `{{#>LINK to="..."}}...{{/LINK}}`
+  pub fn link(&self, to: &str, content: &str) -> String {
+    format!(r#"<a href="{}/{to}"> {content} </a>"#, self.url)
+  }
+
+  /// Output path of style, relative to build directory, **without** `.css` extension, fingerprinted if enabled
+  ///
+  /// Returns `None` if style does not exist
+  pub fn style_path(&self, name: &str) -> Option<&str> {
+    self.style_paths.get(name).map(String::as_str)
+  }
+
+  /// Style tag, like `{{>STYLE name="..."}}`
+  ///
+  /// Returns `None` if style does not exist
+  pub fn style(&self, name: &str) -> Option<String> {
+    let path = self.style_path(name)?;
+    Some(format!(
+      r#"<link rel="stylesheet" href="{}/{path}.css" />"#,
+      self.url
+    ))
+  }
+}
@@ -0,0 +1,93 @@
+use handlebars::html_escape;
+use std::error::Error;
+
+use crate::{server::DEV_SCRIPT, FileMap, UnreactError};
+
+/// Amount of lines of template shown before and after line of error
+const CONTEXT_LINES: usize = 2;
+
+/// Create page which shows error, in place of a page which failed to render in dev mode
+///
+/// Shows message of error, location with lines of template around it, and every source of error.
+/// Page is reloaded by `DEV_SCRIPT` when rebuilt, so it is replaced once the error is fixed
+pub(crate) fn error_page(path: &str, err: &UnreactError, templates: &FileMap) -> String {
+  let mut body = format!(
+    "<h1>Failed to render page</h1><p><code>/{}</code></p><pre>{}</pre>",
+    html_escape(path),
+    html_escape(&err.to_string()),
+  );
+
+  // Lines of template around error
+  if let Some(location) = err.location() {
+    body += &format!("<h2>At {}</h2>", html_escape(&location.to_string()));
+    let lines = (templates.get(&location.name)).zip(location.line);
+    if let Some((template, line)) = lines {
+      body += "<pre>";
+      let start = line.saturating_sub(CONTEXT_LINES + 1);
+      for (i, text) in template
+        .lines()
+        .enumerate()
+        .skip(start)
+        .take(CONTEXT_LINES * 2 + 1)
+      {
+        let marker = if i + 1 == line { ">" } else { " " };
+        body += &format!("{marker} {:>4} | {}\n", i + 1, html_escape(text));
+      }
+      body += "</pre>";
+    }
+  }
+
+  // Sources of error, such as the error of Handlebars, if not already in message
+  let message = err.to_string();
+  let mut sources = Vec::new();
+  let mut source = err.source();
+  while let Some(err) = source {
+    let text = err.to_string();
+    if !message.contains(&text) {
+      sources.push(format!("<li><pre>{}</pre></li>", html_escape(&text)));
+    }
+    source = err.source();
+  }
+  if !sources.is_empty() {
+    body += &format!("<h2>Caused by</h2><ul>{}</ul>", sources.concat());
+  }
+
+  format!(
+    concat!(
+      r#"<!DOCTYPE html><html><head><meta charset="utf-8" /><title>Error - /{}</title>"#,
+      "<style>{}</style></head><body>{}{}</body></html>",
+    ),
+    html_escape(path),
+    STYLE,
+    body,
+    DEV_SCRIPT,
+  )
+}
+
+/// Create style which shows error at top of every page, in place of a style which failed to compile in dev mode
+pub(crate) fn error_style(name: &str, err: &UnreactError) -> String {
+  let message = format!("Failed to compile style '{name}'\n\n{err}");
+  format!(
+    concat!(
+      "body::before {{ content: \"{}\"; display: block; white-space: pre-wrap; ",
+      "padding: 1em; background: #fee; color: #900; font-family: monospace; }}",
+    ),
+    css_escape(&message)
+  )
+}
+
+/// Escape text for a css string, in double quotes
+fn css_escape(text: &str) -> String {
+  text
+    .replace('\\', "\\\\")
+    .replace('"', "\\\"")
+    .replace('\n', "\\A ")
+}
+
+/// Style of error page
+const STYLE: &str = concat!(
+  "body { font-family: sans-serif; max-width: 60em; margin: 2em auto; padding: 0 1em; color: #222; } ",
+  "h1 { color: #900; } ",
+  "pre { background: #f6f6f6; padding: 1em; overflow-x: auto; white-space: pre-wrap; } ",
+  "ul { padding: 0; list-style: none; }",
+);
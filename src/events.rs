@@ -0,0 +1,90 @@
+use std::sync::{
+  mpsc::{channel, Receiver, Sender},
+  Mutex, PoisonError,
+};
+
+use crate::BuildReport;
+
+/// Event of the build lifecycle, sent to every receiver of `Unreact::subscribe`
+///
+/// For progress bars, dashboards, and editor integrations. More variants can be added without a breaking change, so a `match` must have a wildcard arm
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BuildEvent {
+  /// Build started, with `Unreact::finish` or a rebuild in dev mode
+  BuildStarted {
+    /// If build is in dev mode
+    is_dev: bool,
+  },
+  /// Template or partial was registered, when the registry is created after templates change
+  TemplateLoaded {
+    /// Name of template
+    name: String,
+  },
+  /// Page was rendered with its template, or from markdown
+  PageRendered {
+    /// Path of page, **without** `.html` extension
+    path: String,
+  },
+  /// Style was compiled to css
+  StyleCompiled {
+    /// Name of style
+    name: String,
+    /// Output file, relative to build directory, **with** extension
+    file: String,
+  },
+  /// Output file was written to build directory, or to sink
+  FileWritten {
+    /// Path relative to build directory, **with** extension
+    file: String,
+  },
+  /// Warning was printed, such as a broken link, or a failed render in dev mode
+  WarningEmitted {
+    /// Message of warning, as printed
+    message: String,
+  },
+  /// Build finished, after every file is written
+  BuildFinished {
+    /// Report of build, also given to `Plugin::report`
+    report: BuildReport,
+  },
+  /// Dev server started watching directories, with `Unreact::finish_watch`, so each change starts a rebuild
+  Watching {
+    /// Directories which are watched, relative to workspace
+    dirs: Vec<String>,
+  },
+  /// Build failed, or was aborted by callback of `Unreact::finish_with_progress`, instead of `BuildFinished`
+  BuildFailed {
+    /// Message of error, as printed
+    error: String,
+  },
+}
+
+/// Senders of receivers from `Unreact::subscribe`
+#[derive(Debug, Default)]
+pub(crate) struct Subscribers(Mutex<Vec<Sender<BuildEvent>>>);
+
+impl Subscribers {
+  /// Add a subscriber, and get receiver of its events
+  pub fn subscribe(&self) -> Receiver<BuildEvent> {
+    let (sender, receiver) = channel();
+    self.lock().push(sender);
+    receiver
+  }
+
+  /// Send event to every subscriber, and remove subscribers whose receiver is dropped
+  ///
+  /// Event is only created if there are subscribers
+  pub fn send(&self, event: impl FnOnce() -> BuildEvent) {
+    let mut senders = self.lock();
+    if senders.is_empty() {
+      return;
+    }
+    let event = event();
+    senders.retain(|sender| sender.send(event.clone()).is_ok());
+  }
+
+  fn lock(&self) -> std::sync::MutexGuard<'_, Vec<Sender<BuildEvent>>> {
+    self.0.lock().unwrap_or_else(PoisonError::into_inner)
+  }
+}
@@ -0,0 +1,150 @@
+use crate::{date::DateTime, escape_xml as escape, SiteUrl};
+
+/// Format of feed file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FeedFormat {
+  /// RSS 2.0
+  #[default]
+  Rss,
+  /// Atom 1.0
+  Atom,
+}
+
+/// Options of a feed, see `Unreact::feed`
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct FeedOptions {
+  /// Title of feed, such as name of site or blog
+  pub title: String,
+  /// Description of feed
+  ///
+  /// Default: `""`
+  pub description: String,
+  /// Path of page which feed belongs to, such as `"blog"`
+  ///
+  /// Default: `""` (Home page)
+  pub link: String,
+  /// Name of author of every item
+  ///
+  /// Atom feeds need an author to be valid
+  ///
+  /// Default: `None`
+  pub author: Option<String>,
+  /// Format of feed
+  ///
+  /// Default: `FeedFormat::Rss`
+  pub format: FeedFormat,
+}
+
+/// Item of a feed, such as a blog post, see `Unreact::feed`
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct FeedItem {
+  /// Title of item
+  pub title: String,
+  /// Path of page of item (such as `"posts/hello"`), or absolute url
+  pub link: String,
+  /// Date of publishing, as `YYYY-MM-DD`, or RFC 3339 date and time (such as `2024-01-31T09:30:00+10:00`)
+  ///
+  /// Times without offset are UTC
+  pub date: String,
+  /// Short summary of item, as plain text
+  ///
+  /// Default: `None`
+  pub summary: Option<String>,
+}
+
+/// Create feed file of items, in order given
+///
+/// `path`: Path of feed in build directory, for link to itself
+///
+/// Returns error if date of any item is not valid
+pub(crate) fn create_feed(
+  path: &str,
+  options: &FeedOptions,
+  items: &[FeedItem],
+  url: &SiteUrl,
+) -> Result<String, String> {
+  let dates = items
+    .iter()
+    .map(|item| DateTime::parse(&item.date))
+    .collect::<Result<Vec<_>, _>>()?;
+  // Latest item, as feed has no date of its own
+  let updated = dates.iter().max_by_key(|date| date.timestamp());
+
+  let link = |link: &str| url.resolve(link);
+  let feed_url = url.join(path);
+  let site_link = link(&options.link);
+
+  let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+
+  match options.format {
+    FeedFormat::Rss => {
+      xml += "<rss version=\"2.0\" xmlns:atom=\"http://www.w3.org/2005/Atom\" xmlns:dc=\"http://purl.org/dc/elements/1.1/\">\n<channel>\n";
+      xml += &format!("  <title>{}</title>\n", escape(&options.title));
+      xml += &format!("  <link>{}</link>\n", escape(&site_link));
+      xml += &format!(
+        "  <description>{}</description>\n",
+        escape(&options.description)
+      );
+      xml += &format!(
+        "  <atom:link href=\"{}\" rel=\"self\" type=\"application/rss+xml\" />\n",
+        escape(&feed_url)
+      );
+      if let Some(updated) = updated {
+        xml += &format!("  <lastBuildDate>{}</lastBuildDate>\n", updated.rfc822());
+      }
+
+      for (item, date) in items.iter().zip(&dates) {
+        let link = escape(&link(&item.link));
+        xml += "  <item>\n";
+        xml += &format!("    <title>{}</title>\n", escape(&item.title));
+        xml += &format!("    <link>{link}</link>\n");
+        xml += &format!("    <guid isPermaLink=\"true\">{link}</guid>\n");
+        xml += &format!("    <pubDate>{}</pubDate>\n", date.rfc822());
+        if let Some(author) = &options.author {
+          xml += &format!("    <dc:creator>{}</dc:creator>\n", escape(author));
+        }
+        if let Some(summary) = &item.summary {
+          xml += &format!("    <description>{}</description>\n", escape(summary));
+        }
+        xml += "  </item>\n";
+      }
+
+      xml += "</channel>\n</rss>\n";
+    }
+
+    FeedFormat::Atom => {
+      xml += "<feed xmlns=\"http://www.w3.org/2005/Atom\">\n";
+      xml += &format!("  <title>{}</title>\n", escape(&options.title));
+      if !options.description.is_empty() {
+        xml += &format!("  <subtitle>{}</subtitle>\n", escape(&options.description));
+      }
+      xml += &format!("  <id>{}</id>\n", escape(&site_link));
+      xml += &format!("  <link href=\"{}\" />\n", escape(&site_link));
+      xml += &format!("  <link href=\"{}\" rel=\"self\" />\n", escape(&feed_url));
+      xml += &format!(
+        "  <updated>{}</updated>\n",
+        updated.map_or("1970-01-01T00:00:00Z".to_string(), DateTime::rfc3339)
+      );
+      if let Some(author) = &options.author {
+        xml += &format!("  <author><name>{}</name></author>\n", escape(author));
+      }
+
+      for (item, date) in items.iter().zip(&dates) {
+        let link = escape(&link(&item.link));
+        xml += "  <entry>\n";
+        xml += &format!("    <title>{}</title>\n", escape(&item.title));
+        xml += &format!("    <id>{link}</id>\n");
+        xml += &format!("    <link href=\"{link}\" />\n");
+        xml += &format!("    <updated>{}</updated>\n", date.rfc3339());
+        if let Some(summary) = &item.summary {
+          xml += &format!("    <summary>{}</summary>\n", escape(summary));
+        }
+        xml += "  </entry>\n";
+      }
+
+      xml += "</feed>\n";
+    }
+  }
+
+  Ok(xml)
+}
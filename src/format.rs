@@ -0,0 +1,198 @@
+/// Options for formatting html output, when `minify` is disabled in config
+///
+/// Use `FormatOptions::default()` to keep output as rendered
+///
+/// Use `FormatOptions::pretty()` for stable, reviewable output, such as for committing the build directory
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct FormatOptions {
+  /// Re-indent each line by nesting of html elements, with this many spaces per level
+  ///
+  /// Lines are not split or joined. Content of `<pre>`, `<textarea>`, `<script>`, and `<style>` is unchanged
+  ///
+  /// Default: `None` (keep indentation from templates)
+  pub indent: Option<usize>,
+  /// Remove whitespace from the end of each line
+  ///
+  /// Default: `false`
+  pub trim_trailing_whitespace: bool,
+  /// Replace multiple blank lines with a single blank line, and remove blank lines at start and end
+  ///
+  /// Default: `false`
+  pub collapse_blank_lines: bool,
+  /// End file with exactly one newline
+  ///
+  /// Default: `false`
+  pub final_newline: bool,
+}
+
+impl FormatOptions {
+  /// Enable all formatting, with indentation of 2 spaces
+  pub fn pretty() -> Self {
+    FormatOptions {
+      indent: Some(2),
+      trim_trailing_whitespace: true,
+      collapse_blank_lines: true,
+      final_newline: true,
+    }
+  }
+}
+
+/// Elements which never have a closing tag
+const VOID_ELEMENTS: &[&str] = &[
+  "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+  "track", "wbr",
+];
+
+/// Elements with content which is not re-indented
+const RAW_ELEMENTS: &[&str] = &["pre", "textarea", "script", "style"];
+
+/// Format html, with options
+///
+/// # Examples
+///
+/// ```
+/// use unreact::prelude::*;
+///
+/// let html = "<div>\n\t\t<p>\n  Hello   \n </p>\n\n\n</div>";
+///
+/// assert_eq!(
+///   unreact::format_html(html, &FormatOptions::pretty()),
+///   "<div>\n  <p>\n    Hello\n  </p>\n\n</div>\n",
+/// );
+/// ```
+pub fn format_html(content: &str, options: &FormatOptions) -> String {
+  let mut lines = Vec::new();
+
+  // Nesting depth of elements
+  let mut depth: usize = 0;
+  // Name of raw element, if inside one
+  let mut raw: Option<String> = None;
+
+  for line in content.lines() {
+    // Keep content of raw elements unchanged, until closing tag
+    if let Some(name) = &raw {
+      if line.contains(&format!("</{name}")) {
+        raw = None;
+        depth = depth.saturating_sub(1);
+      }
+      lines.push(line.to_string());
+      continue;
+    }
+
+    let mut line = line.to_string();
+
+    if let Some(width) = options.indent {
+      let trimmed = line.trim();
+      let tags = scan_tags(trimmed);
+
+      // Closing tags at start of line are on the level of their opening tag
+      let level = depth.saturating_sub(tags.leading_closes);
+      line = if trimmed.is_empty() {
+        String::new()
+      } else {
+        format!("{}{trimmed}", " ".repeat(level * width))
+      };
+
+      depth = (depth as isize + tags.net).max(0) as usize;
+      raw = tags.raw;
+    }
+
+    if options.trim_trailing_whitespace {
+      line = line.trim_end().to_string();
+    }
+
+    lines.push(line);
+  }
+
+  // Collapse blank lines
+  if options.collapse_blank_lines {
+    let mut collapsed: Vec<String> = Vec::new();
+    for line in lines {
+      let blank = line.trim().is_empty();
+      if blank && collapsed.last().is_none_or(|x| x.trim().is_empty()) {
+        continue;
+      }
+      collapsed.push(line);
+    }
+    while collapsed.last().is_some_and(|x| x.trim().is_empty()) {
+      collapsed.pop();
+    }
+    lines = collapsed;
+  }
+
+  let mut output = lines.join("\n");
+  if options.final_newline {
+    output = output.trim_end_matches('\n').to_string() + "\n";
+  } else if content.ends_with('\n') {
+    // Keep original trailing newline
+    output.push('\n');
+  }
+  output
+}
+
+/// Tags found in a single line of html
+#[derive(Debug, Default)]
+struct LineTags {
+  /// Amount of closing tags before any other content
+  leading_closes: usize,
+  /// Opening tags minus closing tags
+  net: isize,
+  /// Name of raw element, if opened and not closed on this line
+  raw: Option<String>,
+}
+
+/// Find opening and closing tags in a line of html
+fn scan_tags(line: &str) -> LineTags {
+  let mut tags = LineTags::default();
+  // If only closing tags have been found so far
+  let mut leading = true;
+
+  let mut rest = line;
+  while let Some(start) = rest.find('<') {
+    if !rest[..start].trim().is_empty() {
+      leading = false;
+    }
+    let tag = &rest[start + 1..];
+    // End of tag, or end of line if tag continues on next line
+    let end = tag.find('>').unwrap_or(tag.len());
+    let inner = &tag[..end];
+
+    if let Some(name) = inner.strip_prefix('/') {
+      // Closing tag
+      tags.net -= 1;
+      if leading {
+        tags.leading_closes += 1;
+      }
+      // Raw element closed on same line
+      if tags.raw.as_deref() == Some(tag_name(name)) {
+        tags.raw = None;
+      }
+    } else if inner.starts_with(|ch: char| ch.is_ascii_alphabetic()) {
+      // Opening tag
+      leading = false;
+      let name = tag_name(inner).to_lowercase();
+      if !inner.ends_with('/') && !VOID_ELEMENTS.contains(&name.as_str()) {
+        tags.net += 1;
+        if RAW_ELEMENTS.contains(&name.as_str()) {
+          tags.raw = Some(name);
+        }
+      }
+    } else {
+      // Comment or doctype
+      leading = false;
+    }
+
+    // Skip past `>`
+    rest = &tag[(end + 1).min(tag.len())..];
+  }
+
+  tags
+}
+
+/// Get name of tag, from content after `<` or `</`
+fn tag_name(tag: &str) -> &str {
+  tag
+    .split(|ch: char| ch.is_whitespace() || ch == '>' || ch == '/')
+    .next()
+    .unwrap_or_default()
+}
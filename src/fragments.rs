@@ -0,0 +1,96 @@
+use handlebars::{
+  Context, Handlebars, Helper, HelperDef, HelperResult, JsonRender, Output, RenderContext,
+  RenderError, Renderable, StringOutput,
+};
+use std::{
+  cell::RefCell,
+  collections::HashMap,
+  sync::{Arc, Mutex, PoisonError},
+};
+
+/// Fragments rendered by `cache` block helper, shared by every page of a build
+#[derive(Debug, Default)]
+pub(crate) struct Fragments(Mutex<HashMap<String, String>>);
+
+impl Fragments {
+  /// Remove all fragments, before a new build
+  pub fn clear(&self) {
+    self
+      .0
+      .lock()
+      .unwrap_or_else(PoisonError::into_inner)
+      .clear();
+  }
+
+  /// Get rendered fragment of key
+  fn get(&self, key: &str) -> Option<String> {
+    let fragments = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+    fragments.get(key).cloned()
+  }
+
+  /// Save rendered fragment of key
+  fn insert(&self, key: String, fragment: String) {
+    let mut fragments = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+    fragments.insert(key, fragment);
+  }
+}
+
+thread_local! {
+  static FRAGMENTS: RefCell<Option<Arc<Fragments>>> = const { RefCell::new(None) };
+}
+
+/// Run render with fragments of build, on this thread
+///
+/// Registry can be shared between apps, so fragments are not kept by the helper
+pub(crate) fn with_fragments<T>(fragments: &Arc<Fragments>, render: impl FnOnce() -> T) -> T {
+  // Keep fragments of outer render, if any
+  let outer = FRAGMENTS.with(|cell| cell.borrow_mut().replace(Arc::clone(fragments)));
+  let result = render();
+  FRAGMENTS.with(|cell| *cell.borrow_mut() = outer);
+  result
+}
+
+/// Block helper which renders its content once for each key in a build, such as `{{#cache "nav"}}...{{/cache}}`
+///
+/// Every parameter is part of key, such as `{{#cache "nav" locale}}`
+///
+/// Content is rendered every time if not rendering with `with_fragments`
+pub(crate) struct CacheHelper;
+
+impl HelperDef for CacheHelper {
+  fn call<'reg: 'rc, 'rc>(
+    &self,
+    h: &Helper<'reg, 'rc>,
+    reg: &'reg Handlebars<'reg>,
+    ctx: &'rc Context,
+    rc: &mut RenderContext<'reg, 'rc>,
+    out: &mut dyn Output,
+  ) -> HelperResult {
+    if h.params().is_empty() {
+      return Err(RenderError::new("Helper `cache` requires a key"));
+    }
+    let key = (h.params().iter())
+      .map(|param| param.value().render())
+      .collect::<Vec<_>>()
+      .join("\0");
+    let Some(template) = h.template() else {
+      return Ok(());
+    };
+
+    let fragments = FRAGMENTS.with(|cell| cell.borrow().clone());
+    if let Some(fragment) = fragments.as_ref().and_then(|fragments| fragments.get(&key)) {
+      out.write(&fragment)?;
+      return Ok(());
+    }
+
+    // Lock is not held while rendering, so blocks can be nested
+    let mut rendered = StringOutput::new();
+    template.render(reg, ctx, rc, &mut rendered)?;
+    let fragment = rendered.into_string()?;
+    out.write(&fragment)?;
+    if let Some(fragments) = fragments {
+      fragments.insert(key, fragment);
+    }
+    Ok(())
+  }
+}
@@ -0,0 +1,600 @@
+use serde_json::{Map, Number, Value};
+
+/// Split front matter from start of Markdown content, and parse it
+///
+/// YAML front matter is between `---` lines, TOML front matter is between `+++` lines
+///
+/// Values are strings, numbers, booleans, and lists of those. Lists can be inline (`[a, b]`), also over several lines.
+/// YAML can nest keys by indentation, and have block lists (`- a` on following lines), also of maps (`- key: value`).
+/// TOML can have tables (`[name]`) and arrays of tables (`[[name]]`)
+///
+/// Returns data (empty object if there is no front matter), and remaining content
+pub(crate) fn parse_front_matter(content: &str) -> Result<(Value, &str), String> {
+  // Ignore byte order mark
+  let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+
+  let (delimiter, is_toml) = match first_line(content) {
+    "---" => ("---", false),
+    "+++" => ("+++", true),
+    _ => return Ok((Value::Object(Map::new()), content)),
+  };
+
+  // Find closing delimiter
+  let mut offset = 0;
+  let mut lines = Vec::new();
+  let mut body = None;
+  for (i, line) in content.split_inclusive('\n').enumerate() {
+    offset += line.len();
+    if i == 0 {
+      continue;
+    }
+    if line.trim_end() == delimiter {
+      body = Some(&content[offset..]);
+      break;
+    }
+    lines.push(line.trim_end_matches(['\n', '\r']));
+  }
+  let Some(body) = body else {
+    return Err(format!("Front matter is not closed with `{delimiter}`"));
+  };
+
+  // First line is delimiter
+  let data = if is_toml {
+    Value::Object(parse_toml(&lines, 2)?)
+  } else {
+    parse_yaml(&lines, 2)?
+  };
+  if !data.is_object() {
+    return Err("Front matter must be keys, not a list".to_string());
+  }
+  Ok((data, body))
+}
+
+/// Parse whole YAML or TOML file, such as a config file or data file
+///
+/// Supports the same values as `parse_front_matter`, and a YAML file can also be a list
+pub(crate) fn parse_file(content: &str, is_toml: bool) -> Result<Value, String> {
+  let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+  let lines = content.lines().collect::<Vec<_>>();
+  if is_toml {
+    parse_toml(&lines, 1).map(Value::Object)
+  } else {
+    parse_yaml(&lines, 1)
+  }
+}
+
+/// Get first line, without line ending
+fn first_line(content: &str) -> &str {
+  content.lines().next().unwrap_or_default().trim_end()
+}
+
+/// Parse YAML keys or list, with nested maps and lists by indentation
+///
+/// `start_line`: Line number of first line, for errors
+fn parse_yaml(lines: &[&str], start_line: usize) -> Result<Value, String> {
+  // Line number, indentation, and trimmed text of each line, without empty lines and comments
+  let mut items = Vec::new();
+  let mut i = 0;
+  while i < lines.len() {
+    let line = lines[i];
+    let trimmed = line.trim();
+    let number = i + start_line;
+    i += 1;
+    if trimmed.is_empty() || trimmed.starts_with('#') {
+      continue;
+    }
+    let mut indent = line.len() - line.trim_start_matches([' ', '\t']).len();
+    let mut text = strip_comment(trimmed).trim_end().to_string();
+
+    // Split list item of map or list into `-`, and its content with the indentation after `- `
+    // So `- title: Home` is parsed like `-` with a nested `title: Home`
+    while let Some(item) = list_item(&text) {
+      if !is_yaml_key(item) && list_item(item).is_none() {
+        break;
+      }
+      let item = item.to_string();
+      items.push((number, indent, "-".to_string()));
+      indent += text.len() - item.len();
+      text = item;
+    }
+
+    // Inline list over several lines
+    let value = match list_item(&text) {
+      Some(item) => item,
+      None => text.split_once(':').map_or("", |(_, value)| value.trim()),
+    };
+    if value.starts_with('[') {
+      text = join_list(text, lines, &mut i)?;
+    }
+    items.push((number, indent, text));
+  }
+
+  if items.is_empty() {
+    return Ok(Value::Object(Map::new()));
+  }
+  let mut pos = 0;
+  let data = parse_yaml_block(&items, &mut pos, 0)?;
+  if let Some((number, _, text)) = items.get(pos) {
+    if data.is_array() && list_item(text).is_none() {
+      return Err(format!("Line {number}: Expected list item"));
+    }
+    return Err(format!("Line {number}: Unexpected indentation"));
+  }
+  Ok(data)
+}
+
+/// Parse YAML list or map, depending on first line at `pos`
+fn parse_yaml_block(
+  items: &[(usize, usize, String)],
+  pos: &mut usize,
+  indent: usize,
+) -> Result<Value, String> {
+  match items.get(*pos) {
+    Some((_, _, text)) if list_item(text).is_some() => {
+      parse_yaml_list(items, pos, indent).map(Value::Array)
+    }
+    _ => parse_yaml_map(items, pos, indent).map(Value::Object),
+  }
+}
+
+/// Parse YAML list items with the same indentation, starting at `pos`
+fn parse_yaml_list(
+  items: &[(usize, usize, String)],
+  pos: &mut usize,
+  indent: usize,
+) -> Result<Vec<Value>, String> {
+  let mut list = Vec::new();
+  while let Some((_, item_indent, text)) = items.get(*pos) {
+    let item = match list_item(text) {
+      Some(item) if *item_indent == indent => item,
+      _ => break,
+    };
+    *pos += 1;
+    if !item.is_empty() {
+      list.push(parse_value(item, false)?);
+      continue;
+    }
+    // Nested map or list, or empty item
+    list.push(match items.get(*pos) {
+      Some((_, next_indent, _)) if *next_indent > indent => {
+        parse_yaml_block(items, pos, *next_indent)?
+      }
+      _ => Value::Null,
+    });
+  }
+  Ok(list)
+}
+
+/// Parse YAML keys with the same indentation, starting at `pos`, until a line with less indentation
+fn parse_yaml_map(
+  items: &[(usize, usize, String)],
+  pos: &mut usize,
+  indent: usize,
+) -> Result<Map<String, Value>, String> {
+  let mut data = Map::new();
+
+  while let Some((number, line_indent, text)) = items.get(*pos) {
+    if *line_indent < indent {
+      break;
+    }
+    if *line_indent > indent {
+      return Err(format!("Line {number}: Unexpected indentation"));
+    }
+    if list_item(text).is_some() {
+      return Err(format!("Line {number}: List item has no key"));
+    }
+    let Some((key, value)) = text.split_once(':') else {
+      return Err(format!("Line {number}: Expected `key: value`"));
+    };
+    let key = unquote_key(key.trim());
+    let value = value.trim();
+    *pos += 1;
+
+    if !value.is_empty() {
+      data.insert(key, parse_value(value, false)?);
+      continue;
+    }
+
+    // Block list, nested map, or empty value
+    let value = match items.get(*pos) {
+      // List items can have the same indentation as key
+      Some((_, next_indent, next)) if *next_indent >= indent && list_item(next).is_some() => {
+        Value::Array(parse_yaml_list(items, pos, *next_indent)?)
+      }
+      Some((_, next_indent, _)) if *next_indent > indent => {
+        Value::Object(parse_yaml_map(items, pos, *next_indent)?)
+      }
+      _ => Value::Null,
+    };
+    data.insert(key, value);
+  }
+
+  Ok(data)
+}
+
+/// Get value of YAML block list item, such as `a` of `- a`
+fn list_item(text: &str) -> Option<&str> {
+  text
+    .strip_prefix('-')
+    .filter(|x| x.is_empty() || x.starts_with(' '))
+    .map(str::trim)
+}
+
+/// Check if text of YAML line is `key: value` or `key:`, and not a scalar such as `"a: b"` or `https://a.b`
+fn is_yaml_key(text: &str) -> bool {
+  if text.starts_with(['"', '\'', '[']) {
+    return false;
+  }
+  text
+    .split_once(':')
+    .is_some_and(|(_, rest)| rest.is_empty() || rest.starts_with([' ', '\t']))
+}
+
+/// Parse TOML keys, with tables (`[name]`) and arrays of tables (`[[name]]`)
+///
+/// Dotted keys of values are kept as they are, such as `"markdown.tables"`, but dotted names of tables are nested
+///
+/// `start_line`: Line number of first line, for errors
+fn parse_toml(lines: &[&str], start_line: usize) -> Result<Map<String, Value>, String> {
+  let mut data = Map::new();
+  // Keys of current table
+  let mut table = Vec::new();
+
+  let mut i = 0;
+  while i < lines.len() {
+    let trimmed = strip_comment(lines[i].trim()).trim_end();
+    let number = i + start_line;
+    i += 1;
+    if trimmed.is_empty() {
+      continue;
+    }
+
+    // Array of tables, which adds a table to array each time
+    if let Some(name) = trimmed
+      .strip_prefix("[[")
+      .and_then(|x| x.strip_suffix("]]"))
+    {
+      table = split_key(name);
+      let Some((last, parent)) = table.split_last() else {
+        return Err(format!("Line {number}: Table has no name"));
+      };
+      let parent = table_mut(&mut data, parent).map_err(|err| format!("Line {number}: {err}"))?;
+      match parent
+        .entry(last.to_string())
+        .or_insert_with(|| Value::Array(Vec::new()))
+      {
+        Value::Array(list) => list.push(Value::Object(Map::new())),
+        _ => {
+          return Err(format!(
+            "Line {number}: Key `{last}` is not an array of tables"
+          ))
+        }
+      }
+      continue;
+    }
+    if let Some(name) = trimmed.strip_prefix('[').and_then(|x| x.strip_suffix(']')) {
+      table = split_key(name);
+      if table.is_empty() {
+        return Err(format!("Line {number}: Table has no name"));
+      }
+      table_mut(&mut data, &table).map_err(|err| format!("Line {number}: {err}"))?;
+      continue;
+    }
+
+    let Some((key, value)) = trimmed.split_once('=') else {
+      return Err(format!("Line {number}: Expected `key = value`"));
+    };
+    let mut value = value.trim().to_string();
+    // Array over several lines
+    if value.starts_with('[') {
+      value = join_list(value, lines, &mut i)?;
+    }
+    let object = table_mut(&mut data, &table).map_err(|err| format!("Line {number}: {err}"))?;
+    object.insert(unquote_key(key.trim()), parse_value(&value, true)?);
+  }
+
+  Ok(data)
+}
+
+/// Get table of TOML data at keys, creating missing tables
+///
+/// Last table of an array of tables is used
+fn table_mut<'a>(
+  data: &'a mut Map<String, Value>,
+  keys: &[String],
+) -> Result<&'a mut Map<String, Value>, String> {
+  let mut object = data;
+  for key in keys {
+    let value = object
+      .entry(key.to_string())
+      .or_insert_with(|| Value::Object(Map::new()));
+    let value = match value {
+      Value::Array(list) => match list.last_mut() {
+        Some(value) => value,
+        None => return Err(format!("Key `{key}` is not a table")),
+      },
+      value => value,
+    };
+    object = match value {
+      Value::Object(map) => map,
+      _ => return Err(format!("Key `{key}` is not a table")),
+    };
+  }
+  Ok(object)
+}
+
+/// Split dotted name of table into keys, such as `a."b.c"` into `a` and `b.c`
+fn split_key(name: &str) -> Vec<String> {
+  let mut keys = Vec::new();
+  let mut quote = None;
+  let mut start = 0;
+  for (i, ch) in name.char_indices() {
+    match (ch, quote) {
+      ('"' | '\'', None) => quote = Some(ch),
+      (ch, Some(q)) if ch == q => quote = None,
+      ('.', None) => {
+        keys.push(unquote_key(name[start..i].trim()));
+        start = i + 1;
+      }
+      _ => (),
+    }
+  }
+  keys.push(unquote_key(name[start..].trim()));
+  keys.into_iter().filter(|key| !key.is_empty()).collect()
+}
+
+/// Join inline list with following lines, until every `[` is closed
+///
+/// `i`: Index of next line, which is moved past joined lines
+fn join_list(mut value: String, lines: &[&str], i: &mut usize) -> Result<String, String> {
+  while bracket_depth(&value) > 0 {
+    let Some(line) = lines.get(*i) else {
+      return Err(format!("List is not closed in `{value}`"));
+    };
+    *i += 1;
+    value.push(' ');
+    value.push_str(strip_comment(line.trim()).trim_end());
+  }
+  Ok(value)
+}
+
+/// Get amount of `[` which are not closed, outside of quotes
+fn bracket_depth(value: &str) -> i32 {
+  let mut quote = None;
+  let mut depth = 0;
+  for (i, ch) in value.char_indices() {
+    match (ch, quote) {
+      ('"' | '\'', None) if starts_value(&value[..i]) => quote = Some(ch),
+      (ch, Some(q)) if ch == q => quote = None,
+      ('[', None) => depth += 1,
+      (']', None) => depth -= 1,
+      _ => (),
+    }
+  }
+  depth
+}
+
+/// Check if a quote after `before` starts a quoted value, as it is the first character of a value or list item
+///
+/// So the `'` of `Don't` or `[it's, fine]` is part of an unquoted string
+fn starts_value(before: &str) -> bool {
+  let before = before.trim_end();
+  before.is_empty()
+    || before.ends_with(['[', ',', ':', '='])
+    || before == "-"
+    || before.ends_with(" -")
+}
+
+/// Remove quotes from key
+fn unquote_key(key: &str) -> String {
+  ['"', '\'']
+    .iter()
+    .find_map(|quote| key.strip_prefix(*quote)?.strip_suffix(*quote))
+    .unwrap_or(key)
+    .to_string()
+}
+
+/// Remove comment from end of value, if `#` is not inside quotes
+fn strip_comment(value: &str) -> &str {
+  let mut quote = None;
+  for (i, ch) in value.char_indices() {
+    match (ch, quote) {
+      ('"' | '\'', None) if starts_value(&value[..i]) => quote = Some(ch),
+      (ch, Some(q)) if ch == q => quote = None,
+      // Comment must follow whitespace, so `a#b` is kept
+      ('#', None) if value[..i].ends_with([' ', '\t']) || i == 0 => return &value[..i],
+      _ => (),
+    }
+  }
+  value
+}
+
+/// Parse single value
+///
+/// `strict`: If strings must be quoted (TOML)
+fn parse_value(value: &str, strict: bool) -> Result<Value, String> {
+  // Quoted string
+  for quote in ['"', '\''] {
+    if let Some(inner) = value.strip_prefix(quote) {
+      return match inner.strip_suffix(quote) {
+        Some(inner) if quote == '"' => Ok(Value::String(unescape(inner))),
+        Some(inner) => Ok(Value::String(inner.to_string())),
+        None => Err(format!("Quote is not closed in `{value}`")),
+      };
+    }
+  }
+
+  // Inline list
+  if let Some(inner) = value.strip_prefix('[') {
+    let Some(inner) = inner.strip_suffix(']') else {
+      return Err(format!("List is not closed in `{value}`"));
+    };
+    return split_list(inner)
+      .into_iter()
+      .filter(|item| !item.is_empty())
+      .map(|item| parse_value(item, strict))
+      .collect::<Result<Vec<_>, _>>()
+      .map(Value::Array);
+  }
+
+  match value {
+    "true" => return Ok(Value::Bool(true)),
+    "false" => return Ok(Value::Bool(false)),
+    "null" | "~" if !strict => return Ok(Value::Null),
+    _ => (),
+  }
+
+  // Number
+  if let Ok(int) = value.replace('_', "").parse::<i64>() {
+    return Ok(Value::Number(int.into()));
+  }
+  if let Some(float) = value.parse::<f64>().ok().and_then(Number::from_f64) {
+    return Ok(Value::Number(float));
+  }
+
+  if strict {
+    // TOML dates are kept as strings
+    if value.starts_with(|ch: char| ch.is_ascii_digit()) {
+      return Ok(Value::String(value.to_string()));
+    }
+    return Err(format!(
+      "Value `{value}` is not valid, strings must be quoted"
+    ));
+  }
+  Ok(Value::String(value.to_string()))
+}
+
+/// Split items of inline list by commas, which are not inside quotes or nested lists
+fn split_list(inner: &str) -> Vec<&str> {
+  let mut items = Vec::new();
+  let mut quote = None;
+  let mut depth = 0;
+  let mut start = 0;
+
+  for (i, ch) in inner.char_indices() {
+    match (ch, quote) {
+      ('"' | '\'', None) if starts_value(&inner[..i]) => quote = Some(ch),
+      (ch, Some(q)) if ch == q => quote = None,
+      ('[', None) => depth += 1,
+      (']', None) => depth -= 1,
+      (',', None) if depth == 0 => {
+        items.push(inner[start..i].trim());
+        start = i + 1;
+      }
+      _ => (),
+    }
+  }
+  items.push(inner[start..].trim());
+
+  items
+}
+
+/// Replace escape sequences in double-quoted string
+fn unescape(value: &str) -> String {
+  let mut output = String::new();
+  let mut chars = value.chars();
+  while let Some(ch) = chars.next() {
+    if ch != '\\' {
+      output.push(ch);
+      continue;
+    }
+    match chars.next() {
+      Some('n') => output.push('\n'),
+      Some('t') => output.push('\t'),
+      Some(ch) => output.push(ch),
+      None => output.push('\\'),
+    }
+  }
+  output
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use serde_json::json;
+
+  #[test]
+  fn yaml_file_can_be_list_of_maps() {
+    let data = parse_file(
+      "- title: Home\n  url: /\n- title: About\n  url: /about\n",
+      false,
+    );
+    assert_eq!(
+      data,
+      Ok(json!([
+        { "title": "Home", "url": "/" },
+        { "title": "About", "url": "/about" },
+      ]))
+    );
+
+    let data = parse_file("- a\n- [b, c]\n-\n  - d\n", false);
+    assert_eq!(data, Ok(json!(["a", ["b", "c"], ["d"]])));
+  }
+
+  #[test]
+  fn yaml_key_can_be_list_of_maps() {
+    let yaml = "nav:\n  - title: Home\n    url: /\n  - title: Links\n    links:\n    - a\n    - b\nfooter: true\n";
+    assert_eq!(
+      parse_file(yaml, false),
+      Ok(json!({
+        "nav": [
+          { "title": "Home", "url": "/" },
+          { "title": "Links", "links": ["a", "b"] },
+        ],
+        "footer": true,
+      }))
+    );
+
+    // List items with the same indentation as key
+    let yaml = "nav:\n- title: Home\n  url: https://example.com\n- https://example.com\n";
+    assert_eq!(
+      parse_file(yaml, false),
+      Ok(json!({
+        "nav": [
+          { "title": "Home", "url": "https://example.com" },
+          "https://example.com",
+        ],
+      }))
+    );
+  }
+
+  #[test]
+  fn yaml_list_errors() {
+    assert!(parse_file("- a\nb: c\n", false).is_err());
+    assert!(parse_file("a: b\n- c\n", false).is_err());
+    assert!(parse_front_matter("---\n- a\n---\n").is_err());
+  }
+
+  #[test]
+  fn apostrophe_in_unquoted_value() {
+    assert_eq!(
+      parse_file(
+        "title: Don't # note\ntags: [it's, fine]\nlist:\n  - rock 'n' roll # note\n",
+        false
+      ),
+      Ok(json!({
+        "title": "Don't",
+        "tags": ["it's", "fine"],
+        "list": ["rock 'n' roll"],
+      }))
+    );
+    assert_eq!(
+      parse_file(
+        "title = \"Don't # note\"\ntags = [\"it's\", 'a, \"b\"']\n",
+        true
+      ),
+      Ok(json!({ "title": "Don't # note", "tags": ["it's", "a, \"b\""] }))
+    );
+  }
+
+  #[test]
+  fn quote_starts_value() {
+    assert_eq!(strip_comment("a: 'b # c' # d"), "a: 'b # c' ");
+    assert_eq!(strip_comment("a: b'c # d"), "a: b'c ");
+    assert_eq!(strip_comment("- \"b # c\""), "- \"b # c\"");
+    assert_eq!(split_list("'a, b', it's, c\"d"), ["'a, b'", "it's", "c\"d"]);
+    assert_eq!(split_list("[it's, b], c"), ["[it's, b]", "c"]);
+    assert_eq!(bracket_depth("[it's, ["), 2);
+    assert_eq!(bracket_depth("['[', it's]"), 0);
+  }
+}
@@ -0,0 +1,142 @@
+/// Options for the inbuilt `HTML_HEAD` partial
+///
+/// Page data can override `title` and `description`, such as `{{>HTML_HEAD title="About"}}`
+///
+/// Page data can also set `canonical` (path or absolute url) for a canonical link, and `noindex: true` to hide page from search engines
+///
+/// # Examples
+///
+/// ```
+/// use unreact::prelude::*;
+/// use serde_json::{json, Value};
+///
+/// fn main() -> UnreactResult<()> {
+///   let mut config = Config::default();
+///   config.head.title = "My Site".to_string();
+///
+///   let mut vfs = Vfs::new();
+///   vfs.templates.insert(
+///     "index".to_string(),
+///     r#"<head>{{>HTML_HEAD title="About"}}</head>"#.to_string(),
+///   );
+///
+///   let app = Unreact::from_vfs(config, false, SiteUrl::parse("https://mysite.com")?, vfs);
+///   let html = app.render("index", &Value::Null)?;
+///
+///   assert!(html.contains(r#"<meta charset="utf-8" />"#));
+///   assert!(html.contains("<title>About | My Site</title>"));
+///
+///   let html = app.render("index", &json!({"canonical": "posts/hello", "noindex": true}))?;
+///   assert!(html.contains(r#"<link rel="canonical" href="https://mysite.com/posts/hello" />"#));
+///   assert!(html.contains(r#"<meta name="robots" content="noindex" />"#));
+///
+///   Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeadOptions {
+  /// Title of site, shown after title of page
+  ///
+  /// Default: `""`
+  pub title: String,
+  /// Text between title of page and title of site
+  ///
+  /// Default: `" | "`
+  pub title_separator: String,
+  /// Description of site, if page does not set `description`
+  ///
+  /// Default: `None`
+  pub description: Option<String>,
+  /// Character encoding
+  ///
+  /// Default: `"utf-8"`
+  pub charset: String,
+  /// Content of viewport meta tag
+  ///
+  /// Default: `"width=device-width, initial-scale=1"`
+  pub viewport: String,
+  /// Other meta tags, as name and content
+  ///
+  /// Default: Empty
+  pub meta: Vec<(String, String)>,
+}
+
+impl Default for HeadOptions {
+  fn default() -> Self {
+    HeadOptions {
+      title: String::new(),
+      title_separator: " | ".to_string(),
+      description: None,
+      charset: "utf-8".to_string(),
+      viewport: "width=device-width, initial-scale=1".to_string(),
+      meta: Vec::new(),
+    }
+  }
+}
+
+impl HeadOptions {
+  /// Create handlebars partial for `HTML_HEAD`
+  pub(crate) fn to_partial(&self) -> String {
+    let mut partial = String::new();
+
+    partial += &format!(r#"<meta charset="{}" />"#, escape(&self.charset));
+    partial += &format!(
+      r#"<meta name="viewport" content="{}" />"#,
+      escape(&self.viewport)
+    );
+
+    // Title of page, then title of site
+    let site = escape(&self.title);
+    if site.is_empty() {
+      partial += "<title>{{title}}</title>";
+    } else {
+      let separator = escape(&self.title_separator);
+      partial +=
+        &format!("<title>{{{{#if title}}}}{{{{title}}}}{separator}{{{{/if}}}}{site}</title>");
+    }
+
+    // Description of page, or site
+    let fallback = match &self.description {
+      Some(description) => format!(
+        r#"{{{{else}}}}<meta name="description" content="{}" />"#,
+        escape(description)
+      ),
+      None => String::new(),
+    };
+    partial += &format!(
+      r#"{{{{#if description}}}}<meta name="description" content="{{{{description}}}}" />{fallback}{{{{/if}}}}"#
+    );
+
+    // Canonical url, for duplicate pages, or each page of `Unreact::paginate`
+    partial += concat!(
+      r#"{{#if canonical}}<link rel="canonical" href="{{canonical_url canonical}}" />"#,
+      r#"{{else}}{{#if pagination.canonical}}<link rel="canonical" href="{{canonical_url pagination.canonical locale_path}}" />{{/if}}{{/if}}"#,
+    );
+    // Previous and next pages of `Unreact::paginate`
+    partial += concat!(
+      r#"{{#if pagination.prev}}<link rel="prev" href="{{canonical_url pagination.prev locale_path}}" />{{/if}}"#,
+      r#"{{#if pagination.next}}<link rel="next" href="{{canonical_url pagination.next locale_path}}" />{{/if}}"#,
+    );
+    partial += r#"{{#if noindex}}<meta name="robots" content="noindex" />{{/if}}"#;
+
+    for (name, content) in &self.meta {
+      partial += &format!(
+        r#"<meta name="{}" content="{}" />"#,
+        escape(name),
+        escape(content)
+      );
+    }
+
+    partial
+  }
+}
+
+/// Escape text for html attribute, and to not be parsed as handlebars
+fn escape(text: &str) -> String {
+  text
+    .replace('&', "&amp;")
+    .replace('"', "&quot;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+    .replace("{{", "\\{{")
+}
@@ -0,0 +1,53 @@
+use std::{fmt, sync::Arc};
+
+/// Function which changes content of a file, from path and content
+type Hook = Arc<dyn Fn(&str, &str) -> String + Send + Sync>;
+
+/// Functions which change output of build, see `Unreact::on_page_rendered` and `Unreact::before_write`
+#[derive(Clone, Default)]
+pub(crate) struct Hooks {
+  /// Run on html of every page, before minify or format
+  page_rendered: Vec<Hook>,
+  /// Run on every output file with content, before writing
+  before_write: Vec<Hook>,
+}
+
+impl fmt::Debug for Hooks {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("Hooks")
+      .field("page_rendered", &self.page_rendered.len())
+      .field("before_write", &self.before_write.len())
+      .finish()
+  }
+}
+
+impl Hooks {
+  /// Add hook for rendered pages
+  pub fn add_page_rendered(&mut self, hook: impl Fn(&str, &str) -> String + Send + Sync + 'static) {
+    self.page_rendered.push(Arc::new(hook));
+  }
+
+  /// Add hook for output files
+  pub fn add_before_write(&mut self, hook: impl Fn(&str, &str) -> String + Send + Sync + 'static) {
+    self.before_write.push(Arc::new(hook));
+  }
+
+  /// Run every hook for rendered pages, in order added
+  pub fn page_rendered(&self, path: &str, html: &str) -> String {
+    run(&self.page_rendered, path, html)
+  }
+
+  /// Run every hook for output files, in order added
+  pub fn before_write(&self, file: &str, content: &str) -> String {
+    run(&self.before_write, file, content)
+  }
+}
+
+/// Run hooks in order, each with output of the last
+fn run(hooks: &[Hook], path: &str, content: &str) -> String {
+  let mut content = content.to_string();
+  for hook in hooks {
+    content = hook(path, &content);
+  }
+  content
+}
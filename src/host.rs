@@ -0,0 +1,331 @@
+use std::{
+  fs,
+  path::{Path, PathBuf},
+};
+
+/// File with custom domain of site, for GitHub Pages, see `Config::cname`
+pub(crate) const CNAME_FILE: &str = "CNAME";
+
+/// Empty file which turns off Jekyll on GitHub Pages, written with `Host::GitHubPages`
+pub(crate) const NOJEKYLL_FILE: &str = ".nojekyll";
+
+/// Preset for the production host of the site
+///
+/// The dev server sends the same response headers as the host, so issues with headers (such as a `Content-Security-Policy` blocking a script) appear locally
+///
+/// Default: `Host::Generic`
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum Host {
+  /// No host-specific behavior
+  #[default]
+  Generic,
+  /// GitHub Pages
+  ///
+  /// Sends the default headers of GitHub Pages, which allow framing and cross-origin requests
+  ///
+  /// Writes an empty `.nojekyll` file to build directory, so files starting with `_` are served
+  GitHubPages,
+  /// Netlify
+  ///
+  /// Sends headers from a `_headers` file, in root of build directory or public directory
+  ///
+  /// Redirects and rewrites with rules from a `_redirects` file, in the same directories
+  ///
+  /// See [Netlify docs](https://docs.netlify.com/routing/headers/) and [redirects](https://docs.netlify.com/routing/redirects/)
+  Netlify,
+  /// Custom headers, sent with every response
+  Custom(Vec<(String, String)>),
+}
+
+impl Host {
+  /// Get response headers which the host would send for a request path
+  ///
+  /// `build`: Build directory, to read host files (such as `_headers`) from
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use unreact::prelude::*;
+  ///
+  /// let host = Host::Custom(vec![("X-Frame-Options".to_string(), "DENY".to_string())]);
+  ///
+  /// assert_eq!(host.headers("/index.html", "build"), vec![("X-Frame-Options".to_string(), "DENY".to_string())]);
+  /// ```
+  pub fn headers(&self, path: &str, build: &str) -> Vec<(String, String)> {
+    match self {
+      Host::Generic => Vec::new(),
+
+      Host::GitHubPages => vec![
+        ("Access-Control-Allow-Origin".to_string(), "*".to_string()),
+        ("Cache-Control".to_string(), "max-age=600".to_string()),
+        (
+          "Strict-Transport-Security".to_string(),
+          "max-age=31556952".to_string(),
+        ),
+      ],
+
+      Host::Netlify => {
+        // Read file on every request, as it can change between builds
+        let content = fs::read_to_string(format!("./{build}/_headers"))
+          .or_else(|_| fs::read_to_string(format!("./{build}/public/_headers")))
+          .unwrap_or_default();
+        netlify_headers(&content, path)
+      }
+
+      Host::Custom(headers) => headers.clone(),
+    }
+  }
+
+  /// Get redirect or rewrite which the host would apply to a request path
+  ///
+  /// Only `Host::Netlify` has rules, from a `_redirects` file
+  ///
+  /// `build`: Build directory, to read host files (such as `_redirects`) from
+  pub fn redirect(&self, path: &str, build: &str) -> Option<HostRedirect> {
+    match self {
+      Host::Netlify => {
+        // Read file on every request, as it can change between builds
+        let content = fs::read_to_string(format!("./{build}/_redirects"))
+          .or_else(|_| fs::read_to_string(format!("./{build}/public/_redirects")))
+          .ok()?;
+        netlify_redirect(&content, path)
+      }
+      _ => None,
+    }
+  }
+}
+
+/// Redirect or rewrite of a request path, from rules of host, see `Host::redirect`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HostRedirect {
+  /// Path or url to send request to, with placeholders replaced
+  pub to: String,
+  /// Status of response, such as `301` to redirect, `200` to rewrite, or `404` to serve a custom not found page
+  pub status: u16,
+  /// If rule applies even when a file exists at path
+  pub force: bool,
+}
+
+impl HostRedirect {
+  /// If rule redirects browser to `to`, instead of serving the file at `to`
+  pub fn is_redirect(&self) -> bool {
+    (300..400).contains(&self.status)
+  }
+}
+
+/// Parse Netlify `_headers` file, and get headers for all rules matching path
+///
+/// Rules are a path pattern on its own line, followed by indented `Name: value` lines
+///
+/// # Examples
+///
+/// ```
+/// let file = "/*\n  X-Frame-Options: DENY\n/blog/*\n  Cache-Control: no-cache\n";
+///
+/// assert_eq!(
+///   unreact::netlify_headers(file, "/blog/post"),
+///   vec![
+///     ("X-Frame-Options".to_string(), "DENY".to_string()),
+///     ("Cache-Control".to_string(), "no-cache".to_string()),
+///   ],
+/// );
+/// ```
+pub fn netlify_headers(content: &str, path: &str) -> Vec<(String, String)> {
+  let mut headers = Vec::new();
+  // If current rule matches path
+  let mut matches = false;
+
+  for line in content.lines() {
+    // Skip comments and empty lines
+    let trimmed = line.trim();
+    if trimmed.is_empty() || trimmed.starts_with('#') {
+      continue;
+    }
+
+    if !line.starts_with(char::is_whitespace) {
+      // Path pattern
+      matches = path_matches(trimmed, path);
+    } else if matches {
+      // Header of rule
+      if let Some((name, value)) = trimmed.split_once(':') {
+        headers.push((name.trim().to_string(), value.trim().to_string()));
+      }
+    }
+  }
+
+  headers
+}
+
+/// Parse Netlify `_redirects` file, and get first rule matching path
+///
+/// Rules are a path pattern, a path or url to send to, and an optional status (default `301`), with `!` to apply even when a file exists at path.
+/// `:name` placeholders and `:splat` (the part matched by `*`) are replaced in the path to send to
+///
+/// Trailing slashes are ignored, like Netlify. Rules with conditions (such as query parameters or `Country=`) are skipped
+///
+/// # Examples
+///
+/// ```
+/// use unreact::prelude::*;
+///
+/// let file = "/old  /new\n/blog/:year/*  /posts/:year/:splat  302\n/app/*  /app/index.html  200!\n";
+///
+/// assert_eq!(
+///   unreact::netlify_redirect(file, "/blog/2023/hello/"),
+///   Some(HostRedirect { to: "/posts/2023/hello".to_string(), status: 302, force: false }),
+/// );
+/// assert_eq!(
+///   unreact::netlify_redirect(file, "/app/settings"),
+///   Some(HostRedirect { to: "/app/index.html".to_string(), status: 200, force: true }),
+/// );
+/// assert_eq!(unreact::netlify_redirect(file, "/about"), None);
+/// ```
+pub fn netlify_redirect(content: &str, path: &str) -> Option<HostRedirect> {
+  let path = match path.len() > 1 {
+    true => path.trim_end_matches('/'),
+    false => path,
+  };
+
+  for line in content.lines() {
+    let mut fields = line.split_whitespace();
+    // Skip comments and empty lines
+    let Some(from) = fields.next().filter(|from| !from.starts_with('#')) else {
+      continue;
+    };
+    let Some(to) = fields.next() else {
+      continue;
+    };
+
+    let mut status = 301;
+    let mut force = false;
+    let mut has_conditions = false;
+    for field in fields {
+      if field.starts_with('#') {
+        break;
+      }
+      match field.trim_end_matches('!').parse() {
+        Ok(code) => {
+          status = code;
+          force = field.ends_with('!');
+        }
+        Err(_) => has_conditions = true,
+      }
+    }
+    if has_conditions {
+      continue;
+    }
+
+    let from = match from.len() > 1 {
+      true => from.trim_end_matches('/'),
+      false => from,
+    };
+    let Some(mut captures) = path_captures(from, path) else {
+      continue;
+    };
+    // Longest names first, so `:id` does not replace part of `:idx`
+    captures.sort_by_key(|(name, _)| std::cmp::Reverse(name.len()));
+    let mut to = to.to_string();
+    for (name, value) in captures {
+      to = to.replace(&format!(":{name}"), &value);
+    }
+
+    return Some(HostRedirect { to, status, force });
+  }
+
+  None
+}
+
+/// Check if request path matches Netlify path pattern
+fn path_matches(pattern: &str, path: &str) -> bool {
+  path_captures(pattern, path).is_some()
+}
+
+/// Match request path with Netlify path pattern, and get values of placeholders
+///
+/// `*` matches any characters, as `splat`, and `:name` placeholders match a single path segment
+fn path_captures(pattern: &str, path: &str) -> Option<Vec<(String, String)>> {
+  // Full url patterns, for other domains, are not supported
+  if !pattern.starts_with('/') {
+    return None;
+  }
+
+  let pattern_parts = pattern.split('/').collect::<Vec<_>>();
+  let path_parts = path.split('/').collect::<Vec<_>>();
+  let mut captures = Vec::new();
+
+  for (i, part) in pattern_parts.iter().enumerate() {
+    if *part == "*" {
+      // Splat matches rest of path
+      let rest = path_parts.get(i..).unwrap_or_default().join("/");
+      captures.push(("splat".to_string(), rest));
+      return Some(captures);
+    }
+    match path_parts.get(i) {
+      Some(segment) if part.starts_with(':') => {
+        captures.push((part[1..].to_string(), segment.to_string()))
+      }
+      Some(segment) if part == segment => (),
+      _ => return None,
+    }
+  }
+
+  (pattern_parts.len() == path_parts.len()).then_some(captures)
+}
+
+/// Get owner and name of GitHub repository, such as `"user/repo"`, from `origin` remote of git repository of current directory or a parent
+///
+/// Used in dev mode outside of GitHub Actions, so the site has the same base path as when deployed, see `Config::github_base_path`
+pub(crate) fn local_repository() -> Option<String> {
+  let mut dir = std::env::current_dir().ok()?;
+  let config = loop {
+    let config = git_dir(&dir).map(|git| git.join("config"));
+    if let Some(config) = config.filter(|config| config.is_file()) {
+      break fs::read_to_string(config).ok()?;
+    }
+    if !dir.pop() {
+      return None;
+    }
+  };
+
+  let mut in_origin = false;
+  for line in config.lines().map(str::trim) {
+    if line.starts_with('[') {
+      in_origin = line == r#"[remote "origin"]"#;
+      continue;
+    }
+    let Some(("url", url)) = line
+      .split_once('=')
+      .map(|(key, value)| (key.trim(), value.trim()))
+    else {
+      continue;
+    };
+    if !in_origin || !url.contains("github.com") {
+      continue;
+    }
+    // `git@github.com:user/repo.git` or `https://github.com/user/repo`
+    let url = url.trim_end_matches('/').trim_end_matches(".git");
+    let mut parts = url.rsplit(['/', ':']);
+    let (name, owner) = (parts.next()?, parts.next()?);
+    return Some(format!("{owner}/{name}"));
+  }
+  None
+}
+
+/// Get directory of git repository in `dir`, which has its config
+///
+/// In worktrees and submodules, `.git` is a file with path of directory, such as `gitdir: ../.git/modules/site`.
+/// Worktrees share config of main repository, which is in `commondir`
+fn git_dir(dir: &Path) -> Option<PathBuf> {
+  let git = dir.join(".git");
+  let git = match git.is_file() {
+    true => {
+      let content = fs::read_to_string(&git).ok()?;
+      dir.join(content.trim().strip_prefix("gitdir:")?.trim())
+    }
+    false => git,
+  };
+  match fs::read_to_string(git.join("commondir")) {
+    Ok(common) => Some(git.join(common.trim())),
+    Err(_) => Some(git),
+  }
+}
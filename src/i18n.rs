@@ -0,0 +1,161 @@
+use handlebars::{
+  html_escape, Context, Handlebars, Helper, HelperResult, Output, RenderContext, RenderError,
+};
+use serde_json::{json, Value};
+use std::{collections::BTreeMap, fs, sync::Arc};
+
+use crate::{merge_json, UnreactError, UnreactResult};
+
+/// Locales of site, with translations from files in `locales` directory, see `Unreact::set_locales`
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Locales {
+  /// Names of locales, with default locale first
+  pub names: Vec<String>,
+  /// Translations of each locale, by name of locale
+  translations: BTreeMap<String, Value>,
+}
+
+impl Locales {
+  /// Read translations of every locale, from `./{dir}/{locale}.json`
+  ///
+  /// Returns error if a file is missing or is not a JSON object
+  pub fn read(dir: &str, names: &[String]) -> UnreactResult<Self> {
+    let mut translations = BTreeMap::new();
+    for name in names {
+      let path = format!("./{dir}/{name}.json");
+      let content = match fs::read_to_string(&path) {
+        Ok(x) => x,
+        Err(err) => return Err(UnreactError::IoError(err, path)),
+      };
+      let value = match serde_json::from_str(&content) {
+        Ok(value @ Value::Object(_)) => value,
+        Ok(_) => {
+          return Err(UnreactError::TranslationFail(
+            path,
+            "File must contain an object".to_string(),
+          ))
+        }
+        Err(err) => return Err(UnreactError::TranslationFail(path, err.to_string())),
+      };
+      translations.insert(name.to_string(), value);
+    }
+
+    Ok(Locales {
+      names: names.to_vec(),
+      translations,
+    })
+  }
+
+  /// If no locales are set
+  pub fn is_empty(&self) -> bool {
+    self.names.is_empty()
+  }
+
+  /// Get base path of locale, with default locale at root (`""`), and other locales in a subdirectory, such as `"/de"`
+  fn locale_path(&self, locale: &str) -> String {
+    if self.names.first().map(String::as_str) == Some(locale) {
+      String::new()
+    } else {
+      format!("/{locale}")
+    }
+  }
+
+  /// Get path of page in locale, such as `about` or `de/about`
+  fn page_path(&self, locale: &str, path: &str) -> String {
+    format!("{}/{path}", self.locale_path(locale))
+      .trim_start_matches('/')
+      .to_string()
+  }
+
+  /// Get path and data of page for each locale
+  ///
+  /// Data has `locale`, `locale_path` (such as `""` or `"/de"`, for links), and `alternates`, a list of `locale` and `path` of the page in every locale.
+  /// Data which is not an object is not changed
+  ///
+  /// Returns path and data unchanged if no locales are set
+  pub fn localize(&self, path: &str, data: &Value) -> Vec<(String, Value)> {
+    if self.is_empty() {
+      return vec![(path.to_string(), data.clone())];
+    }
+
+    let alternates = (self.names.iter())
+      .map(|locale| json!({ "locale": locale, "path": self.page_path(locale, path) }))
+      .collect::<Vec<_>>();
+
+    (self.names.iter())
+      .map(|locale| {
+        let path = self.page_path(locale, path);
+        let mut data = data.clone();
+        if data.is_object() || data.is_null() {
+          merge_json(
+            &mut data,
+            json!({
+              "locale": locale,
+              "locale_path": self.locale_path(locale),
+              "alternates": alternates,
+            }),
+          );
+        }
+        (path, data)
+      })
+      .collect()
+  }
+
+  /// Get translation of dotted key (such as `nav.home`) in locale, or in default locale if missing
+  fn translate(&self, locale: &str, key: &str) -> Option<&str> {
+    let find = |locale: &str| {
+      let mut value = self.translations.get(locale)?;
+      for part in key.split('.') {
+        value = value.get(part)?;
+      }
+      value.as_str()
+    };
+    find(locale).or_else(|| find(self.names.first()?))
+  }
+
+  /// Hash input of translations, for keys of build cache
+  pub fn fingerprint(&self) -> String {
+    format!("{:?} {:?}", self.names, self.translations)
+  }
+}
+
+/// Register `t` helper, which translates a key for locale of page, such as `{{t "nav.home"}}`
+///
+/// Hash arguments replace `{name}` in translation, such as `{{t "greeting" name=user}}`, and are escaped
+pub(crate) fn register_translate_helper(reg: &mut Handlebars, locales: Arc<Locales>) {
+  reg.register_helper(
+    "t",
+    Box::new(
+      move |h: &Helper,
+            _: &Handlebars,
+            ctx: &Context,
+            _: &mut RenderContext,
+            out: &mut dyn Output|
+            -> HelperResult {
+        let Some(key) = h.param(0).and_then(|x| x.value().as_str()) else {
+          return Err(RenderError::new("Helper `t` requires a translation key"));
+        };
+        // Pages registered before locales are set use default locale
+        let locale = (ctx.data().get("locale").and_then(Value::as_str))
+          .or_else(|| locales.names.first().map(String::as_str))
+          .unwrap_or_default();
+
+        let Some(text) = locales.translate(locale, key) else {
+          return Err(RenderError::new(format!(
+            "Helper `t` has no translation of `{key}` for locale `{locale}`"
+          )));
+        };
+        let mut text = text.to_string();
+        for (name, value) in h.hash() {
+          let value = match value.value() {
+            Value::String(value) => value.to_string(),
+            value => value.to_string(),
+          };
+          text = text.replace(&format!("{{{name}}}"), &html_escape(&value));
+        }
+        out.write(&text)?;
+        Ok(())
+      },
+    ),
+  );
+}
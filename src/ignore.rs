@@ -0,0 +1,193 @@
+use std::fs;
+
+use crate::{list_files, Config};
+
+/// Names of files with patterns of files to leave out, see `Config::ignore_files`
+pub(crate) const IGNORE_FILES: [&str; 2] = [".gitignore", ".unreactignore"];
+
+/// Patterns of `.gitignore` and `.unreactignore` files, to leave out files when loading directories
+///
+/// Supports comments, `!` to include again, `/` to anchor to directory of file, trailing `/` for directories only, and `*`, `?`, `**`, and `[...]` wildcards
+#[derive(Debug, Clone, Default)]
+pub(crate) struct IgnoreRules {
+  rules: Vec<Rule>,
+}
+
+/// Single pattern of an ignore file
+#[derive(Debug, Clone)]
+struct Rule {
+  /// Directory of ignore file, relative to workspace, or empty for workspace
+  base: String,
+  /// Pattern, without `!`, and leading or trailing `/`
+  pattern: String,
+  /// If pattern includes matching files again
+  negate: bool,
+  /// If pattern only matches directories
+  dir_only: bool,
+  /// If pattern is matched with path relative to `base`, instead of name of file
+  anchored: bool,
+}
+
+impl IgnoreRules {
+  /// Read ignore files of workspace, and of directory and its subdirectories, if `ignore_files` is set in config
+  ///
+  /// Rules of workspace apply to paths relative to workspace, such as `public/notes.txt`
+  pub fn read(config: &Config, dir: &str) -> Self {
+    let mut rules = IgnoreRules::default();
+    if !config.ignore_files {
+      return rules;
+    }
+
+    for name in IGNORE_FILES {
+      rules.add_file("", name);
+    }
+    for file in list_files(dir).unwrap_or_default() {
+      let (parent, name) = match file.rsplit_once('/') {
+        Some((parent, name)) => (format!("{dir}/{parent}"), name),
+        None => (dir.to_string(), file.as_str()),
+      };
+      if IGNORE_FILES.contains(&name) {
+        rules.add_file(&parent, name);
+      }
+    }
+    rules
+  }
+
+  /// Add rules of ignore file in directory, if it exists
+  fn add_file(&mut self, base: &str, name: &str) {
+    let path = match base.is_empty() {
+      true => format!("./{name}"),
+      false => format!("./{base}/{name}"),
+    };
+    let Ok(content) = fs::read_to_string(path) else {
+      return;
+    };
+
+    for line in content.lines() {
+      let line = line.trim_end();
+      if line.is_empty() || line.starts_with('#') {
+        continue;
+      }
+      let (negate, line) = match line.strip_prefix('!') {
+        Some(line) => (true, line),
+        None => (false, line.strip_prefix('\\').unwrap_or(line)),
+      };
+      let (dir_only, line) = match line.strip_suffix('/') {
+        Some(line) => (true, line),
+        None => (false, line),
+      };
+      // Slash at start or in middle anchors pattern to directory of file
+      let anchored = line.contains('/');
+      self.rules.push(Rule {
+        base: base.trim_matches('/').to_string(),
+        pattern: line.trim_start_matches('/').to_string(),
+        negate,
+        dir_only,
+        anchored,
+      });
+    }
+  }
+
+  /// If file is left out, or is in a directory which is left out
+  ///
+  /// `dir`: Directory of file, relative to workspace, such as `public`
+  ///
+  /// `file`: Path of file relative to `dir`, such as `img/logo.png`
+  pub fn is_ignored(&self, dir: &str, file: &str) -> bool {
+    if self.rules.is_empty() {
+      return false;
+    }
+    let name = file.rsplit('/').next().unwrap_or(file);
+    // Ignore files are only for Unreact and git
+    if IGNORE_FILES.contains(&name) {
+      return true;
+    }
+
+    let path = format!("{}/{file}", dir.trim_matches('/'));
+    let parts = path.split('/').collect::<Vec<_>>();
+    // Files in ignored directories cannot be included again, like git
+    (1..=parts.len()).any(|i| self.matches(&parts[..i].join("/"), i < parts.len()))
+  }
+
+  /// If path is left out by last matching rule
+  fn matches(&self, path: &str, is_dir: bool) -> bool {
+    let mut ignored = false;
+    for rule in &self.rules {
+      if rule.dir_only && !is_dir {
+        continue;
+      }
+      let relative = match rule.base.is_empty() {
+        true => Some(path),
+        false => path
+          .strip_prefix(&rule.base)
+          .and_then(|rest| rest.strip_prefix('/')),
+      };
+      let Some(relative) = relative else {
+        continue;
+      };
+      let target = match rule.anchored {
+        true => relative,
+        false => relative.rsplit('/').next().unwrap_or(relative),
+      };
+      if glob_match(rule.pattern.as_bytes(), target.as_bytes()) {
+        ignored = !rule.negate;
+      }
+    }
+    ignored
+  }
+}
+
+/// Filter files of directory which are not left out by ignore files, see `Config::ignore_files`
+pub(crate) fn filter_ignored(config: &Config, dir: &str, files: Vec<String>) -> Vec<String> {
+  let rules = IgnoreRules::read(config, dir);
+  (files.into_iter())
+    .filter(|file| !rules.is_ignored(dir, file))
+    .collect()
+}
+
+/// Match text with glob pattern, where `*` and `?` do not match `/`, and `**` matches any directories
+fn glob_match(pattern: &[u8], text: &[u8]) -> bool {
+  match pattern {
+    [] => text.is_empty(),
+    // `**/` matches zero or more directories
+    [b'*', b'*', b'/', rest @ ..] => {
+      glob_match(rest, text)
+        || (0..text.len())
+          .filter(|i| text[*i] == b'/')
+          .any(|i| glob_match(rest, &text[i + 1..]))
+    }
+    // `**` at end matches everything
+    [b'*', b'*'] => true,
+    [b'*', rest @ ..] => (0..=text.len())
+      .take_while(|i| *i == 0 || text[i - 1] != b'/')
+      .any(|i| glob_match(rest, &text[i..])),
+    [b'?', rest @ ..] => {
+      matches!(text.first(), Some(c) if *c != b'/') && glob_match(rest, &text[1..])
+    }
+    [b'[', rest @ ..] => {
+      let Some(end) = rest.iter().skip(1).position(|c| *c == b']').map(|i| i + 1) else {
+        return text.first() == Some(&b'[') && glob_match(rest, &text[1..]);
+      };
+      let Some(c) = text.first() else {
+        return false;
+      };
+      let (negate, class) = match rest[0] {
+        b'!' | b'^' => (true, &rest[1..end]),
+        _ => (false, &rest[..end]),
+      };
+      let mut found = false;
+      let mut i = 0;
+      while i < class.len() {
+        if i + 2 < class.len() && class[i + 1] == b'-' {
+          found |= (class[i]..=class[i + 2]).contains(c);
+          i += 3;
+        } else {
+          found |= class[i] == *c;
+          i += 1;
+        }
+      }
+      found != negate && *c != b'/' && glob_match(&rest[end + 1..], &text[1..])
+    }
+    [c, rest @ ..] => text.first() == Some(c) && glob_match(rest, &text[1..]),
+  }
+}
@@ -0,0 +1,1062 @@
+use flate2::{read::ZlibDecoder, write::ZlibEncoder, Compression};
+use std::{
+  fmt, fs,
+  io::{Read, Write},
+  path::Path,
+};
+
+use crate::{hash_content, UnreactError, UnreactResult};
+
+/// Options for processing images of public directory, see `Config::images`
+///
+/// Images are processed with `PngProcessor`, unless another processor is set with `Unreact::set_image_processor`.
+/// Images which the processor does not support are copied unchanged, without variants
+///
+/// Use `{{>IMAGE src="public/cat.png" alt="A cat"}}` to show an image with every variant, see `Unreact::set_image_processor`
+///
+/// Default: No processing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageOptions {
+  /// Widths of resized variants of each image, in pixels, such as `[480, 960, 1920]`
+  ///
+  /// Variant of `img/cat.png` with width `480` is `img/cat-480w.png`. Widths which are not smaller than the image are skipped
+  ///
+  /// Default: `[]`
+  pub widths: Vec<u32>,
+  /// If images are encoded again with the processor, to make them smaller
+  ///
+  /// Original image is kept if it is already smaller.
+  /// The default `PngProcessor` only compresses PNG images, so JPEG images are copied unchanged (with a warning), unless another processor is set
+  ///
+  /// Default: `false`
+  pub compress: bool,
+  /// If a WebP copy of each image and variant is created, such as `img/cat.webp` and `img/cat-480w.webp`
+  ///
+  /// **Not supported by the default `PngProcessor`**, so no WebP copies are created (with a warning), unless a processor which encodes WebP is set with `Unreact::set_image_processor`
+  ///
+  /// Default: `false`
+  pub webp: bool,
+  /// Directory of processed images, kept between builds so unchanged images are not processed again
+  ///
+  /// Default: `".unreact-images"`
+  pub dir: String,
+}
+
+impl Default for ImageOptions {
+  fn default() -> Self {
+    ImageOptions {
+      widths: Vec::new(),
+      compress: false,
+      webp: false,
+      dir: ".unreact-images".to_string(),
+    }
+  }
+}
+
+impl ImageOptions {
+  /// If any images are processed
+  pub(crate) fn is_enabled(&self) -> bool {
+    self.compress || self.webp || !self.widths.is_empty()
+  }
+}
+
+/// Format of image file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ImageFormat {
+  /// `.png`
+  Png,
+  /// `.jpg` or `.jpeg`
+  Jpeg,
+  /// `.webp`
+  WebP,
+  /// `.gif`
+  Gif,
+}
+
+impl ImageFormat {
+  /// Get format from extension of file, such as `img/cat.png`
+  ///
+  /// Returns `None` if file is not an image, or is an unsupported format, such as `.svg`
+  pub fn from_path(path: &str) -> Option<Self> {
+    let extension = path.rsplit_once('.')?.1.to_ascii_lowercase();
+    Some(match extension.as_str() {
+      "png" => ImageFormat::Png,
+      "jpg" | "jpeg" => ImageFormat::Jpeg,
+      "webp" => ImageFormat::WebP,
+      "gif" => ImageFormat::Gif,
+      _ => return None,
+    })
+  }
+
+  /// File extension of format, without `.`
+  pub fn extension(&self) -> &'static str {
+    match self {
+      ImageFormat::Png => "png",
+      ImageFormat::Jpeg => "jpg",
+      ImageFormat::WebP => "webp",
+      ImageFormat::Gif => "gif",
+    }
+  }
+}
+
+/// Encoder of images for `Config::images`, see `Unreact::set_image_processor`
+///
+/// `PngProcessor` is used by default. Implement this trait to support other formats, such as with the `image` crate
+///
+/// Processed images are saved in `dir` of `Config::images`, by hash of image and arguments, so the processor must give the same output for the same input
+///
+/// # Examples
+///
+/// ```
+/// use unreact::prelude::*;
+///
+/// #[derive(Debug)]
+/// struct Resizer;
+///
+/// impl ImageProcessor for Resizer {
+///   fn supports(&self, from: ImageFormat, _to: ImageFormat) -> bool {
+///     from == ImageFormat::Png
+///   }
+///
+///   fn process(&self, _image: &[u8], _from: ImageFormat, to: ImageFormat, width: Option<u32>) -> Result<Vec<u8>, String> {
+///     // Decode, resize, and encode image, such as with the `image` crate
+///     Ok(format!("{to:?} {width:?}").into_bytes())
+///   }
+/// }
+///
+/// fn main() -> UnreactResult<()> {
+///   // Public directory in a temporary path, relative to current directory
+///   let dir = "target/unreact-images-example";
+///   std::fs::create_dir_all(format!("{dir}/public")).unwrap();
+///
+///   // Header of a 1000x500 PNG
+///   let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+///   png.extend(1000u32.to_be_bytes());
+///   png.extend(500u32.to_be_bytes());
+///   std::fs::write(format!("{dir}/public/cat.png"), png).unwrap();
+///
+///   let mut vfs = Vfs::new();
+///   vfs.templates.insert("index".to_string(), r#"{{>IMAGE src="public/cat.png" alt="A cat"}}"#.to_string());
+///
+///   let config = Config {
+///     public: format!("{dir}/public"),
+///     images: ImageOptions {
+///       widths: vec![480, 1920],
+///       webp: true,
+///       dir: format!("{dir}/cache"),
+///       ..ImageOptions::default()
+///     },
+///     ..Config::default()
+///   };
+///   let url = SiteUrl::parse("https://mysite.com")?;
+///   let mut app = Unreact::from_vfs(config, false, url, vfs);
+///   app.set_image_processor(Resizer).index("index", &serde_json::json!({}))?;
+///
+///   let files = app.compile()?;
+///   assert!(files.contains_key("public/cat.png"));
+///   assert!(files.contains_key("public/cat.webp"));
+///   // Image is smaller than 1920
+///   assert!(!files.contains_key("public/cat-1920w.png"));
+///   assert_eq!(std::fs::read_to_string(&files["public/cat-480w.webp"]).unwrap(), "WebP Some(480)");
+///
+///   assert!(files["index.html"].contains(
+///     r#"srcset="https://mysite.com/public/cat-480w.webp 480w, https://mysite.com/public/cat.webp 1000w""#
+///   ));
+///
+///   Ok(())
+/// }
+/// ```
+pub trait ImageProcessor: fmt::Debug + Send + Sync {
+  /// If images can be converted from one format to another
+  ///
+  /// `from` and `to` are the same format for compressed and resized images
+  fn supports(&self, from: ImageFormat, to: ImageFormat) -> bool;
+
+  /// Convert image, and resize to `width` (keeping aspect ratio) if given
+  ///
+  /// `width` is always smaller than width of image
+  ///
+  /// Returns reason if image cannot be processed
+  fn process(
+    &self,
+    image: &[u8],
+    from: ImageFormat,
+    to: ImageFormat,
+    width: Option<u32>,
+  ) -> Result<Vec<u8>, String>;
+}
+
+/// Inbuilt image processor, which compresses and resizes PNG images
+///
+/// JPEG compression and WebP conversion are **not** supported: JPEG, GIF, and WebP images are copied unchanged, and no WebP copies are created.
+/// Set another `ImageProcessor` for those, such as with the `image` crate.
+/// Color profiles of PNG images (`iCCP`, `sRGB`, `gAMA`, and `cHRM` chunks) are kept, but other metadata (such as text) is removed
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PngProcessor;
+
+impl ImageProcessor for PngProcessor {
+  fn supports(&self, from: ImageFormat, to: ImageFormat) -> bool {
+    from == ImageFormat::Png && to == ImageFormat::Png
+  }
+
+  fn process(
+    &self,
+    image: &[u8],
+    _from: ImageFormat,
+    _to: ImageFormat,
+    width: Option<u32>,
+  ) -> Result<Vec<u8>, String> {
+    let mut pixels = decode_png(image)?;
+    if let Some(width) = width {
+      pixels = pixels.resize(width);
+    }
+    encode_png(&pixels)
+  }
+}
+
+/// Get width and height of image, from header of file
+///
+/// Returns `None` if format is unknown or file is invalid
+pub(crate) fn image_size(image: &[u8]) -> Option<(u32, u32)> {
+  let be16 = |i: usize| Some(u16::from_be_bytes(image.get(i..i + 2)?.try_into().ok()?) as u32);
+  let le16 = |i: usize| Some(u16::from_le_bytes(image.get(i..i + 2)?.try_into().ok()?) as u32);
+  let le24 = |i: usize| {
+    let bytes = image.get(i..i + 3)?;
+    Some(bytes[0] as u32 | (bytes[1] as u32) << 8 | (bytes[2] as u32) << 16)
+  };
+
+  if image.starts_with(PNG_SIGNATURE) {
+    let size = image.get(16..24)?;
+    let width = u32::from_be_bytes(size[..4].try_into().ok()?);
+    let height = u32::from_be_bytes(size[4..].try_into().ok()?);
+    return Some((width, height));
+  }
+  if image.starts_with(b"GIF8") {
+    return Some((le16(6)?, le16(8)?));
+  }
+  if image.starts_with(b"RIFF") && image.get(8..12)? == b"WEBP" {
+    return match image.get(12..16)? {
+      b"VP8 " => Some((le16(26)? & 0x3fff, le16(28)? & 0x3fff)),
+      b"VP8L" => {
+        let bits = u32::from_le_bytes(image.get(21..25)?.try_into().ok()?);
+        Some((1 + (bits & 0x3fff), 1 + ((bits >> 14) & 0x3fff)))
+      }
+      b"VP8X" => Some((1 + le24(24)?, 1 + le24(27)?)),
+      _ => None,
+    };
+  }
+  if image.starts_with(&[0xff, 0xd8]) {
+    // Find start of frame segment
+    let mut i = 2;
+    while *image.get(i)? == 0xff {
+      let marker = *image.get(i + 1)?;
+      if (0xc0..=0xcf).contains(&marker) && !matches!(marker, 0xc4 | 0xc8 | 0xcc) {
+        return Some((be16(i + 7)?, be16(i + 5)?));
+      }
+      i += 2 + be16(i + 2)? as usize;
+    }
+  }
+  None
+}
+
+/// Variant of a public image
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Variant {
+  /// Width to resize to, or `None` for original size
+  pub width: Option<u32>,
+  pub format: ImageFormat,
+}
+
+impl Variant {
+  /// Get path of variant, from path of image, such as `img/cat-480w.webp` for `img/cat.png`
+  pub fn path(&self, path: &str) -> String {
+    let stem = path.rsplit_once('.').map_or(path, |(stem, _)| stem);
+    match self.width {
+      Some(width) => format!("{stem}-{width}w.{}", self.format.extension()),
+      None => format!("{stem}.{}", self.format.extension()),
+    }
+  }
+}
+
+/// Get every variant of image to create, other than the original image
+///
+/// `size`: Width and height of original image
+pub(crate) fn variants(
+  options: &ImageOptions,
+  processor: &dyn ImageProcessor,
+  format: ImageFormat,
+  size: (u32, u32),
+) -> Vec<Variant> {
+  let mut widths = (options.widths.iter())
+    .copied()
+    .filter(|width| *width > 0 && *width < size.0)
+    .collect::<Vec<_>>();
+  widths.sort_unstable();
+  widths.dedup();
+
+  let mut formats = vec![format];
+  if options.webp && format != ImageFormat::WebP {
+    formats.push(ImageFormat::WebP);
+  }
+
+  let mut variants = Vec::new();
+  for to in formats {
+    if !processor.supports(format, to) {
+      continue;
+    }
+    for width in &widths {
+      variants.push(Variant {
+        width: Some(*width),
+        format: to,
+      });
+    }
+    if to != format {
+      variants.push(Variant {
+        width: None,
+        format: to,
+      });
+    }
+  }
+  variants
+}
+
+/// Get warning if processor cannot create every output in options for images of format, such as WebP copies with `PngProcessor`
+pub(crate) fn unsupported_warning(
+  options: &ImageOptions,
+  processor: &dyn ImageProcessor,
+  format: ImageFormat,
+) -> Option<String> {
+  let same_format = processor.supports(format, format);
+  let mut options = [
+    ("compress", options.compress && !same_format),
+    ("widths", !options.widths.is_empty() && !same_format),
+    (
+      "webp",
+      options.webp && format != ImageFormat::WebP && !processor.supports(format, ImageFormat::WebP),
+    ),
+  ]
+  .into_iter()
+  .filter(|(_, unsupported)| *unsupported)
+  .map(|(option, _)| format!("`images.{option}`"))
+  .peekable();
+  options.peek()?;
+  Some(format!(
+    "Image processor {processor:?} does not support {} for {} images, so they are copied unchanged",
+    options.collect::<Vec<_>>().join(", "),
+    format.extension(),
+  ))
+}
+
+/// Process image into every variant (and compressed original, if enabled), saved in `dir` of options
+///
+/// `source`: Path of image in public directory
+///
+/// `path`: Output path of image, relative to build directory, such as `public/img/cat.png`
+///
+/// Returns output path and source file of original image and each variant.
+/// Returns only the original image if it is not a supported format
+pub(crate) fn process_image(
+  options: &ImageOptions,
+  processor: &dyn ImageProcessor,
+  source: &str,
+  path: &str,
+) -> UnreactResult<Vec<(String, String)>> {
+  let original = vec![(path.to_string(), source.to_string())];
+  let Some(format) = ImageFormat::from_path(source) else {
+    return Ok(original);
+  };
+  let image = fs::read(source).map_err(|err| UnreactError::IoError(err, source.to_string()))?;
+  let Some(size) = image_size(&image) else {
+    return Ok(original);
+  };
+
+  let hash = hash_content(&image);
+  let processed = |variant: Variant| -> UnreactResult<String> {
+    // Hash of image and arguments, so changing either processes image again
+    let key = format!("{hash} {processor:?} {variant:?}");
+    let file = format!(
+      "./{}/{}.{}",
+      options.dir,
+      &hash_content(key.as_bytes())[..32],
+      variant.format.extension()
+    );
+    if Path::new(&file).is_file() {
+      return Ok(file);
+    }
+
+    let content = processor
+      .process(&image, format, variant.format, variant.width)
+      .map_err(|reason| UnreactError::ImageFail(source.to_string(), reason))?;
+    fs::create_dir_all(&options.dir)
+      .map_err(|err| UnreactError::IoError(err, options.dir.to_string()))?;
+    fs::write(&file, content).map_err(|err| UnreactError::IoError(err, file.clone()))?;
+    Ok(file)
+  };
+
+  let mut outputs = Vec::new();
+  // Compressed original, if smaller
+  if options.compress && processor.supports(format, format) {
+    let file = processed(Variant {
+      width: None,
+      format,
+    })?;
+    let is_smaller = fs::metadata(&file).is_ok_and(|meta| (meta.len() as usize) < image.len());
+    outputs.push((
+      path.to_string(),
+      if is_smaller { file } else { source.to_string() },
+    ));
+  } else {
+    outputs.extend(original);
+  }
+
+  for variant in variants(options, processor, format, size) {
+    outputs.push((variant.path(path), processed(variant)?));
+  }
+  Ok(outputs)
+}
+
+/// Get `srcset` of image, with urls of every variant in format, and original image
+///
+/// `source`: Path of image in public directory
+///
+/// `url`: Url of image, such as `https://mysite.com/public/img/cat.png`
+///
+/// `format`: Format of variants, or `None` for format of image
+///
+/// Returns empty string if there are no variants in format
+pub(crate) fn srcset(
+  options: &ImageOptions,
+  processor: &dyn ImageProcessor,
+  source: &str,
+  url: &str,
+  format: Option<ImageFormat>,
+) -> String {
+  let Some(original) = ImageFormat::from_path(source) else {
+    return String::new();
+  };
+  let format = format.unwrap_or(original);
+  let size = fs::read(source).ok().and_then(|image| image_size(&image));
+  let Some(size) = size.filter(|_| options.is_enabled()) else {
+    return String::new();
+  };
+
+  let mut sources = (variants(options, processor, original, size).into_iter())
+    .filter(|variant| variant.format == format)
+    .filter_map(|variant| Some((variant.path(url), variant.width?)))
+    .collect::<Vec<_>>();
+  if format == original {
+    sources.push((url.to_string(), size.0));
+  } else if processor.supports(original, format) {
+    sources.push((
+      Variant {
+        width: None,
+        format,
+      }
+      .path(url),
+      size.0,
+    ));
+  }
+
+  // Original image only is not a set
+  if sources.len() < 2 {
+    return String::new();
+  }
+  (sources.iter())
+    .map(|(url, width)| format!("{url} {width}w"))
+    .collect::<Vec<_>>()
+    .join(", ")
+}
+
+/// First bytes of every PNG file
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// Chunks of PNG image which are kept when encoding again, as they change how colors are shown
+const COLOR_CHUNKS: [&[u8; 4]; 4] = [b"iCCP", b"sRGB", b"gAMA", b"cHRM"];
+
+/// Decoded image, as 8-bit RGBA pixels
+struct Pixels {
+  width: usize,
+  height: usize,
+  data: Vec<u8>,
+  /// Kind and data of each chunk in `COLOR_CHUNKS`
+  color_chunks: Vec<([u8; 4], Vec<u8>)>,
+}
+
+impl Pixels {
+  /// Resize image to width, keeping aspect ratio, by averaging pixels
+  ///
+  /// Colors are weighted by alpha, so transparent pixels do not darken edges
+  fn resize(&self, width: u32) -> Pixels {
+    let width = (width as usize).clamp(1, self.width);
+    let height = ((self.height * width + self.width / 2) / self.width).max(1);
+
+    let mut data = Vec::with_capacity(width * height * 4);
+    for y in 0..height {
+      let (top, bottom) = (y * self.height / height, (y + 1) * self.height / height);
+      for x in 0..width {
+        let (left, right) = (x * self.width / width, (x + 1) * self.width / width);
+        let mut sum = [0u64; 4];
+        let mut count = 0;
+        for row in top..bottom.max(top + 1) {
+          for column in left..right.max(left + 1) {
+            let i = (row * self.width + column) * 4;
+            let alpha = self.data[i + 3] as u64;
+            for (sum, value) in sum.iter_mut().zip(&self.data[i..i + 3]) {
+              *sum += *value as u64 * alpha;
+            }
+            sum[3] += alpha;
+            count += 1;
+          }
+        }
+        for channel in &sum[..3] {
+          data.push(channel.checked_div(sum[3]).unwrap_or(0) as u8);
+        }
+        data.push((sum[3] / count) as u8);
+      }
+    }
+    Pixels {
+      width,
+      height,
+      data,
+      color_chunks: self.color_chunks.clone(),
+    }
+  }
+}
+
+/// Decode PNG image into RGBA pixels
+fn decode_png(image: &[u8]) -> Result<Pixels, String> {
+  let Some(mut rest) = image.strip_prefix(PNG_SIGNATURE) else {
+    return Err("File is not a PNG image".to_string());
+  };
+
+  let mut header = None;
+  let mut palette = Vec::new();
+  let mut transparency = Vec::new();
+  let mut compressed = Vec::new();
+  let mut color_chunks = Vec::new();
+  while rest.len() >= 12 {
+    let length = u32::from_be_bytes(rest[..4].try_into().unwrap_or_default()) as usize;
+    let Some(data) = rest.get(8..8 + length) else {
+      return Err("Chunk is longer than file".to_string());
+    };
+    match &rest[4..8] {
+      b"IHDR" => header = Some(data.to_vec()),
+      b"PLTE" => palette = data.to_vec(),
+      b"tRNS" => transparency = data.to_vec(),
+      b"IDAT" => compressed.extend_from_slice(data),
+      b"IEND" => break,
+      kind if COLOR_CHUNKS.iter().any(|x| *x == kind) => {
+        color_chunks.push((kind.try_into().unwrap_or_default(), data.to_vec()))
+      }
+      _ => (),
+    }
+    rest = &rest[(12 + length).min(rest.len())..];
+  }
+
+  let Some(header) = header.filter(|header| header.len() == 13) else {
+    return Err("Missing header".to_string());
+  };
+  let width = u32::from_be_bytes(header[..4].try_into().unwrap_or_default()) as usize;
+  let height = u32::from_be_bytes(header[4..8].try_into().unwrap_or_default()) as usize;
+  let (depth, color, interlaced) = (header[8] as usize, header[9], header[12] == 1);
+  let channels = match color {
+    0 | 3 => 1,
+    2 => 3,
+    4 => 2,
+    6 => 4,
+    _ => return Err(format!("Unknown color type {color}")),
+  };
+  if width == 0 || height == 0 || !matches!(depth, 1 | 2 | 4 | 8 | 16) {
+    return Err("Invalid header".to_string());
+  }
+
+  let bits = channels * depth;
+  // Position and spacing of pixels in each pass of Adam7 interlacing
+  let passes: &[(usize, usize, usize, usize)] = if interlaced {
+    &[
+      (0, 0, 8, 8),
+      (4, 0, 8, 8),
+      (0, 4, 4, 8),
+      (2, 0, 4, 4),
+      (0, 2, 2, 4),
+      (1, 0, 2, 2),
+      (0, 1, 1, 2),
+    ]
+  } else {
+    &[(0, 0, 1, 1)]
+  };
+  // Width, height, and bytes in each row of each pass
+  let pass_sizes = (passes.iter())
+    .map(|(x0, y0, dx, dy)| {
+      let pass_width = (width + dx - 1 - x0) / dx;
+      let pass_height = (height + dy - 1 - y0) / dy;
+      (pass_width, pass_height, (pass_width * bits).div_ceil(8))
+    })
+    .collect::<Vec<_>>();
+
+  // Check size of image data before allocating pixels, so a small file cannot claim a huge image
+  let too_large = || "Image is too large".to_string();
+  let mut expected = 0usize;
+  for (pass_width, pass_height, row_length) in &pass_sizes {
+    if *pass_width > 0 && *pass_height > 0 {
+      expected = (row_length + 1)
+        .checked_mul(*pass_height)
+        .and_then(|size| expected.checked_add(size))
+        .ok_or_else(too_large)?;
+    }
+  }
+  let size = (width.checked_mul(height))
+    .and_then(|x| x.checked_mul(4))
+    .ok_or_else(too_large)?;
+
+  let mut filtered = Vec::new();
+  ZlibDecoder::new(compressed.as_slice())
+    .take(expected as u64)
+    .read_to_end(&mut filtered)
+    .map_err(|err| format!("Invalid image data - {err}"))?;
+  if filtered.len() < expected {
+    return Err("Image data is too short".to_string());
+  }
+
+  let mut pixels = Pixels {
+    width,
+    height,
+    data: vec![0; size],
+    color_chunks,
+  };
+  let mut rest = filtered.as_slice();
+  for ((x0, y0, dx, dy), (pass_width, pass_height, row_length)) in passes.iter().zip(pass_sizes) {
+    if pass_width == 0 || pass_height == 0 {
+      continue;
+    }
+    let size = (row_length + 1) * pass_height;
+    let (pass, next) = rest.split_at(size);
+    rest = next;
+
+    let rows = unfilter(pass, row_length, bits.div_ceil(8))?;
+    for (y, row) in rows.chunks(row_length).enumerate() {
+      for x in 0..pass_width {
+        let sample = |channel: usize| sample(row, x * channels + channel, depth);
+        let rgba = match color {
+          0 => {
+            let gray = sample(0);
+            let alpha = transparency_matches(&transparency, &[gray], depth);
+            [scale(gray, depth); 3]
+              .into_iter()
+              .chain([alpha])
+              .collect::<Vec<_>>()
+          }
+          2 => {
+            let rgb = [sample(0), sample(1), sample(2)];
+            let alpha = transparency_matches(&transparency, &rgb, depth);
+            rgb
+              .iter()
+              .map(|x| scale(*x, depth))
+              .chain([alpha])
+              .collect()
+          }
+          3 => {
+            let index = sample(0) as usize;
+            let Some(rgb) = palette.get(index * 3..index * 3 + 3) else {
+              return Err(format!("Missing color {index} in palette"));
+            };
+            let alpha = transparency.get(index).copied().unwrap_or(255);
+            rgb.iter().copied().chain([alpha]).collect()
+          }
+          4 => {
+            let gray = scale(sample(0), depth);
+            vec![gray, gray, gray, scale(sample(1), depth)]
+          }
+          _ => (0..4)
+            .map(|channel| scale(sample(channel), depth))
+            .collect(),
+        };
+        let i = ((y0 + y * dy) * width + x0 + x * dx) * 4;
+        pixels.data[i..i + 4].copy_from_slice(&rgba);
+      }
+    }
+  }
+  Ok(pixels)
+}
+
+/// Reverse filter of each row of PNG image data
+///
+/// `row_length`: Bytes in each row, without filter type
+///
+/// `step`: Bytes in each pixel, at least `1`
+fn unfilter(data: &[u8], row_length: usize, step: usize) -> Result<Vec<u8>, String> {
+  let mut rows = Vec::with_capacity(data.len());
+  let mut previous = vec![0; row_length];
+  for chunk in data.chunks(row_length + 1) {
+    let (filter, row) = (chunk[0], &chunk[1..]);
+    let mut current = row.to_vec();
+    for i in 0..row_length {
+      let left = if i >= step { current[i - step] } else { 0 };
+      let up = previous[i];
+      let up_left = if i >= step { previous[i - step] } else { 0 };
+      let predicted = match filter {
+        0 => 0,
+        1 => left,
+        2 => up,
+        3 => ((left as u16 + up as u16) / 2) as u8,
+        4 => paeth(left, up, up_left),
+        _ => return Err(format!("Unknown filter type {filter}")),
+      };
+      current[i] = current[i].wrapping_add(predicted);
+    }
+    rows.extend_from_slice(&current);
+    previous = current;
+  }
+  Ok(rows)
+}
+
+/// Paeth predictor of PNG filters
+fn paeth(left: u8, up: u8, up_left: u8) -> u8 {
+  let estimate = left as i16 + up as i16 - up_left as i16;
+  let (a, b, c) = (
+    (estimate - left as i16).abs(),
+    (estimate - up as i16).abs(),
+    (estimate - up_left as i16).abs(),
+  );
+  if a <= b && a <= c {
+    left
+  } else if b <= c {
+    up
+  } else {
+    up_left
+  }
+}
+
+/// Get sample in row of PNG image data, by index of sample
+fn sample(row: &[u8], index: usize, depth: usize) -> u16 {
+  match depth {
+    16 => u16::from_be_bytes([row[index * 2], row[index * 2 + 1]]),
+    8 => row[index] as u16,
+    _ => {
+      let bit = index * depth;
+      let shift = 8 - depth - bit % 8;
+      ((row[bit / 8] >> shift) & ((1 << depth) - 1) as u8) as u16
+    }
+  }
+}
+
+/// Scale sample of bit depth to 8 bits
+fn scale(sample: u16, depth: usize) -> u8 {
+  match depth {
+    16 => (sample >> 8) as u8,
+    _ => (sample as usize * 255 / ((1 << depth) - 1)) as u8,
+  }
+}
+
+/// Get alpha of gray or RGB pixel, which is transparent if it is the color in `tRNS` chunk
+fn transparency_matches(transparency: &[u8], samples: &[u16], depth: usize) -> u8 {
+  let color = (transparency.chunks(2))
+    .map(|x| u16::from_be_bytes([x[0], *x.get(1).unwrap_or(&0)]))
+    .collect::<Vec<_>>();
+  let mask = if depth == 16 {
+    u16::MAX
+  } else {
+    (1 << depth) - 1
+  };
+  let is_transparent = color.len() == samples.len()
+    && (color.iter().zip(samples)).all(|(color, sample)| color & mask == *sample);
+  if is_transparent {
+    0
+  } else {
+    255
+  }
+}
+
+/// Encode pixels as PNG image, with smallest color type and best filter of each row
+fn encode_png(pixels: &Pixels) -> Result<Vec<u8>, String> {
+  let opaque = pixels.data.chunks(4).all(|x| x[3] == 255);
+  let gray = pixels.data.chunks(4).all(|x| x[0] == x[1] && x[1] == x[2]);
+  let (color, channels): (u8, &[usize]) = match (gray, opaque) {
+    (true, true) => (0, &[0]),
+    (true, false) => (4, &[0, 3]),
+    (false, true) => (2, &[0, 1, 2]),
+    (false, false) => (6, &[0, 1, 2, 3]),
+  };
+
+  let step = channels.len();
+  let row_length = pixels.width * step;
+  let mut filtered = Vec::with_capacity((row_length + 1) * pixels.height);
+  let mut previous = vec![0; row_length];
+  for row in pixels.data.chunks(pixels.width * 4) {
+    let current = (row.chunks(4))
+      .flat_map(|pixel| channels.iter().map(|channel| pixel[*channel]))
+      .collect::<Vec<_>>();
+
+    // Filter with smallest sum, as a guess of which compresses best
+    let mut best: Option<Vec<u8>> = None;
+    for filter in 0..5 {
+      let mut candidate = Vec::with_capacity(row_length + 1);
+      candidate.push(filter);
+      for i in 0..row_length {
+        let left = if i >= step { current[i - step] } else { 0 };
+        let up = previous[i];
+        let up_left = if i >= step { previous[i - step] } else { 0 };
+        let predicted = match filter {
+          0 => 0,
+          1 => left,
+          2 => up,
+          3 => ((left as u16 + up as u16) / 2) as u8,
+          _ => paeth(left, up, up_left),
+        };
+        candidate.push(current[i].wrapping_sub(predicted));
+      }
+      let sum = |row: &[u8]| -> u64 {
+        row[1..]
+          .iter()
+          .map(|x| (*x as i8).unsigned_abs() as u64)
+          .sum()
+      };
+      if best.as_ref().is_none_or(|best| sum(&candidate) < sum(best)) {
+        best = Some(candidate);
+      }
+    }
+    filtered.extend(best.unwrap_or_default());
+    previous = current;
+  }
+
+  let mut encoder = ZlibEncoder::new(Vec::new(), Compression::best());
+  let compressed = (encoder.write_all(&filtered))
+    .and_then(|_| encoder.finish())
+    .map_err(|err| format!("Failed to compress image data - {err}"))?;
+
+  let mut header = Vec::with_capacity(13);
+  header.extend((pixels.width as u32).to_be_bytes());
+  header.extend((pixels.height as u32).to_be_bytes());
+  header.extend([8, color, 0, 0, 0]);
+
+  // Color chunks must be before image data
+  let mut chunks = vec![(*b"IHDR", header)];
+  chunks.extend(pixels.color_chunks.iter().cloned());
+  chunks.extend([(*b"IDAT", compressed), (*b"IEND", Vec::new())]);
+
+  let mut image = PNG_SIGNATURE.to_vec();
+  for (kind, data) in chunks {
+    image.extend((data.len() as u32).to_be_bytes());
+    let start = image.len();
+    image.extend(kind);
+    image.extend(&data);
+    let crc = crc32(&image[start..]);
+    image.extend(crc.to_be_bytes());
+  }
+  Ok(image)
+}
+
+/// CRC-32 of PNG chunk
+fn crc32(bytes: &[u8]) -> u32 {
+  let mut crc = u32::MAX;
+  for byte in bytes {
+    crc ^= *byte as u32;
+    for _ in 0..8 {
+      crc = if crc & 1 == 1 {
+        0xedb88320 ^ (crc >> 1)
+      } else {
+        crc >> 1
+      };
+    }
+  }
+  !crc
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// Create PNG image with header and image data, which is not filtered (filter type `0` of each row must be included)
+  fn png(
+    width: u32,
+    height: u32,
+    depth: u8,
+    color: u8,
+    interlaced: bool,
+    filtered: &[u8],
+  ) -> Vec<u8> {
+    let mut header = Vec::new();
+    header.extend(width.to_be_bytes());
+    header.extend(height.to_be_bytes());
+    header.extend([depth, color, 0, 0, interlaced as u8]);
+
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(filtered).unwrap();
+    let compressed = encoder.finish().unwrap();
+
+    let mut image = PNG_SIGNATURE.to_vec();
+    for (kind, data) in [
+      (b"IHDR", header),
+      (b"IDAT", compressed),
+      (b"IEND", Vec::new()),
+    ] {
+      image.extend((data.len() as u32).to_be_bytes());
+      image.extend(kind);
+      image.extend(&data);
+      image.extend(crc32(&[kind.as_slice(), &data].concat()).to_be_bytes());
+    }
+    image
+  }
+
+  /// Get gray values of decoded pixels
+  fn gray(pixels: &Pixels) -> Vec<u8> {
+    pixels.data.chunks(4).map(|x| x[0]).collect()
+  }
+
+  #[test]
+  fn round_trip() {
+    let data = (0..6 * 4)
+      .flat_map(|i| {
+        [
+          i as u8 * 10,
+          255 - i as u8,
+          i as u8 % 3 * 100,
+          255 - i as u8 % 2 * 128,
+        ]
+      })
+      .collect::<Vec<_>>();
+    for data in [
+      data
+        .chunks(4)
+        .map(|_| [7, 7, 7, 255])
+        .collect::<Vec<_>>()
+        .concat(),
+      data,
+    ] {
+      let pixels = Pixels {
+        width: 6,
+        height: 4,
+        data,
+        color_chunks: Vec::new(),
+      };
+      let image = encode_png(&pixels).unwrap();
+      assert_eq!(image_size(&image), Some((6, 4)));
+      assert_eq!(decode_png(&image).unwrap().data, pixels.data);
+    }
+  }
+
+  #[test]
+  fn color_chunks_are_kept() {
+    let pixels = Pixels {
+      width: 4,
+      height: 4,
+      data: vec![200; 4 * 4 * 4],
+      color_chunks: vec![
+        (*b"sRGB", vec![0]),
+        (*b"gAMA", 45455u32.to_be_bytes().to_vec()),
+      ],
+    };
+    let image = PngProcessor
+      .process(
+        &encode_png(&pixels).unwrap(),
+        ImageFormat::Png,
+        ImageFormat::Png,
+        Some(2),
+      )
+      .unwrap();
+    let decoded = decode_png(&image).unwrap();
+    assert_eq!((decoded.width, decoded.height), (2, 2));
+    assert_eq!(decoded.color_chunks, pixels.color_chunks);
+  }
+
+  #[test]
+  fn filters() {
+    let filtered = [
+      [0, 10, 20, 30],
+      [1, 5, 5, 5],
+      [2, 1, 1, 1],
+      [3, 2, 2, 2],
+      [4, 1, 1, 1],
+    ]
+    .concat();
+    assert_eq!(
+      unfilter(&filtered, 3, 1).unwrap(),
+      [10, 20, 30, 5, 10, 15, 6, 11, 16, 5, 10, 15, 6, 11, 16]
+    );
+    assert!(unfilter(&[5, 0, 0, 0], 3, 1).is_err());
+
+    // Encoder chooses a filter for each row
+    let data = (0..16 * 16)
+      .flat_map(|i| {
+        [
+          (i % 16 * 16) as u8,
+          (i / 16 * 16) as u8,
+          (i * 7 % 256) as u8,
+          255,
+        ]
+      })
+      .collect::<Vec<_>>();
+    let pixels = Pixels {
+      width: 16,
+      height: 16,
+      data,
+      color_chunks: Vec::new(),
+    };
+    assert_eq!(
+      decode_png(&encode_png(&pixels).unwrap()).unwrap().data,
+      pixels.data
+    );
+  }
+
+  #[test]
+  fn adam7() {
+    // 5x5 gray image, where each pixel is its index
+    let mut filtered = Vec::new();
+    for (x0, y0, dx, dy) in [
+      (0, 0, 8, 8),
+      (4, 0, 8, 8),
+      (0, 4, 4, 8),
+      (2, 0, 4, 4),
+      (0, 2, 2, 4),
+      (1, 0, 2, 2),
+      (0, 1, 1, 2),
+    ] {
+      for y in (y0..5).step_by(dy) {
+        if x0 >= 5 {
+          continue;
+        }
+        filtered.push(0);
+        filtered.extend((x0..5).step_by(dx).map(|x| (y * 5 + x) as u8));
+      }
+    }
+    let pixels = decode_png(&png(5, 5, 8, 0, true, &filtered)).unwrap();
+    assert_eq!(gray(&pixels), (0..25).collect::<Vec<u8>>());
+
+    // 2x2 image with 1-bit depth, where first row is white, has pixels in passes 1, 6, and 7
+    let filtered = [0, 0b1000_0000, 0, 0b1000_0000, 0, 0b0000_0000];
+    let pixels = decode_png(&png(2, 2, 1, 0, true, &filtered)).unwrap();
+    assert_eq!(gray(&pixels), [255, 255, 0, 0]);
+  }
+
+  #[test]
+  fn malformed() {
+    assert!(decode_png(b"GIF89a").is_err());
+    assert!(decode_png(PNG_SIGNATURE).is_err());
+
+    let image = png(2, 2, 8, 0, false, &[0, 1, 2, 0, 3, 4]);
+    assert_eq!(gray(&decode_png(&image).unwrap()), [1, 2, 3, 4]);
+    // Chunk is cut off
+    assert!(decode_png(&image[..image.len() - 20]).is_err());
+    // Image data is too short
+    assert!(decode_png(&png(2, 2, 8, 0, false, &[0, 1, 2])).is_err());
+    // Unknown color type and bit depth
+    assert!(decode_png(&png(2, 2, 8, 5, false, &[0; 6])).is_err());
+    assert!(decode_png(&png(2, 2, 3, 0, false, &[0; 6])).is_err());
+    // Missing color in palette
+    assert!(decode_png(&png(2, 2, 8, 3, false, &[0; 6])).is_err());
+
+    // Huge image with little data is not allocated
+    assert_eq!(
+      decode_png(&png(100_000, 100_000, 8, 6, false, &[0; 100]))
+        .err()
+        .as_deref(),
+      Some("Image data is too short")
+    );
+    assert_eq!(
+      decode_png(&png(u32::MAX, u32::MAX, 16, 6, true, &[0; 100]))
+        .err()
+        .as_deref(),
+      Some("Image is too large")
+    );
+  }
+}
@@ -0,0 +1,138 @@
+use serde_json::json;
+
+use crate::{sitemap::url_path, Manifest, ManifestDiff, SiteUrl, UnreactError, UnreactResult};
+
+/// Default endpoint of IndexNow, which shares submitted urls with all participating search engines
+pub const INDEXNOW_ENDPOINT: &str = "https://api.indexnow.org/indexnow";
+
+/// Options for IndexNow request of pages, see `Unreact::build_indexnow_request`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexNow {
+  /// Key to verify ownership of site
+  ///
+  /// Must be 8 to 128 characters of letters, numbers, and dashes
+  pub key: String,
+  /// Url to submit pages to
+  ///
+  /// Default: `INDEXNOW_ENDPOINT`
+  pub endpoint: String,
+  /// If only pages which changed since previous build are submitted, instead of every page
+  ///
+  /// Default: `true`
+  pub changed_only: bool,
+}
+
+impl IndexNow {
+  /// Create options with key, using default endpoint
+  ///
+  /// Generate a key with any random hex string, such as the output of `uuidgen`
+  pub fn new(key: &str) -> Self {
+    IndexNow {
+      key: key.to_string(),
+      endpoint: INDEXNOW_ENDPOINT.to_string(),
+      changed_only: true,
+    }
+  }
+
+  /// Returns error if key is not 8 to 128 characters of letters, numbers, and dashes
+  pub(crate) fn check_key(&self) -> UnreactResult<()> {
+    let valid = (8..=128).contains(&self.key.len())
+      && self
+        .key
+        .chars()
+        .all(|ch| ch.is_ascii_alphanumeric() || ch == '-');
+
+    if valid {
+      Ok(())
+    } else {
+      Err(UnreactError::InvalidIndexNowKey(self.key.to_string()))
+    }
+  }
+}
+
+/// Request to send to search engines with any HTTP client, returned by `Unreact::build_indexnow_request`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexNowRequest {
+  /// Url to send `POST` request to
+  pub endpoint: String,
+  /// JSON body of request, with `Content-Type: application/json; charset=utf-8`
+  pub body: String,
+  /// Urls of pages included in request
+  pub urls: Vec<String>,
+}
+
+/// Get file name of key, relative to build directory
+///
+/// Must be at root of site, as IndexNow only accepts urls in the same directory as the key
+pub(crate) fn key_file(options: &IndexNow) -> String {
+  format!("{}.txt", options.key)
+}
+
+/// Create request for pages in `manifest`, or only pages which differ from `old`
+///
+/// Returns `None` if there are no pages to submit
+pub(crate) fn create_request(
+  options: &IndexNow,
+  url: &SiteUrl,
+  manifest: &Manifest,
+  old: Option<&Manifest>,
+) -> Option<IndexNowRequest> {
+  let files = match old {
+    Some(old) if options.changed_only => {
+      let ManifestDiff {
+        added,
+        changed,
+        removed,
+      } = manifest.diff(old);
+      // Removed pages are submitted too, so search engines can stop showing them
+      let mut files = added
+        .into_iter()
+        .chain(changed)
+        .chain(removed)
+        .collect::<Vec<_>>();
+      files.sort();
+      files
+    }
+    _ => manifest.files.keys().cloned().collect(),
+  };
+
+  let urls = files
+    .iter()
+    .filter_map(|file| page_path(file))
+    .map(|path| url.join(&path))
+    .collect::<Vec<_>>();
+
+  if urls.is_empty() {
+    return None;
+  }
+
+  // Host without scheme or port
+  let host = url
+    .as_str()
+    .split("://")
+    .nth(1)
+    .and_then(|x| x.split(['/', ':']).next())
+    .unwrap_or_default();
+
+  let body = json!({
+    "host": host,
+    "key": options.key,
+    "keyLocation": url.join(&key_file(options)),
+    "urlList": urls,
+  });
+
+  Some(IndexNowRequest {
+    endpoint: options.endpoint.to_string(),
+    body: body.to_string(),
+    urls,
+  })
+}
+
+/// Get url path of output file, if it is a page
+///
+/// `about.html` is `about`, `blog/index.html` is `blog/`
+///
+/// Returns `None` for other files, and for the 404 page
+fn page_path(file: &str) -> Option<String> {
+  url_path(file.strip_suffix(".html")?)
+}
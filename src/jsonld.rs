@@ -0,0 +1,90 @@
+use serde_json::{Map, Value};
+
+/// Create `<script type="application/ld+json">` tag, for structured data
+///
+/// `@context` is set to `https://schema.org` if not given
+///
+/// `<`, `>`, and `&` are escaped in strings, so content cannot close the script tag
+///
+/// Also available in templates as the inbuilt `jsonld` helper, where named arguments are properties, and `type` is `@type`:
+///
+/// ```hbs
+/// {{jsonld type="Article" headline=title datePublished=date}}
+/// ```
+///
+/// Or with an object from page data: `{{jsonld schema}}`
+///
+/// # Examples
+///
+/// ```
+/// use serde_json::json;
+///
+/// let script = unreact::jsonld(&json!({"@type": "Article", "headline": "</script> oops"}));
+///
+/// assert_eq!(
+///   script,
+///   r#"<script type="application/ld+json">{"@context":"https://schema.org","@type":"Article","headline":"\u003c/script\u003e oops"}</script>"#
+/// );
+/// ```
+///
+/// Using the helper in a template
+///
+/// ```
+/// use unreact::prelude::*;
+/// use serde_json::json;
+///
+/// fn main() -> UnreactResult<()> {
+///   let mut vfs = Vfs::new();
+///   vfs.templates.insert(
+///     "post".to_string(),
+///     r#"{{jsonld type="Article" headline=title}}"#.to_string(),
+///   );
+///
+///   let app = Unreact::from_vfs(Config::default(), false, SiteUrl::parse("https://mysite.com")?, vfs);
+///
+///   assert_eq!(
+///     app.render("post", &json!({"title": "Tom & Jerry"}))?,
+///     r#"<script type="application/ld+json">{"@context":"https://schema.org","@type":"Article","headline":"Tom \u0026 Jerry"}</script>"#
+///   );
+///
+///   Ok(())
+/// }
+/// ```
+pub fn jsonld(schema: &Value) -> String {
+  let mut schema = schema.clone();
+  if let Value::Object(map) = &mut schema {
+    map
+      .entry("@context")
+      .or_insert_with(|| Value::String("https://schema.org".to_string()));
+  }
+
+  let json = schema
+    .to_string()
+    .replace('<', "\\u003c")
+    .replace('>', "\\u003e")
+    .replace('&', "\\u0026");
+
+  format!(r#"<script type="application/ld+json">{json}</script>"#)
+}
+
+/// Create schema for `jsonld` helper, from positional object and named arguments
+///
+/// Named argument `type` is renamed to `@type`, and `context` to `@context`
+pub(crate) fn schema_from_args(base: Option<&Value>, hash: Vec<(&str, Value)>) -> Value {
+  let mut schema = match base {
+    Some(Value::Object(map)) => map.clone(),
+    _ => Map::new(),
+  };
+
+  for (key, value) in hash {
+    let key = match key {
+      "type" => "@type",
+      "context" => "@context",
+      "id" => "@id",
+      key => key,
+    };
+    schema.insert(key.to_string(), value);
+  }
+
+  Value::Object(schema)
+}
@@ -0,0 +1,137 @@
+use std::{
+  collections::{BTreeMap, HashSet},
+  fs,
+  sync::{Mutex, PoisonError},
+};
+
+use crate::{
+  ignore::IgnoreRules, list_files, watch::partials_used, Config, FileMap, UnreactError,
+  UnreactResult,
+};
+
+/// Templates read from disk when first rendered, instead of all when app is created, see `Config::lazy_templates`
+#[derive(Debug, Default)]
+pub(crate) struct LazyTemplates {
+  /// Directory of templates
+  dir: String,
+  /// Path of each template file, relative to directory, by name of template
+  files: BTreeMap<String, String>,
+  /// Templates which are read, by name
+  loaded: Mutex<BTreeMap<String, Template>>,
+}
+
+/// Template read from disk
+#[derive(Debug)]
+struct Template {
+  content: String,
+  /// Names of partials used by template, or `None` if it uses a dynamic partial
+  partials: Option<Vec<String>>,
+}
+
+impl LazyTemplates {
+  /// List template files in directory of config, without reading them
+  pub fn list(config: &Config) -> UnreactResult<Self> {
+    let dir = &config.templates;
+    let ignore = IgnoreRules::read(config, dir);
+    let mut files = BTreeMap::new();
+    for file in list_files(dir)? {
+      if ignore.is_ignored(dir, &file) {
+        continue;
+      }
+      // Name is path without extension, like `load_filemap`
+      let (parent, name) = match file.rsplit_once('/') {
+        Some((parent, name)) => (format!("{parent}/"), name),
+        None => (String::new(), file.as_str()),
+      };
+      let name = name.split('.').next().unwrap_or(name);
+      files.insert(format!("{parent}{name}"), file.clone());
+    }
+
+    Ok(LazyTemplates {
+      dir: dir.to_string(),
+      files,
+      loaded: Mutex::default(),
+    })
+  }
+
+  /// List template files again, and forget changed templates, so they are read again when rendered
+  ///
+  /// `changed`: Names of templates which changed, were added, or were removed
+  #[cfg(feature = "dev-server")]
+  pub fn refresh(&mut self, config: &Config, changed: &[String]) -> UnreactResult<()> {
+    let mut loaded = std::mem::take(
+      self
+        .loaded
+        .get_mut()
+        .unwrap_or_else(PoisonError::into_inner),
+    );
+    for name in changed {
+      loaded.remove(name);
+    }
+    *self = LazyTemplates {
+      loaded: Mutex::new(loaded),
+      ..LazyTemplates::list(config)?
+    };
+    Ok(())
+  }
+
+  /// Read template and every partial it uses, if not read yet
+  ///
+  /// Returns `true` if any template was read, so the registry must be created again.
+  /// Every template is read if a dynamic partial is used
+  pub fn load(&self, name: &str) -> UnreactResult<bool> {
+    let mut loaded = self.loaded.lock().unwrap_or_else(PoisonError::into_inner);
+    let mut changed = false;
+    let mut seen = HashSet::new();
+    let mut queue = vec![name.to_string()];
+
+    while let Some(name) = queue.pop() {
+      if !seen.insert(name.to_string()) {
+        continue;
+      }
+      if !loaded.contains_key(&name) {
+        // Inbuilt partials, and templates of plugins, are not files
+        let Some(file) = self.files.get(&name) else {
+          continue;
+        };
+        loaded.insert(name.to_string(), self.read(file)?);
+        changed = true;
+      }
+
+      match &loaded[&name].partials {
+        Some(partials) => queue.extend(partials.iter().cloned()),
+        None => {
+          for (name, file) in &self.files {
+            if !loaded.contains_key(name) {
+              loaded.insert(name.to_string(), self.read(file)?);
+              changed = true;
+            }
+          }
+          break;
+        }
+      }
+    }
+    Ok(changed)
+  }
+
+  /// Get every template which is read, by name
+  pub fn templates(&self) -> FileMap {
+    let loaded = self.loaded.lock().unwrap_or_else(PoisonError::into_inner);
+    (loaded.iter())
+      .map(|(name, template)| (name.to_string(), template.content.to_string()))
+      .collect()
+  }
+
+  /// Read template file, relative to directory
+  fn read(&self, file: &str) -> UnreactResult<Template> {
+    let path = format!("./{}/{file}", self.dir);
+    let content = match fs::read_to_string(&path) {
+      Ok(x) => x,
+      Err(err) => return Err(UnreactError::IoError(err, path)),
+    };
+    Ok(Template {
+      partials: partials_used(&content),
+      content,
+    })
+  }
+}
@@ -1,6 +1,60 @@
 mod app;
+mod assets;
+mod audit;
+mod cache;
+mod collection;
+mod config_file;
+mod context_schema;
+mod data;
+mod date;
+mod dev_build;
+mod engine;
+mod error_page;
+mod events;
+mod feed;
+mod format;
+mod fragments;
+mod front_matter;
+mod head;
+mod hooks;
+mod host;
+mod i18n;
+mod ignore;
+mod images;
+#[cfg(feature = "indexnow")]
+mod indexnow;
+mod jsonld;
+mod lazy;
+mod limits;
+mod links;
+mod lockfile;
+mod manifest;
+mod markdown;
+mod normalize;
+mod package;
+mod parallel;
+mod partial_docs;
+mod path_check;
+mod plugin;
+mod profile;
+mod progress;
+mod public;
+mod schedule;
+mod scss;
 mod server;
+mod sink;
+mod site_url;
+mod sitemap;
+#[cfg(feature = "dev-server")]
+mod stats_page;
 mod types;
+mod verify;
+mod vfs;
+mod watch;
+mod workspace;
+
+#[cfg(feature = "scaffold")]
+pub mod scaffold;
 
 /// Most useful functions and types
 ///
@@ -11,8 +65,40 @@ mod types;
 /// ```
 pub mod prelude {
   pub use crate::app::{Config, Unreact};
+  pub use crate::audit::{AssetWarning, AuditOptions};
+  pub use crate::cache::{CacheStats, TemplateCache};
+  pub use crate::collection::CollectionOptions;
+  pub use crate::engine::{Inbuilt, TemplateEngine};
+  pub use crate::events::BuildEvent;
+  pub use crate::feed::{FeedFormat, FeedItem, FeedOptions};
+  pub use crate::format::FormatOptions;
+  pub use crate::head::HeadOptions;
+  pub use crate::host::{Host, HostRedirect};
+  pub use crate::images::{ImageFormat, ImageOptions, ImageProcessor, PngProcessor};
+  #[cfg(feature = "indexnow")]
+  pub use crate::indexnow::{IndexNow, IndexNowRequest, INDEXNOW_ENDPOINT};
   pub use crate::is_dev;
-  pub use crate::types::{UnreactError, UnreactResult};
+  pub use crate::limits::RenderLimits;
+  pub use crate::links::LinkCheck;
+  pub use crate::lockfile::Lockfile;
+  pub use crate::manifest::{
+    BuildManifest, BuildManifestEntry, Manifest, ManifestDiff, ManifestEntry,
+  };
+  pub use crate::markdown::MarkdownOptions;
+  pub use crate::normalize::PathNormalization;
+  pub use crate::partial_docs::{PartialDoc, PartialParam};
+  pub use crate::plugin::{BuildReport, Plugin};
+  pub use crate::profile::{Profile, TemplateProfile};
+  pub use crate::progress::{BuildProgress, BuildStage};
+  pub use crate::public::{CopyOptions, PermissionMode, TimestampMode};
+  pub use crate::server::DirectoryMode;
+  #[cfg(feature = "dev-server")]
+  pub use crate::server::ServerHandle;
+  pub use crate::sink::BuildSink;
+  pub use crate::site_url::SiteUrl;
+  pub use crate::sitemap::{ChangeFreq, SitemapOptions};
+  pub use crate::types::{ErrorLocation, FileMap, UnreactError, UnreactResult};
+  pub use crate::vfs::Vfs;
 }
 
 /// Development items, not used often, but good to have exposed if necessary
@@ -23,9 +109,10 @@ pub mod prelude {
 /// use unreact::dev::*;
 /// ```
 pub mod dev {
-  /// Directory of temporary development build
-  pub const DEV_BUILD_DIR: &str = ".devbuild";
-  pub use crate::server::{ADDRESS, DEV_SCRIPT};
+  /// Default directory of temporary development builds, see `Config::dev_build`
+  pub const DEV_BUILD_DIR: &str = "target/unreact-dev";
+  pub use crate::package::DEFAULT_PACKAGE_LEVEL;
+  pub use crate::server::{ADDRESS, DEV_SCRIPT, RELOAD_PATH, RENDER_PATH};
 }
 
 use serde_json::Value;
@@ -33,8 +120,16 @@ use std::{fs, path::Path};
 
 // For `crate`, not `pub`
 use dev::*;
+pub use format::format_html;
+/// Re-export of `handlebars` crate, for writing custom helpers with `Unreact::register_helper`
+pub use handlebars;
+pub use host::{netlify_headers, netlify_redirect};
+use ignore::IgnoreRules;
+pub use jsonld::jsonld;
+pub use markdown::markdown_to_html;
+pub use normalize::normalize_path;
 pub use prelude::*;
-use types::{File, FileMap};
+use types::{Page, PageSource};
 
 /// Check if `--dev` or `-d` argument was passed on `cargo run`
 ///
@@ -64,8 +159,15 @@ pub fn is_dev() -> bool {
 /// `parent`: Directory to collate all templates
 ///
 /// `child`: Path of subdirectories (not including `parent`)
+///
+/// `ignore`: Files to leave out, from ignore files, see `Config::ignore_files`
 // ? Change to `std::io::Result` ?
-fn load_filemap(map: &mut FileMap, parent: &str, child: &str) -> UnreactResult<()> {
+fn load_filemap(
+  map: &mut FileMap,
+  parent: &str,
+  child: &str,
+  ignore: &IgnoreRules,
+) -> UnreactResult<()> {
   // Full path, relative to workspace, of directory
   let dir_path = format!("./{parent}/{child}");
 
@@ -84,11 +186,14 @@ fn load_filemap(map: &mut FileMap, parent: &str, child: &str) -> UnreactResult<(
       if let Some(name) = file.file_name().to_str() {
         // Only include first slash if child directory is not empty
         let slash = if child.is_empty() { "" } else { "/" };
+        if ignore.is_ignored(parent, &format!("{child}{slash}{name}")) {
+          continue;
+        }
 
         // If is folder
         if Path::new(&path).is_dir() {
           // Recurse function
-          load_filemap(map, parent, &format!("{child}{slash}{name}",))?;
+          load_filemap(map, parent, &format!("{child}{slash}{name}",), ignore)?;
         } else {
           // Add to templates
           let content = match fs::read_to_string(file.path()) {
@@ -118,6 +223,53 @@ fn load_filemap(map: &mut FileMap, parent: &str, child: &str) -> UnreactResult<(
   Ok(())
 }
 
+/// List every file in directory recursively, with paths relative to directory
+///
+/// Paths use `/` as separator, and are sorted
+fn list_files(dir: &str) -> UnreactResult<Vec<String>> {
+  let mut files = Vec::new();
+  list_files_in(&mut files, dir, "")?;
+  files.sort();
+  Ok(files)
+}
+
+/// Recursively add files of directory to list, for `list_files`
+///
+/// `child`: Path of subdirectories (not including `parent`)
+fn list_files_in(files: &mut Vec<String>, parent: &str, child: &str) -> UnreactResult<()> {
+  let dir_path = format!("./{parent}/{child}");
+  let dir = match fs::read_dir(&dir_path) {
+    Ok(x) => x,
+    Err(err) => return Err(UnreactError::IoError(err, dir_path)),
+  };
+
+  for file in dir.flatten() {
+    if let Some(name) = file.file_name().to_str() {
+      // Only include first slash if child directory is not empty
+      let slash = if child.is_empty() { "" } else { "/" };
+      let path = format!("{child}{slash}{name}");
+
+      if file.path().is_dir() {
+        list_files_in(files, parent, &path)?;
+      } else {
+        files.push(path);
+      }
+    }
+  }
+
+  Ok(())
+}
+
+/// Escape text for xml, such as sitemaps and feeds
+fn escape_xml(text: &str) -> String {
+  text
+    .replace('&', "&amp;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+    .replace('"', "&quot;")
+    .replace('\'', "&apos;")
+}
+
 /// Create folder recursively
 fn create_dir_all_safe(parent: &str, child: &str) -> UnreactResult<()> {
   let folders = child.split("/").collect::<Vec<_>>();
@@ -126,6 +278,10 @@ fn create_dir_all_safe(parent: &str, child: &str) -> UnreactResult<()> {
     // Check if exists, create if not
     if !Path::new(&path).exists() {
       if let Err(err) = fs::create_dir(path) {
+        // Could be created by another thread since checking
+        if err.kind() == std::io::ErrorKind::AlreadyExists {
+          continue;
+        }
         return Err(UnreactError::IoError(
           err,
           format!("./{}/{}", parent, folders.get(0..i).unwrap().join("/")),
@@ -137,6 +293,60 @@ fn create_dir_all_safe(parent: &str, child: &str) -> UnreactResult<()> {
   Ok(())
 }
 
+/// Get path of staging directory for build directory, see `Config::clean`
+fn staging_dir(build: &str) -> String {
+  format!("{}.staging", build.trim_end_matches('/'))
+}
+
+/// Create empty build directory, with `public` subfolder
+///
+/// Removes directory first if it exists
+fn create_build_dir(dir: &str) -> UnreactResult<()> {
+  if Path::new(&format!("./{dir}")).exists() {
+    if let Err(err) = fs::remove_dir_all(format!("./{dir}")) {
+      return Err(UnreactError::IoError(err, dir.to_string()));
+    };
+  }
+
+  // Style directories are created when writing styles
+  for child in ["", "/public"] {
+    if let Err(err) = fs::create_dir(format!("./{dir}{child}")) {
+      return Err(UnreactError::IoError(err, dir.to_string()));
+    }
+  }
+  Ok(())
+}
+
+/// Replace directory `to` with directory `from`
+///
+/// Old directory is renamed before it is removed, so `to` is only missing between two renames
+fn replace_dir(from: &str, to: &str) -> UnreactResult<()> {
+  let old = format!("./{}.old", to.trim_end_matches('/'));
+  let (from, to) = (format!("./{from}"), format!("./{to}"));
+  let io_error = |err, path: &str| UnreactError::IoError(err, path.to_string());
+
+  // Left over from an interrupted build
+  if Path::new(&old).exists() {
+    fs::remove_dir_all(&old).map_err(|err| io_error(err, &old))?;
+  }
+
+  let has_old = Path::new(&to).exists();
+  if has_old {
+    fs::rename(&to, &old).map_err(|err| io_error(err, &to))?;
+  }
+  if let Err(err) = fs::rename(&from, &to) {
+    // Put old directory back
+    if has_old {
+      fs::rename(&old, &to).ok();
+    }
+    return Err(io_error(err, &to));
+  }
+  if has_old {
+    fs::remove_dir_all(&old).map_err(|err| io_error(err, &old))?;
+  }
+  Ok(())
+}
+
 /// Convert `DirEntry` to string and get file name without extension
 fn get_file_name(path: &fs::DirEntry) -> Option<String> {
   Some(
@@ -145,13 +355,22 @@ fn get_file_name(path: &fs::DirEntry) -> Option<String> {
       .to_str()?
       .replace('\\', "/")
       .split('/')
-      .last()?
+      .next_back()?
       .split('.')
       .next()?
       .to_owned(),
   )
 }
 
+/// Get SHA-256 hash of content, as lowercase hex
+fn hash_content(content: &[u8]) -> String {
+  use sha2::{Digest, Sha256};
+  Sha256::digest(content)
+    .iter()
+    .map(|byte| format!("{byte:02x}"))
+    .collect()
+}
+
 /// Merge one `serde_json` value with another
 fn merge_json(a: &mut Value, b: Value) {
   if let Value::Object(a) = a {
@@ -0,0 +1,189 @@
+use std::{
+  cell::RefCell,
+  io,
+  time::{Duration, Instant},
+};
+
+/// Limits of rendering each page, so a runaway template fails with `UnreactError::RenderLimit` instead of hanging the build
+///
+/// Not checked for pages rendered with a `TemplateEngine`
+///
+/// Use `RenderLimits::default()` for no limits
+///
+/// # Examples
+///
+/// ```
+/// use unreact::prelude::*;
+/// use serde_json::json;
+///
+/// fn main() -> UnreactResult<()> {
+///   let mut vfs = Vfs::new();
+///   // Include each other forever
+///   vfs.templates.insert("tree".to_string(), "<ul>{{>branch}}</ul>".to_string());
+///   vfs.templates.insert("branch".to_string(), "<li>{{>tree}}</li>".to_string());
+///
+///   let config = Config {
+///     limits: RenderLimits {
+///       partial_depth: Some(32),
+///       ..RenderLimits::default()
+///     },
+///     ..Config::default()
+///   };
+///   let url = SiteUrl::parse("https://mysite.com")?;
+///   let mut app = Unreact::from_vfs(config, false, url, vfs);
+///
+///   app.page("tree", "tree", &json!({}))?;
+///   assert!(matches!(app.compile(), Err(UnreactError::RenderLimit(..))));
+///
+///   Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RenderLimits {
+  /// Maximum time to render a page
+  ///
+  /// Checked when output is written, and when a partial starts
+  ///
+  /// Default: `None`
+  pub time: Option<Duration>,
+  /// Maximum size of rendered page in bytes, before minifying
+  ///
+  /// Default: `None`
+  pub output_size: Option<usize>,
+  /// Maximum depth of partials inside each other, such as a partial which includes itself
+  ///
+  /// Default: `None`
+  pub partial_depth: Option<usize>,
+}
+
+impl RenderLimits {
+  /// If partials are checked when they start, so must be wrapped with guard helpers
+  pub(crate) fn guards_partials(&self) -> bool {
+    self.time.is_some() || self.partial_depth.is_some()
+  }
+}
+
+/// State of page currently rendering on this thread
+struct Guard {
+  limits: RenderLimits,
+  /// Start of render, only if time is limited, as `Instant::now` panics on `wasm32-unknown-unknown`
+  start: Option<Instant>,
+  /// Size of output written
+  size: usize,
+  /// Partials currently rendering
+  depth: usize,
+  /// Reason that a limit was exceeded
+  exceeded: Option<String>,
+}
+
+thread_local! {
+  static GUARD: RefCell<Option<Guard>> = const { RefCell::new(None) };
+}
+
+/// Run render with limits, on this thread
+///
+/// Returns result of render, and reason if a limit was exceeded
+pub(crate) fn guarded<T>(limits: RenderLimits, render: impl FnOnce() -> T) -> (T, Option<String>) {
+  let guard = Guard {
+    limits,
+    start: limits.time.map(|_| Instant::now()),
+    size: 0,
+    depth: 0,
+    exceeded: None,
+  };
+  // Keep state of outer render, if any
+  let outer = GUARD.with(|cell| cell.borrow_mut().replace(guard));
+  let result = render();
+  let guard = GUARD.with(|cell| std::mem::replace(&mut *cell.borrow_mut(), outer));
+  (result, guard.and_then(|guard| guard.exceeded))
+}
+
+/// Update state of render on this thread, and return reason if a limit is exceeded
+///
+/// Does nothing if not rendering with `guarded`
+fn check(update: impl FnOnce(&mut Guard) -> Option<String>) -> Result<(), String> {
+  GUARD.with(|cell| {
+    let mut guard = cell.borrow_mut();
+    let Some(guard) = guard.as_mut() else {
+      return Ok(());
+    };
+    let exceeded = update(guard).or_else(|| match (guard.limits.time, guard.start) {
+      (Some(time), Some(start)) if start.elapsed() > time => {
+        Some(format!("Render took longer than {time:?}"))
+      }
+      _ => None,
+    });
+    match exceeded {
+      Some(reason) => {
+        guard.exceeded = Some(reason.clone());
+        Err(reason)
+      }
+      None => Ok(()),
+    }
+  })
+}
+
+/// Record start of partial, see `wrap_guard`
+pub(crate) fn start_partial(name: &str) -> Result<(), String> {
+  check(|guard| {
+    guard.depth += 1;
+    match guard.limits.partial_depth {
+      Some(max) if guard.depth > max => Some(format!(
+        "Partials are nested deeper than {max}, at partial '{name}'"
+      )),
+      _ => None,
+    }
+  })
+}
+
+/// Record end of partial, see `wrap_guard`
+pub(crate) fn end_partial() {
+  let _ = check(|guard| {
+    guard.depth = guard.depth.saturating_sub(1);
+    None
+  });
+}
+
+/// Output of render, which fails when a limit is exceeded, to stop render as soon as possible
+#[derive(Debug, Default)]
+pub(crate) struct LimitedOutput(Vec<u8>);
+
+impl LimitedOutput {
+  /// Get output as string
+  pub fn into_string(self) -> String {
+    match String::from_utf8(self.0) {
+      Ok(output) => output,
+      Err(err) => String::from_utf8_lossy(err.as_bytes()).to_string(),
+    }
+  }
+}
+
+impl io::Write for LimitedOutput {
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    check(|guard| {
+      guard.size += buf.len();
+      match guard.limits.output_size {
+        Some(max) if guard.size > max => Some(format!("Output is larger than {max} bytes")),
+        _ => None,
+      }
+    })
+    .map_err(io::Error::other)?;
+    self.0.extend_from_slice(buf);
+    Ok(buf.len())
+  }
+
+  fn flush(&mut self) -> io::Result<()> {
+    Ok(())
+  }
+}
+
+/// Wrap partial source, to check limits with guard helpers
+pub(crate) fn wrap_guard(name: &str, source: &str) -> String {
+  let name = name.replace('\\', "\\\\").replace('"', "\\\"");
+  format!("{{{{{GUARD_START} \"{name}\"}}}}{source}{{{{{GUARD_END}}}}}")
+}
+
+/// Name of helper to check limits at start of partial
+pub(crate) const GUARD_START: &str = "__unreact_guard_start";
+/// Name of helper to record end of partial
+pub(crate) const GUARD_END: &str = "__unreact_guard_end";
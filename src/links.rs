@@ -0,0 +1,402 @@
+use std::collections::BTreeMap;
+
+/// What happens to broken internal links in build, see `Config::check_links`
+///
+/// Default: `LinkCheck::Off`
+///
+/// # Examples
+///
+/// ```
+/// use unreact::prelude::*;
+/// use serde_json::json;
+///
+/// fn main() -> UnreactResult<()> {
+///   let mut vfs = Vfs::new();
+///   vfs.templates.insert("index".to_string(), r#"<a href="{{>URL}}/about">About</a>"#.to_string());
+///
+///   let config = Config {
+///     check_links: LinkCheck::Fail,
+///     ..Config::default()
+///   };
+///   let url = SiteUrl::parse("https://mysite.com")?;
+///   let mut app = Unreact::from_vfs(config, false, url, vfs);
+///   app.index("index", &json!({}))?;
+///
+///   // No `about` page
+///   assert!(matches!(app.compile(), Err(UnreactError::BrokenLinks(_))));
+///
+///   app.page("about", "index", &json!({}))?;
+///   assert!(app.compile().is_ok());
+///
+///   Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LinkCheck {
+  /// Links are not checked
+  #[default]
+  Off,
+  /// Broken links are printed, and build continues
+  Warn,
+  /// Build fails with `UnreactError::BrokenLinks`
+  Fail,
+}
+
+/// Find start and end of every value of attributes in html, quoted or unquoted
+///
+/// `names`: Names of attributes, with `=`, such as `"href="`
+fn find_attributes(html: &str, names: &[&str]) -> Vec<(usize, usize)> {
+  let mut ranges = Vec::new();
+  let bytes = html.as_bytes();
+  let mut i = 0;
+  while i < bytes.len() {
+    // Attribute name must follow whitespace, so `data-src` is not a link
+    let Some(name) = (names.iter()).find(|name| bytes[i..].starts_with(name.as_bytes())) else {
+      i += 1;
+      continue;
+    };
+    if i == 0 || !bytes[i - 1].is_ascii_whitespace() {
+      i += 1;
+      continue;
+    }
+
+    let start = i + name.len();
+    let (start, end) = match bytes.get(start) {
+      Some(quote @ (b'"' | b'\'')) => {
+        let end = html[start + 1..]
+          .find(*quote as char)
+          .map_or(html.len(), |x| start + 1 + x);
+        (start + 1, end)
+      }
+      _ => {
+        let end = html[start..]
+          .find(|ch: char| ch.is_ascii_whitespace() || ch == '>')
+          .map_or(html.len(), |x| start + x);
+        (start, end)
+      }
+    };
+    ranges.push((start, end));
+    i = end.max(i + 1);
+  }
+  ranges
+}
+
+/// Find every `href` and `src` attribute value in html
+fn find_links(html: &str) -> Vec<&str> {
+  (find_attributes(html, &["href=", "src="]).into_iter())
+    .map(|(start, end)| &html[start..end])
+    .collect()
+}
+
+/// Decode `%XX` escapes in path of link
+pub(crate) fn decode_path(path: &str) -> String {
+  let bytes = path.as_bytes();
+  let mut decoded = Vec::with_capacity(bytes.len());
+  let mut i = 0;
+  while i < bytes.len() {
+    let hex = bytes.get(i + 1..i + 3).and_then(|hex| {
+      let hex = std::str::from_utf8(hex).ok()?;
+      u8::from_str_radix(hex, 16).ok()
+    });
+    match (bytes[i], hex) {
+      (b'%', Some(byte)) => {
+        decoded.push(byte);
+        i += 3;
+      }
+      (byte, _) => {
+        decoded.push(byte);
+        i += 1;
+      }
+    }
+  }
+  String::from_utf8_lossy(&decoded).to_string()
+}
+
+/// File which a link points to
+enum Target {
+  /// Link to another site, or to same page
+  External,
+  /// Link which cannot work, such as a root-relative link without base path of site
+  Broken,
+  /// Path relative to build directory
+  Path(String),
+}
+
+/// Get file in build directory which link points to
+///
+/// `page`: Output file of page with link, such as `blog/post.html`
+///
+/// `base_url`: Url of site (or dev server), without trailing slash, such as `https://mysite.com/repo`
+///
+/// `base_path`: Path of url, such as `/repo`, or empty
+fn link_target(link: &str, page: &str, base_url: &str, base_path: &str) -> Target {
+  let link = link.trim();
+  // Fragment and query do not change file
+  let link = link.split(['#', '?']).next().unwrap_or_default();
+
+  let path = if let Some(path) = link.strip_prefix(base_url) {
+    // Not `https://mysite.com.au` for `https://mysite.com`
+    if !path.is_empty() && !path.starts_with('/') {
+      return Target::External;
+    }
+    path.to_string()
+  } else if link.starts_with("//") || link.contains(':') {
+    // External, or other scheme such as `mailto:`
+    return Target::External;
+  } else if link.starts_with('/') {
+    // Root-relative links must include base path of site
+    match link.strip_prefix(base_path) {
+      Some(path) if path.is_empty() || path.starts_with('/') => path.to_string(),
+      _ => return Target::Broken,
+    }
+  } else if link.is_empty() {
+    // Link to fragment of same page, such as `#main`
+    return Target::External;
+  } else {
+    // Relative to directory of page
+    match page.rsplit_once('/') {
+      Some((dir, _)) => format!("/{dir}/{link}"),
+      None => format!("/{link}"),
+    }
+  };
+
+  // Resolve `.` and `..`
+  let path = decode_path(&path);
+  let mut parts = Vec::new();
+  for part in path.split('/') {
+    match part {
+      "" | "." => (),
+      ".." => {
+        parts.pop();
+      }
+      part => parts.push(part),
+    }
+  }
+  Target::Path(parts.join("/"))
+}
+
+/// If path exists in build, as a file, a page without `.html`, or a directory with `index.html`
+fn link_exists<T>(path: &str, outputs: &BTreeMap<String, T>) -> bool {
+  let path = path.trim_matches('/');
+  if path.is_empty() {
+    return outputs.contains_key("index.html");
+  }
+  outputs.contains_key(path)
+    || outputs.contains_key(&format!("{path}.html"))
+    || outputs.contains_key(&format!("{path}/index.html"))
+}
+
+/// Find internal links in html which point to no output file
+///
+/// `page`: Output file of page, such as `blog/post.html`
+///
+/// Returns each broken link as written in html
+pub(crate) fn broken_links<'a, T>(
+  html: &'a str,
+  page: &str,
+  outputs: &BTreeMap<String, T>,
+  base_url: &str,
+  base_path: &str,
+) -> Vec<&'a str> {
+  let mut broken = Vec::new();
+  for link in find_links(html) {
+    let is_broken = match link_target(link, page, base_url, base_path) {
+      Target::External => false,
+      Target::Broken => true,
+      Target::Path(path) => !link_exists(&path, outputs),
+    };
+    if is_broken && !broken.contains(&link) {
+      broken.push(link);
+    }
+  }
+  broken
+}
+
+/// Find links to assets in html which point to no output file, in `src` and `srcset` attributes
+///
+/// Unlike `broken_links`, the exact file must exist, as assets are not pages
+///
+/// `page`: Output file of page, such as `blog/post.html`
+///
+/// Returns each missing link as written in html
+pub(crate) fn missing_assets<'a, T>(
+  html: &'a str,
+  page: &str,
+  outputs: &BTreeMap<String, T>,
+  base_url: &str,
+  base_path: &str,
+) -> Vec<&'a str> {
+  let mut links = Vec::new();
+  for (start, end) in find_attributes(html, &["src=", "srcset="]) {
+    let value = &html[start..end];
+    // Candidates of `srcset` are separated by commas, with an optional width or density after the url
+    let is_srcset = html[..start]
+      .trim_end_matches(['"', '\''])
+      .ends_with("srcset=");
+    if is_srcset {
+      links.extend((value.split(',')).filter_map(|candidate| candidate.split_whitespace().next()));
+    } else {
+      links.push(value);
+    }
+  }
+
+  let mut missing = Vec::new();
+  for link in links {
+    let is_missing = match link_target(link, page, base_url, base_path) {
+      Target::External => false,
+      Target::Broken => true,
+      Target::Path(path) => !outputs.contains_key(&path),
+    };
+    if is_missing && !missing.contains(&link) {
+      missing.push(link);
+    }
+  }
+  missing
+}
+
+/// Replace internal links in html to renamed pages with links to their new path, see `Unreact::rename_page`
+///
+/// Changes `href` attributes only. Fragment and query of link are kept
+///
+/// `page`: Output file of page, such as `blog/post.html`
+///
+/// `renamed`: New path of each renamed page, by old path, **without** `.html` extension
+///
+/// `base_url`: Url of site (or dev server), without trailing slash
+///
+/// `base_path`: Path of url, such as `/repo`, or empty
+pub(crate) fn rename_links(
+  html: &str,
+  page: &str,
+  renamed: &BTreeMap<String, String>,
+  base_url: &str,
+  base_path: &str,
+) -> String {
+  if renamed.is_empty() {
+    return html.to_string();
+  }
+
+  let mut output = String::with_capacity(html.len());
+  let mut last = 0;
+  for (start, end) in find_attributes(html, &["href="]) {
+    let link = &html[start..end];
+    let Target::Path(path) = link_target(link, page, base_url, base_path) else {
+      continue;
+    };
+    let path = path.strip_suffix(".html").unwrap_or(&path);
+    // Directory of `index` page, such as `/blog/` for `blog/index`
+    let new = (renamed.get(path))
+      .or_else(|| renamed.get(&format!("{path}/index")))
+      .or_else(|| renamed.get("index").filter(|_| path.is_empty()));
+    let Some(new) = new else {
+      continue;
+    };
+    let suffix = link.find(['#', '?']).map_or("", |i| &link[i..]);
+    output.push_str(&html[last..start]);
+    output.push_str(&format!("{base_url}/{}{suffix}", page_link(new)));
+    last = end;
+  }
+  output.push_str(&html[last..]);
+  output
+}
+
+/// Get path of page as written in links, with `index` pages as their directory, such as `blog/` for `blog/index`
+fn page_link(path: &str) -> String {
+  if path == "index" {
+    return String::new();
+  }
+  match path.strip_suffix("/index") {
+    Some(dir) => format!("{dir}/"),
+    None => path.to_string(),
+  }
+}
+
+/// Create page which redirects to url, written at old path of renamed page, see `Unreact::rename_page`
+///
+/// Works on every host, with a canonical link for search engines, and a link if refresh is blocked
+pub(crate) fn redirect_page(base_url: &str, path: &str) -> String {
+  let url = handlebars::html_escape(&format!("{base_url}/{}", page_link(path)));
+  format!(
+    concat!(
+      r#"<!DOCTYPE html><html><head><meta charset="utf-8"><title>Redirecting</title>"#,
+      r#"<link rel="canonical" href="{url}"><meta http-equiv="refresh" content="0; url={url}">"#,
+      r#"</head><body><a href="{url}">{url}</a></body></html>"#,
+    ),
+    url = url
+  )
+}
+
+/// If text links to path of page, such as `/blog/post` or `{{>URL}}/blog/post.html`, but not `/blog/post-2`
+///
+/// Used to find templates and data which still link to old path of renamed page
+pub(crate) fn links_to(text: &str, path: &str) -> bool {
+  let needle = format!("/{}", page_link(path).trim_end_matches('/'));
+  // Every link starts with root
+  if needle == "/" {
+    return false;
+  }
+  text.match_indices(&needle).any(|(i, _)| {
+    let after = &text[i + needle.len()..];
+    let after = after.strip_prefix(".html").unwrap_or(after);
+    !after.starts_with(|ch: char| ch.is_alphanumeric() || matches!(ch, '-' | '_' | '.'))
+  })
+}
+
+/// Replace links to site in html with links relative to page, see `Config::relative_links`
+///
+/// Changes `href`, `src`, and `srcset` attributes, except of canonical links
+///
+/// `page`: Output file of page, such as `blog/post.html`
+///
+/// `base_url`: Url of site (or dev server), without trailing slash
+pub(crate) fn relative_links(html: &str, page: &str, base_url: &str) -> String {
+  // Path to root of site from directory of page
+  let root = match page.matches('/').count() {
+    0 => "./".to_string(),
+    depth => "../".repeat(depth),
+  };
+
+  let mut output = String::with_capacity(html.len());
+  let mut last = 0;
+  for (start, end) in find_attributes(html, &["href=", "src=", "srcset="]) {
+    let tag_start = html[..start].rfind('<').unwrap_or(0);
+    let tag_end = html[end..].find('>').map_or(html.len(), |i| end + i);
+    // Canonical link must be absolute
+    if is_canonical(&html[tag_start..tag_end]) {
+      continue;
+    }
+    output.push_str(&html[last..start]);
+    output.push_str(&relative_value(&html[start..end], base_url, &root));
+    last = end;
+  }
+  output.push_str(&html[last..]);
+  output
+}
+
+/// If tag is a canonical link, with `rel="canonical"`
+fn is_canonical(tag: &str) -> bool {
+  ["rel=\"canonical\"", "rel='canonical'", "rel=canonical"]
+    .iter()
+    .any(|rel| tag.contains(rel))
+}
+
+/// Replace every occurence of url (and following slash) in attribute value with path to root
+///
+/// Longer urls which start with this url (such as `https://mysite.com.au` for `https://mysite.com`) are not replaced
+fn relative_value(value: &str, base_url: &str, root: &str) -> String {
+  let mut output = String::with_capacity(value.len());
+  let mut rest = value;
+  while let Some(i) = rest.find(base_url) {
+    output.push_str(&rest[..i]);
+    let after = &rest[i + base_url.len()..];
+    if after.starts_with(|ch: char| ch.is_alphanumeric() || matches!(ch, '.' | '-' | '_' | ':')) {
+      output.push_str(base_url);
+      rest = after;
+      continue;
+    }
+    output.push_str(root);
+    rest = after.strip_prefix('/').unwrap_or(after);
+  }
+  output.push_str(rest);
+  output
+}
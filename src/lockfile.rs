@@ -0,0 +1,125 @@
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs, path::Path};
+
+use crate::{hash_content, list_files, UnreactError, UnreactResult};
+
+/// Hashes of every input of a build, to check that a later build used identical inputs
+///
+/// Written to `lockfile` in config after every production build, such as `unreact.lock`, see `Config::lockfile`
+///
+/// Each input is a SHA-256 hash as hex. Templates, styles, and data are hashed as loaded, so the same inputs from a `Vfs` have the same hashes.
+/// Content, locales, and public files are hashed from their directories
+///
+/// # Examples
+///
+/// ```
+/// use unreact::prelude::*;
+///
+/// let mut locked = Lockfile::default();
+/// locked.templates.insert("index".to_string(), "a".to_string());
+/// locked.styles.insert("main".to_string(), "b".to_string());
+///
+/// let mut current = locked.clone();
+/// current.templates.insert("index".to_string(), "c".to_string());
+/// current.public.insert("logo.png".to_string(), "d".to_string());
+///
+/// assert_eq!(current.diff(&locked), vec!["templates/index", "public/logo.png"]);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Lockfile {
+  /// Templates, by name
+  #[serde(default)]
+  pub templates: BTreeMap<String, String>,
+  /// Custom partials, by name, see `Unreact::register_partial`
+  #[serde(default)]
+  pub partials: BTreeMap<String, String>,
+  /// Styles and style partials, by name
+  #[serde(default)]
+  pub styles: BTreeMap<String, String>,
+  /// Data files, by name
+  #[serde(default)]
+  pub data: BTreeMap<String, String>,
+  /// Files of content directory, with path relative to directory
+  #[serde(default)]
+  pub content: BTreeMap<String, String>,
+  /// Files of locales directory, with path relative to directory
+  #[serde(default)]
+  pub locales: BTreeMap<String, String>,
+  /// Files of public directory, with path relative to directory
+  #[serde(default)]
+  pub public: BTreeMap<String, String>,
+}
+
+impl Lockfile {
+  /// Read lockfile from file
+  pub fn read(path: &str) -> UnreactResult<Self> {
+    let content = match fs::read_to_string(path) {
+      Ok(x) => x,
+      Err(err) => return Err(UnreactError::IoError(err, path.to_string())),
+    };
+    match serde_json::from_str(&content) {
+      Ok(x) => Ok(x),
+      Err(err) => Err(UnreactError::LockfileFail(path.to_string(), err)),
+    }
+  }
+
+  /// Write lockfile as pretty JSON to file
+  pub fn write(&self, path: &str) -> UnreactResult<()> {
+    let content = match serde_json::to_string_pretty(self) {
+      Ok(x) => x,
+      Err(err) => return Err(UnreactError::LockfileFail(path.to_string(), err)),
+    };
+    if let Err(err) = fs::write(path, content + "\n") {
+      return Err(UnreactError::IoError(err, path.to_string()));
+    }
+    Ok(())
+  }
+
+  /// Compare with lockfile of an older build
+  ///
+  /// Returns every input which was added, changed, or removed, such as `templates/index`, in order of sections
+  pub fn diff(&self, locked: &Lockfile) -> Vec<String> {
+    let mut inputs = Vec::new();
+    for ((section, current), (_, locked)) in self.sections().into_iter().zip(locked.sections()) {
+      let names = current
+        .keys()
+        .chain(locked.keys().filter(|name| !current.contains_key(*name)));
+      for name in names {
+        if current.get(name) != locked.get(name) {
+          inputs.push(format!("{section}/{name}"));
+        }
+      }
+    }
+    inputs
+  }
+
+  /// Get each section with its name
+  fn sections(&self) -> [(&str, &BTreeMap<String, String>); 7] {
+    [
+      ("templates", &self.templates),
+      ("partials", &self.partials),
+      ("styles", &self.styles),
+      ("data", &self.data),
+      ("content", &self.content),
+      ("locales", &self.locales),
+      ("public", &self.public),
+    ]
+  }
+}
+
+/// Hash every file in directory (recursively), or none if directory does not exist
+pub(crate) fn hash_dir(dir: &str) -> UnreactResult<BTreeMap<String, String>> {
+  if !Path::new(dir).is_dir() {
+    return Ok(BTreeMap::new());
+  }
+
+  let mut hashes = BTreeMap::new();
+  for path in list_files(dir)? {
+    let content = match fs::read(format!("./{dir}/{path}")) {
+      Ok(x) => x,
+      Err(err) => return Err(UnreactError::IoError(err, format!("./{dir}/{path}"))),
+    };
+    hashes.insert(path, hash_content(&content));
+  }
+  Ok(hashes)
+}
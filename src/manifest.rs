@@ -0,0 +1,205 @@
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs};
+
+use crate::{hash_content, list_files, UnreactError, UnreactResult};
+
+/// Name of manifest file, written to root of build directory
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Name of build manifest file, with sources of output files, written to root of build directory
+pub const BUILD_MANIFEST_FILE: &str = "build-manifest.json";
+
+/// List of every output file in build directory, with size and hash
+///
+/// Written to `manifest.json` in build directory if `manifest` is enabled in config
+///
+/// Compare with manifest of a previous build using `Manifest::diff`, to only upload changed files
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Manifest {
+  /// Output files, with path relative to build directory
+  pub files: BTreeMap<String, ManifestEntry>,
+}
+
+/// Single file in `Manifest`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+  /// Size of file in bytes
+  pub size: u64,
+  /// SHA-256 hash of file content, as hex
+  pub hash: String,
+}
+
+/// List of every output file in build directory, with size, hash, and what produced it
+///
+/// Written to `build-manifest.json` in build directory if `build_manifest` is enabled in config
+///
+/// # Examples
+///
+/// `build-manifest.json` of a page from Markdown content:
+///
+/// ```json
+/// {
+///   "files": {
+///     "posts/hello.html": {
+///       "size": 1024,
+///       "hash": "0123456789abcdef...",
+///       "template": "post",
+///       "source": "content/posts/hello.md"
+///     }
+///   }
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildManifest {
+  /// Output files, with path relative to build directory
+  pub files: BTreeMap<String, BuildManifestEntry>,
+}
+
+/// Single file in `BuildManifest`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildManifestEntry {
+  /// Size of file in bytes
+  pub size: u64,
+  /// SHA-256 hash of file content, as hex
+  pub hash: String,
+  /// Name of template which rendered page, if any
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub template: Option<String>,
+  /// Source file, relative to workspace, such as `content/posts/hello.md`, `styles/main.scss`, or `public/logo.png`
+  ///
+  /// `None` for generated files, such as feeds and sitemaps, and pages registered without a file
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub source: Option<String>,
+}
+
+impl BuildManifest {
+  /// Write build manifest as pretty JSON to file
+  pub fn write(&self, path: &str) -> UnreactResult<()> {
+    let content = match serde_json::to_string_pretty(self) {
+      Ok(x) => x,
+      Err(err) => return Err(UnreactError::ManifestFail(path.to_string(), err)),
+    };
+    if let Err(err) = fs::write(path, content) {
+      return Err(UnreactError::IoError(err, path.to_string()));
+    }
+    Ok(())
+  }
+}
+
+/// Difference between two manifests
+///
+/// All paths are relative to build directory
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ManifestDiff {
+  /// Files which did not exist in old manifest
+  pub added: Vec<String>,
+  /// Files which exist in both manifests, with a different hash
+  pub changed: Vec<String>,
+  /// Files which only exist in old manifest
+  pub removed: Vec<String>,
+}
+
+impl ManifestDiff {
+  /// Returns `true` if no files were added, changed, or removed
+  pub fn is_empty(&self) -> bool {
+    self.added.is_empty() && self.changed.is_empty() && self.removed.is_empty()
+  }
+}
+
+impl Manifest {
+  /// Create manifest from every file in directory (recursively)
+  ///
+  /// Does not include a `manifest.json` in the root of the directory
+  pub fn from_dir(dir: &str) -> UnreactResult<Self> {
+    let mut manifest = Manifest::default();
+
+    for path in list_files(dir)? {
+      if path == MANIFEST_FILE {
+        continue;
+      }
+
+      let content = match fs::read(format!("./{dir}/{path}")) {
+        Ok(x) => x,
+        Err(err) => return Err(UnreactError::IoError(err, format!("./{dir}/{path}"))),
+      };
+
+      manifest.files.insert(
+        path,
+        ManifestEntry {
+          size: content.len() as u64,
+          hash: hash_content(&content),
+        },
+      );
+    }
+
+    Ok(manifest)
+  }
+
+  /// Read manifest from a `manifest.json` file
+  ///
+  /// See `Unreact::diff_manifest`
+  pub fn read(path: &str) -> UnreactResult<Self> {
+    let content = match fs::read_to_string(path) {
+      Ok(x) => x,
+      Err(err) => return Err(UnreactError::IoError(err, path.to_string())),
+    };
+    match serde_json::from_str(&content) {
+      Ok(x) => Ok(x),
+      Err(err) => Err(UnreactError::ManifestFail(path.to_string(), err)),
+    }
+  }
+
+  /// Write manifest as pretty JSON to file
+  pub fn write(&self, path: &str) -> UnreactResult<()> {
+    let content = match serde_json::to_string_pretty(self) {
+      Ok(x) => x,
+      Err(err) => return Err(UnreactError::ManifestFail(path.to_string(), err)),
+    };
+    if let Err(err) = fs::write(path, content) {
+      return Err(UnreactError::IoError(err, path.to_string()));
+    }
+    Ok(())
+  }
+
+  /// Compare with manifest of an older build
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use unreact::prelude::*;
+  ///
+  /// let entry = |hash: &str| ManifestEntry { size: 1, hash: hash.to_string() };
+  ///
+  /// let mut old = Manifest::default();
+  /// old.files.insert("index.html".to_string(), entry("a"));
+  /// old.files.insert("old.html".to_string(), entry("b"));
+  ///
+  /// let mut new = Manifest::default();
+  /// new.files.insert("index.html".to_string(), entry("c"));
+  /// new.files.insert("new.html".to_string(), entry("d"));
+  ///
+  /// let diff = new.diff(&old);
+  /// assert_eq!(diff.added, vec!["new.html"]);
+  /// assert_eq!(diff.changed, vec!["index.html"]);
+  /// assert_eq!(diff.removed, vec!["old.html"]);
+  /// ```
+  pub fn diff(&self, old: &Manifest) -> ManifestDiff {
+    let mut diff = ManifestDiff::default();
+
+    for (path, entry) in &self.files {
+      match old.files.get(path) {
+        None => diff.added.push(path.to_string()),
+        Some(old_entry) if old_entry.hash != entry.hash => diff.changed.push(path.to_string()),
+        _ => (),
+      }
+    }
+
+    for path in old.files.keys() {
+      if !self.files.contains_key(path) {
+        diff.removed.push(path.to_string());
+      }
+    }
+
+    diff
+  }
+}
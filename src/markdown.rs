@@ -0,0 +1,463 @@
+use comrak::Options;
+use serde_json::{Map, Value};
+use std::ops::Range;
+
+use crate::{UnreactError, UnreactResult};
+
+/// Toggles for Markdown extensions
+///
+/// Use `MarkdownOptions::default()` for GitHub-flavored Markdown (all extensions enabled)
+///
+/// Use `MarkdownOptions::commonmark()` for plain CommonMark (all extensions disabled)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarkdownOptions {
+  /// Tables, using pipes (`| a | b |`)
+  ///
+  /// Default: `true`
+  pub tables: bool,
+  /// Task list items (`- [x] Done`)
+  ///
+  /// Default: `true`
+  pub task_lists: bool,
+  /// Footnotes (`[^1]`)
+  ///
+  /// Default: `true`
+  pub footnotes: bool,
+  /// Strikethrough text (`~~text~~`)
+  ///
+  /// Default: `true`
+  pub strikethrough: bool,
+  /// Bare URLs are converted to links (`https://example.com`)
+  ///
+  /// Default: `true`
+  pub autolinks: bool,
+  /// Headings are given an `id` attribute from a slug of their text, for anchor links
+  ///
+  /// Default: `true`
+  pub heading_ids: bool,
+}
+
+impl Default for MarkdownOptions {
+  fn default() -> Self {
+    MarkdownOptions {
+      tables: true,
+      task_lists: true,
+      footnotes: true,
+      strikethrough: true,
+      autolinks: true,
+      heading_ids: true,
+    }
+  }
+}
+
+impl MarkdownOptions {
+  /// Plain CommonMark, with all extensions disabled
+  pub fn commonmark() -> Self {
+    MarkdownOptions {
+      tables: false,
+      task_lists: false,
+      footnotes: false,
+      strikethrough: false,
+      autolinks: false,
+      heading_ids: false,
+    }
+  }
+}
+
+/// Convert Markdown to HTML, with extensions enabled from `options`
+///
+/// Raw HTML in Markdown is kept as-is, as content is written by the site author
+///
+/// # Examples
+///
+/// ```
+/// use unreact::prelude::*;
+///
+/// let html = unreact::markdown_to_html("~~old~~ new", &MarkdownOptions::default());
+/// assert_eq!(html, "<p><del>old</del> new</p>\n");
+///
+/// let html = unreact::markdown_to_html("~~old~~ new", &MarkdownOptions::commonmark());
+/// assert_eq!(html, "<p>~~old~~ new</p>\n");
+/// ```
+pub fn markdown_to_html(content: &str, options: &MarkdownOptions) -> String {
+  let mut comrak = Options::default();
+
+  comrak.extension.table = options.tables;
+  comrak.extension.tasklist = options.task_lists;
+  comrak.extension.footnotes = options.footnotes;
+  comrak.extension.strikethrough = options.strikethrough;
+  comrak.extension.autolink = options.autolinks;
+  // Empty prefix gives the same ids as GitHub, without the `user-content-` prefix
+  comrak.extension.header_id_prefix = if options.heading_ids {
+    Some(String::new())
+  } else {
+    None
+  };
+
+  // Allow raw html
+  comrak.render.r#unsafe = true;
+
+  comrak::markdown_to_html(content, &comrak)
+}
+
+/// Opening delimiter of a shortcode
+const SHORTCODE_OPEN: &str = "{{<";
+/// Closing delimiter of a shortcode
+const SHORTCODE_CLOSE: &str = ">}}";
+
+/// Shortcode found in Markdown content
+///
+/// `{{< youtube abc123 start="30" >}}` has name `youtube`, and data `{"args": ["abc123"], "start": "30"}`
+#[derive(Debug, PartialEq)]
+pub(crate) struct Shortcode {
+  /// Name of partial to render
+  pub name: String,
+  /// Data to render partial with
+  ///
+  /// Positional arguments are in `args`, named arguments are keys
+  pub data: Value,
+}
+
+/// Get placeholder text to substitute for shortcode with index
+///
+/// Only contains alphanumeric characters, so it passes through Markdown conversion unchanged
+pub(crate) fn shortcode_placeholder(index: usize) -> String {
+  format!("UNREACTSHORTCODE{index}X")
+}
+
+/// Replace every shortcode in Markdown content with a placeholder
+///
+/// Shortcodes in code spans and code blocks (fenced or indented) are left as text, so they can be shown in documentation
+///
+/// Returns the content with placeholders, and shortcodes in order of placeholder index
+///
+/// Returns error if a shortcode is not closed, or has no name
+pub(crate) fn extract_shortcodes(content: &str) -> UnreactResult<(String, Vec<Shortcode>)> {
+  let mut output = String::new();
+  let mut shortcodes = Vec::new();
+  let code = code_ranges(content);
+
+  let mut rest = content;
+  while let Some(start) = rest.find(SHORTCODE_OPEN) {
+    // Copy code which contains shortcode unchanged
+    let index = content.len() - rest.len() + start;
+    if let Some(range) = code.iter().find(|range| range.contains(&index)) {
+      let end = range.end - (content.len() - rest.len());
+      output.push_str(&rest[..end]);
+      rest = &rest[end..];
+      continue;
+    }
+
+    output.push_str(&rest[..start]);
+    let after = &rest[start + SHORTCODE_OPEN.len()..];
+
+    // Find end of shortcode
+    let end = match after.find(SHORTCODE_CLOSE) {
+      Some(x) => x,
+      None => {
+        return Err(UnreactError::ShortcodeFail(
+          rest[start..].lines().next().unwrap_or_default().to_string(),
+          "Shortcode is not closed with `>}}`".to_string(),
+        ))
+      }
+    };
+
+    let inner = &after[..end];
+    let shortcode = parse_shortcode(inner).map_err(|reason| {
+      UnreactError::ShortcodeFail(format!("{SHORTCODE_OPEN}{inner}{SHORTCODE_CLOSE}"), reason)
+    })?;
+
+    output.push_str(&shortcode_placeholder(shortcodes.len()));
+    shortcodes.push(shortcode);
+
+    rest = &after[end + SHORTCODE_CLOSE.len()..];
+  }
+  output.push_str(rest);
+
+  Ok((output, shortcodes))
+}
+
+/// Get byte ranges of fenced code blocks, indented code blocks, and code spans in Markdown content, in order
+///
+/// A fence is 3 or more backticks or tildes, indented by at most 3 spaces, and is closed by a fence of the same character and at least the same length.
+/// An indented code block is lines indented by 4 or more spaces (or a tab), after a blank line, and not in a list.
+/// A code span is closed by the next run of the same amount of backticks
+fn code_ranges(content: &str) -> Vec<Range<usize>> {
+  let mut ranges = Vec::new();
+  // Start of text between code blocks, and start, character, and length of open fence
+  let mut text_start = 0;
+  let mut fence: Option<(usize, char, usize)> = None;
+  // Start and end of open indented code block, without blank lines at end
+  let mut indented: Option<(usize, usize)> = None;
+  // If an indented code block can start on next line, as it cannot interrupt a paragraph
+  let mut after_blank = true;
+  // If lines are in a list, where indented lines continue an item
+  let mut in_list = false;
+
+  let mut line_start = 0;
+  for line in content.split_inclusive('\n') {
+    let line_end = line_start + line.len();
+    let trimmed = line.trim_start_matches(' ');
+    let marker = trimmed.chars().next().filter(|ch| *ch == '`' || *ch == '~');
+    let run = marker.map_or(0, |ch| trimmed.chars().take_while(|x| *x == ch).count());
+    let is_fence = line.len() - trimmed.len() <= 3 && run >= 3;
+    let is_blank = line.trim().is_empty();
+    let is_indented = !is_blank && indentation(line) >= 4;
+
+    if fence.is_none() {
+      match indented {
+        // Blank lines can be inside block
+        Some((start, _)) if is_indented => indented = Some((start, line_end)),
+        Some(_) if is_blank => (),
+        Some((start, end)) => {
+          ranges.push(start..end);
+          indented = None;
+          text_start = end;
+        }
+        None if is_indented && after_blank && !in_list => {
+          code_spans(content, text_start..line_start, &mut ranges);
+          indented = Some((line_start, line_end));
+        }
+        None => (),
+      }
+      if indented.is_some() {
+        line_start = line_end;
+        continue;
+      }
+      if !is_blank && !is_indented {
+        in_list = is_list_item(trimmed) || in_list && !after_blank;
+      }
+      after_blank = is_blank;
+    }
+
+    match fence {
+      // Closing fence has no info string
+      Some((start, ch, len))
+        if is_fence && marker == Some(ch) && run >= len && trimmed[run..].trim().is_empty() =>
+      {
+        ranges.push(start..line_end);
+        fence = None;
+        text_start = line_end;
+        after_blank = true;
+      }
+      Some(_) => (),
+      // Backtick fence cannot have backticks in info string
+      None if is_fence && !(marker == Some('`') && trimmed[run..].contains('`')) => {
+        code_spans(content, text_start..line_start, &mut ranges);
+        fence = Some((line_start, marker.unwrap_or('`'), run));
+      }
+      None => (),
+    }
+    line_start = line_end;
+  }
+
+  match (fence, indented) {
+    // Fence which is not closed continues to end
+    (Some((start, _, _)), _) => ranges.push(start..content.len()),
+    (None, Some((start, end))) => {
+      ranges.push(start..end);
+      code_spans(content, end..content.len(), &mut ranges);
+    }
+    (None, None) => code_spans(content, text_start..content.len(), &mut ranges),
+  }
+  ranges.sort_by_key(|range| range.start);
+  ranges
+}
+
+/// Get width of indentation of line, with tabs to next multiple of 4
+fn indentation(line: &str) -> usize {
+  let mut width = 0;
+  for ch in line.chars() {
+    match ch {
+      ' ' => width += 1,
+      '\t' => width += 4 - width % 4,
+      _ => break,
+    }
+  }
+  width
+}
+
+/// Check if line (without indentation) starts a list item, such as `- a` or `1. a`
+fn is_list_item(line: &str) -> bool {
+  let line = line.trim_end_matches(['\n', '\r']);
+  let rest = match line.strip_prefix(['-', '*', '+']) {
+    Some(rest) => rest,
+    None => {
+      let digits = line.chars().take_while(char::is_ascii_digit).count();
+      if !(1..=9).contains(&digits) {
+        return false;
+      }
+      match line[digits..].strip_prefix(['.', ')']) {
+        Some(rest) => rest,
+        None => return false,
+      }
+    }
+  };
+  rest.is_empty() || rest.starts_with([' ', '\t'])
+}
+
+/// Add byte ranges of code spans in part of Markdown content, outside of code blocks
+fn code_spans(content: &str, part: Range<usize>, ranges: &mut Vec<Range<usize>>) {
+  let bytes = content.as_bytes();
+  let mut i = part.start;
+  while i < part.end {
+    if bytes[i] != b'`' {
+      i += 1;
+      continue;
+    }
+    let start = i;
+    while i < part.end && bytes[i] == b'`' {
+      i += 1;
+    }
+    let len = i - start;
+
+    // Find run of the same length, otherwise backticks are text
+    let mut j = i;
+    while j < part.end {
+      if bytes[j] != b'`' {
+        j += 1;
+        continue;
+      }
+      let run_start = j;
+      while j < part.end && bytes[j] == b'`' {
+        j += 1;
+      }
+      if j - run_start == len {
+        ranges.push(start..j);
+        i = j;
+        break;
+      }
+    }
+  }
+}
+
+/// Parse name and arguments of shortcode, between delimiters
+fn parse_shortcode(inner: &str) -> Result<Shortcode, String> {
+  let mut words = split_arguments(inner)?.into_iter();
+
+  // First word is name
+  let name = match words.next() {
+    Some((name, false)) => name,
+    _ => return Err("Shortcode has no name".to_string()),
+  };
+
+  let mut data = Map::new();
+  let mut args = Vec::new();
+  for (word, quoted) in words {
+    // Named argument, unless the `=` was inside quotes
+    match word.split_once('=') {
+      Some((key, value)) if !quoted && !key.is_empty() => {
+        data.insert(key.to_string(), Value::String(unquote(value)));
+      }
+      _ => args.push(Value::String(word)),
+    }
+  }
+  data.insert("args".to_string(), Value::Array(args));
+
+  Ok(Shortcode {
+    name,
+    data: Value::Object(data),
+  })
+}
+
+/// Split shortcode by whitespace, keeping quoted strings together
+///
+/// Returns each word, and whether the word started with a quote
+fn split_arguments(inner: &str) -> Result<Vec<(String, bool)>, String> {
+  let mut words = Vec::new();
+  let mut word = String::new();
+  let mut in_quotes = false;
+  let mut started_quoted = false;
+
+  for ch in inner.chars() {
+    match ch {
+      '"' => {
+        if word.is_empty() && !in_quotes {
+          started_quoted = true;
+        } else if !started_quoted {
+          // Keep quotes of named arguments, removed by `unquote`
+          word.push(ch);
+        }
+        in_quotes = !in_quotes;
+      }
+      ch if ch.is_whitespace() && !in_quotes => {
+        if !word.is_empty() || started_quoted {
+          words.push((std::mem::take(&mut word), started_quoted));
+        }
+        started_quoted = false;
+      }
+      ch => word.push(ch),
+    }
+  }
+
+  if in_quotes {
+    return Err("Quote is not closed".to_string());
+  }
+  if !word.is_empty() || started_quoted {
+    words.push((word, started_quoted));
+  }
+
+  Ok(words)
+}
+
+/// Remove surrounding quotes from named argument value
+fn unquote(value: &str) -> String {
+  value
+    .strip_prefix('"')
+    .and_then(|x| x.strip_suffix('"'))
+    .unwrap_or(value)
+    .to_string()
+}
+
+/// Replace placeholders in converted html with rendered shortcodes
+///
+/// Placeholder alone in a paragraph is replaced with the paragraph, so block elements are not wrapped in `<p>`
+pub(crate) fn insert_shortcodes(html: &str, rendered: &[String]) -> String {
+  let mut html = html.to_string();
+  for (i, content) in rendered.iter().enumerate() {
+    let placeholder = shortcode_placeholder(i);
+    html = html
+      .replace(&format!("<p>{placeholder}</p>"), content)
+      .replace(&placeholder, content);
+  }
+  html
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// Get text of each code range
+  fn code(content: &str) -> Vec<&str> {
+    code_ranges(content)
+      .into_iter()
+      .map(|range| &content[range])
+      .collect()
+  }
+
+  #[test]
+  fn fences_and_spans() {
+    assert_eq!(
+      code("a `b` c\n```rust\nd\n```\n`e`"),
+      ["`b`", "```rust\nd\n```\n", "`e`"]
+    );
+    // Fence which is not closed continues to end
+    assert_eq!(code("~~~\na\n```\n"), ["~~~\na\n```\n"]);
+  }
+
+  #[test]
+  fn indented_code() {
+    assert_eq!(
+      code("Text\n\n    a\n\n\tb\n\nText `c`\n"),
+      ["    a\n\n\tb\n", "`c`"]
+    );
+    assert_eq!(code("    a\n    b"), ["    a\n    b"]);
+    assert_eq!(code("```\nx\n```\n    a\n"), ["```\nx\n```\n", "    a\n"]);
+
+    // Paragraph continues, without blank line
+    assert!(code("Text\n    a\n").is_empty());
+    // Item of list continues
+    assert!(code("- Item\n\n    a\n\n1. Item\n\n    b\n").is_empty());
+    assert_eq!(code("- Item\n\nText\n\n    a\n"), ["    a\n"]);
+  }
+}
@@ -0,0 +1,83 @@
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+
+/// Unicode normalization of page paths, see `Config::normalize_paths`
+///
+/// macOS file systems can store names decomposed (NFD), such as `é` as `e` and an accent, so the same path can be different bytes on different machines.
+/// Normalizing paths makes builds and urls the same everywhere
+///
+/// Default: `PathNormalization::Nfc`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PathNormalization {
+  /// Paths are unchanged
+  Keep,
+  /// Composed form, such as `é` as one character. Used by most systems and urls
+  #[default]
+  Nfc,
+  /// Decomposed form, such as `é` as `e` and a combining accent
+  Nfd,
+  /// Latin letters with accents are replaced with ASCII letters, such as `café` to `cafe` and `straße` to `strasse`
+  ///
+  /// Other scripts (such as Cyrillic or Chinese) are kept, in composed form
+  Ascii,
+}
+
+/// Normalize page path
+///
+/// # Examples
+///
+/// ```
+/// use unreact::{normalize_path, PathNormalization};
+///
+/// // Decomposed `é`, such as from a file name on macOS
+/// let path = "posts/cafe\u{301}";
+///
+/// assert_eq!(normalize_path(path, PathNormalization::Nfc), "posts/caf\u{e9}");
+/// assert_eq!(normalize_path(path, PathNormalization::Ascii), "posts/cafe");
+/// assert_eq!(normalize_path("Straße/Æther", PathNormalization::Ascii), "Strasse/AEther");
+/// ```
+pub fn normalize_path(path: &str, mode: PathNormalization) -> String {
+  match mode {
+    PathNormalization::Keep => path.to_string(),
+    PathNormalization::Nfc => path.nfc().collect(),
+    PathNormalization::Nfd => path.nfd().collect(),
+    PathNormalization::Ascii => {
+      let mut output = String::new();
+      for ch in path.nfd().filter(|ch| !is_combining_mark(*ch)) {
+        match transliterate(ch) {
+          Some(replacement) => output.push_str(replacement),
+          None => output.push(ch),
+        }
+      }
+      // Compose any other scripts again
+      output.nfc().collect()
+    }
+  }
+}
+
+/// Get ASCII replacement of Latin letter which does not decompose into letter and accent
+fn transliterate(ch: char) -> Option<&'static str> {
+  Some(match ch {
+    'ß' => "ss",
+    'ẞ' => "SS",
+    'æ' => "ae",
+    'Æ' => "AE",
+    'œ' => "oe",
+    'Œ' => "OE",
+    'ø' => "o",
+    'Ø' => "O",
+    'đ' | 'ð' => "d",
+    'Đ' | 'Ð' => "D",
+    'ł' => "l",
+    'Ł' => "L",
+    'þ' => "th",
+    'Þ' => "TH",
+    'ı' => "i",
+    'ħ' => "h",
+    'Ħ' => "H",
+    // Typographic punctuation, common in titles
+    '‘' | '’' => "'",
+    '“' | '”' => "\"",
+    '–' | '—' => "-",
+    _ => return None,
+  })
+}
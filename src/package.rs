@@ -0,0 +1,123 @@
+use flate2::{write::GzEncoder, Compression};
+use std::{fs, io::Write};
+
+use crate::{list_files, UnreactError, UnreactResult};
+
+/// Default compression level for packages, from `0` (none) to `9` (best)
+pub const DEFAULT_PACKAGE_LEVEL: u32 = 6;
+
+/// Archive format of package, inferred from file extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+  /// `.tar`
+  Tar,
+  /// `.tar.gz` or `.tgz`
+  TarGz,
+  /// `.zip`
+  Zip,
+}
+
+impl Format {
+  /// Get format from extension of path
+  fn from_path(path: &str) -> Option<Self> {
+    if path.ends_with(".tar.gz") || path.ends_with(".tgz") {
+      Some(Format::TarGz)
+    } else if path.ends_with(".tar") {
+      Some(Format::Tar)
+    } else if path.ends_with(".zip") {
+      Some(Format::Zip)
+    } else {
+      None
+    }
+  }
+}
+
+/// Archive every file in directory `dir` to `path`
+///
+/// Files are added in sorted order, with fixed timestamps and permissions, so the same build produces an identical archive
+///
+/// `level`: Compression level from `0` (none) to `9` (best). Ignored for `.tar`
+pub(crate) fn package_dir(dir: &str, path: &str, level: u32) -> UnreactResult<()> {
+  let format = match Format::from_path(path) {
+    Some(x) => x,
+    None => {
+      return Err(UnreactError::PackageFail(
+        path.to_string(),
+        "Unknown archive format, use `.tar`, `.tar.gz`, `.tgz`, or `.zip`".to_string(),
+      ))
+    }
+  };
+
+  let files = list_files(dir)?;
+
+  let output = match fs::File::create(path) {
+    Ok(x) => x,
+    Err(err) => return Err(UnreactError::IoError(err, path.to_string())),
+  };
+  let io_err = |err| UnreactError::IoError(err, path.to_string());
+
+  match format {
+    Format::Tar => {
+      write_tar(output, dir, &files).map_err(io_err)?;
+    }
+    Format::TarGz => {
+      let encoder = GzEncoder::new(output, Compression::new(level.min(9)));
+      write_tar(encoder, dir, &files)
+        .and_then(|encoder| encoder.finish())
+        .map_err(io_err)?;
+    }
+    Format::Zip => {
+      write_zip(output, dir, &files, level)
+        .map_err(|err| UnreactError::PackageFail(path.to_string(), err.to_string()))?;
+    }
+  }
+
+  Ok(())
+}
+
+/// Write files to tar archive, returning inner writer
+fn write_tar<W: Write>(writer: W, dir: &str, files: &[String]) -> std::io::Result<W> {
+  let mut builder = tar::Builder::new(writer);
+
+  for file in files {
+    let content = fs::read(format!("./{dir}/{file}"))?;
+
+    let mut header = tar::Header::new_gnu();
+    header.set_size(content.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(0);
+    header.set_uid(0);
+    header.set_gid(0);
+    header.set_cksum();
+
+    builder.append_data(&mut header, file, content.as_slice())?;
+  }
+
+  builder.into_inner()
+}
+
+/// Write files to zip archive
+fn write_zip(
+  writer: fs::File,
+  dir: &str,
+  files: &[String],
+  level: u32,
+) -> zip::result::ZipResult<()> {
+  use zip::{write::SimpleFileOptions, CompressionMethod, DateTime, ZipWriter};
+
+  let options = SimpleFileOptions::default()
+    .compression_method(CompressionMethod::Deflated)
+    .compression_level(Some(level.min(9) as i64))
+    .last_modified_time(DateTime::DEFAULT)
+    .unix_permissions(0o644);
+
+  let mut zip = ZipWriter::new(writer);
+  for file in files {
+    let content = fs::read(format!("./{dir}/{file}"))?;
+    zip.start_file(file, options)?;
+    zip.write_all(&content)?;
+  }
+  zip.finish()?;
+
+  Ok(())
+}
@@ -0,0 +1,79 @@
+use std::{
+  sync::{
+    atomic::{AtomicBool, AtomicUsize, Ordering},
+    mpsc,
+  },
+  thread,
+};
+
+use crate::UnreactResult;
+
+/// Get amount of threads to use from `threads` in config
+///
+/// `0` is the amount of available CPUs
+pub(crate) fn thread_count(threads: usize) -> usize {
+  match threads {
+    0 => thread::available_parallelism().map_or(1, |x| x.get()),
+    x => x,
+  }
+}
+
+/// Run `work` for every item, on up to `threads` threads
+///
+/// `done` is called on the current thread with the result of each item, in order of completion (not in order of items).
+/// If `done` returns an error, remaining items are not started, and the error is returned
+///
+/// Runs on the current thread only, in order of items, if `threads` is `1` or there is only one item
+pub(crate) fn for_each_parallel<T, R>(
+  items: &[T],
+  threads: usize,
+  work: impl Fn(&T) -> R + Sync,
+  mut done: impl FnMut(usize, R) -> UnreactResult<()>,
+) -> UnreactResult<()>
+where
+  T: Sync,
+  R: Send,
+{
+  let threads = threads.min(items.len());
+  if threads <= 1 {
+    for (i, item) in items.iter().enumerate() {
+      done(i, work(item))?;
+    }
+    return Ok(());
+  }
+
+  // Index of next item to start
+  let next = AtomicUsize::new(0);
+  // Set when `done` fails, so no more items are started
+  let stop = AtomicBool::new(false);
+
+  thread::scope(|scope| {
+    let (sender, receiver) = mpsc::channel();
+
+    for _ in 0..threads {
+      let sender = sender.clone();
+      let (next, stop, work) = (&next, &stop, &work);
+      scope.spawn(move || {
+        while !stop.load(Ordering::Relaxed) {
+          let i = next.fetch_add(1, Ordering::Relaxed);
+          let Some(item) = items.get(i) else {
+            break;
+          };
+          if sender.send((i, work(item))).is_err() {
+            break;
+          }
+        }
+      });
+    }
+    // Receiver ends when all threads finish
+    drop(sender);
+
+    for (i, result) in receiver {
+      if let Err(err) = done(i, result) {
+        stop.store(true, Ordering::Relaxed);
+        return Err(err);
+      }
+    }
+    Ok(())
+  })
+}
@@ -0,0 +1,193 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Documentation of a template or partial, from comments at start of file, see `Unreact::partial_docs`
+///
+/// Lines starting with `@param` describe a parameter, as `@param name: type - description`.
+/// Type and description are optional, and a name ending with `?` is optional, such as `@param subtitle?: string`.
+/// Other lines are the description of the partial
+///
+/// # Examples
+///
+/// ```
+/// use unreact::prelude::*;
+///
+/// let template = r#"{{!--
+///   Card with a title, and an optional image
+///   @param title: string - Heading of card
+///   @param image?: string
+/// --}}
+/// <div class="card"><h2>{{title}}</h2></div>"#;
+///
+/// let doc = PartialDoc::parse(template).unwrap();
+/// assert_eq!(doc.description, "Card with a title, and an optional image");
+/// assert_eq!(
+///   doc.params,
+///   vec![
+///     PartialParam {
+///       name: "title".to_string(),
+///       kind: "string".to_string(),
+///       optional: false,
+///       description: "Heading of card".to_string(),
+///     },
+///     PartialParam {
+///       name: "image".to_string(),
+///       kind: "string".to_string(),
+///       optional: true,
+///       description: String::new(),
+///     },
+///   ]
+/// );
+///
+/// assert_eq!(PartialDoc::parse("<p>No comment</p>"), None);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PartialDoc {
+  /// Description of partial, with lines joined by newlines
+  pub description: String,
+  /// Parameters of partial, in order written
+  pub params: Vec<PartialParam>,
+}
+
+/// Parameter of a template or partial, from a `@param` line, see `PartialDoc`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PartialParam {
+  /// Name of parameter, without `?`
+  pub name: String,
+  /// Type of parameter as written, such as `string` or `Post[]`, or empty
+  #[serde(rename = "type")]
+  pub kind: String,
+  /// If name ends with `?`
+  pub optional: bool,
+  /// Description of parameter, or empty
+  pub description: String,
+}
+
+impl PartialDoc {
+  /// Parse comments at start of template, such as `{{!-- @param title: string --}}`
+  ///
+  /// Returns `None` if template does not start with a comment. Whitespace, and more comments, can be between comments
+  pub fn parse(template: &str) -> Option<Self> {
+    let mut rest = template.trim_start();
+    let mut comments = Vec::new();
+    loop {
+      let (comment, after) = if let Some(comment) = rest.strip_prefix("{{!--") {
+        comment.split_once("--}}")?
+      } else if let Some(comment) = rest.strip_prefix("{{!") {
+        comment.split_once("}}")?
+      } else {
+        break;
+      };
+      comments.push(comment);
+      rest = after.trim_start();
+    }
+    if comments.is_empty() {
+      return None;
+    }
+
+    let mut doc = PartialDoc::default();
+    let mut description = Vec::new();
+    for line in comments.iter().flat_map(|comment| comment.lines()) {
+      let line = line.trim();
+      match line.strip_prefix("@param") {
+        Some(param) if param.is_empty() || param.starts_with(char::is_whitespace) => {
+          doc.params.extend(parse_param(param.trim()));
+        }
+        // Other tags are left out
+        _ if line.starts_with('@') => (),
+        _ => description.push(line),
+      }
+    }
+    doc.description = description.join("\n").trim().to_string();
+    Some(doc)
+  }
+}
+
+/// Parse parameter, such as `title: string - Heading of card`
+fn parse_param(param: &str) -> Option<PartialParam> {
+  let (param, description) = match param.split_once(" - ") {
+    Some((param, description)) => (param, description.trim()),
+    None => (param, ""),
+  };
+  let (name, kind) = match param.split_once(':') {
+    Some((name, kind)) => (name.trim(), kind.trim()),
+    None => (param.trim(), ""),
+  };
+  let (name, optional) = match name.strip_suffix('?') {
+    Some(name) => (name, true),
+    None => (name, false),
+  };
+  if name.is_empty() {
+    return None;
+  }
+  Some(PartialParam {
+    name: name.to_string(),
+    kind: kind.to_string(),
+    optional,
+    description: description.to_string(),
+  })
+}
+
+/// Create JSON of documentation of each partial, by name, see `Config::partial_docs`
+pub(crate) fn partial_docs_json(docs: &BTreeMap<String, PartialDoc>) -> String {
+  serde_json::to_string_pretty(docs).unwrap_or_default() + "\n"
+}
+
+/// Create html of reference page, listing documentation of each partial, served at `/__unreact/partials/` in dev mode
+#[cfg(feature = "dev-server")]
+pub(crate) fn partial_docs_page(docs: &BTreeMap<String, PartialDoc>) -> String {
+  use handlebars::html_escape as escape;
+
+  let mut sections = String::new();
+  for (name, doc) in docs {
+    sections += &format!("<h2><code>{}</code></h2>", escape(name));
+    for paragraph in doc.description.split("\n\n") {
+      sections += &format!("<p>{}</p>", escape(paragraph));
+    }
+    if doc.params.is_empty() {
+      continue;
+    }
+
+    sections += "<table><tr><th>Parameter</th><th>Type</th><th>Description</th></tr>";
+    for param in &doc.params {
+      sections += &format!(
+        "<tr><td><code>{}</code>{}</td><td><code>{}</code></td><td>{}</td></tr>",
+        escape(&param.name),
+        if param.optional { " (optional)" } else { "" },
+        escape(if param.kind.is_empty() {
+          "any"
+        } else {
+          &param.kind
+        }),
+        escape(&param.description),
+      );
+    }
+    sections += "</table>";
+  }
+  if docs.is_empty() {
+    sections = "<p>No partials start with a comment</p>".to_string();
+  }
+
+  format!(
+    r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8" />
+<meta name="viewport" content="width=device-width, initial-scale=1" />
+<title>Unreact partials</title>
+<style>
+body {{ font-family: system-ui, sans-serif; max-width: 60rem; margin: 2rem auto; padding: 0 1rem; }}
+table {{ border-collapse: collapse; width: 100%; }}
+th, td {{ text-align: left; padding: 0.25rem 0.5rem; border-bottom: 1px solid #ccc; }}
+</style>
+</head>
+<body>
+<h1>Unreact partials</h1>
+<p>{} documented templates and partials. Only generated in dev mode</p>
+{sections}
+</body>
+</html>
+"#,
+    docs.len(),
+  )
+}
@@ -0,0 +1,72 @@
+/// Maximum length of output path, relative to build directory
+///
+/// Windows limits full paths to 260 characters, so this leaves room for the directory the site is checked out or built in
+pub(crate) const MAX_PATH_LENGTH: usize = 200;
+
+/// Maximum length of each file or directory name, in bytes, on most file systems
+const MAX_NAME_LENGTH: usize = 255;
+
+/// Names which cannot be used for files on Windows, with or without an extension
+const RESERVED_NAMES: &[&str] = &[
+  "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+  "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Characters which cannot be used in file names on Windows
+const INVALID_CHARS: &[char] = &['<', '>', ':', '"', '\\', '|', '?', '*'];
+
+/// Check that output file can be written and checked out on every OS
+///
+/// `file`: Path relative to build directory, **with** extension
+///
+/// Returns reason if path is not valid
+pub(crate) fn check_path(file: &str) -> Result<(), String> {
+  if file.chars().count() > MAX_PATH_LENGTH {
+    return Err(format!(
+      "Path is longer than {MAX_PATH_LENGTH} characters, which is too long for Windows"
+    ));
+  }
+
+  // Leading slash is ignored when writing
+  for name in file.trim_start_matches('/').split('/') {
+    if name.is_empty() {
+      return Err("Path contains an empty file or directory name".to_string());
+    }
+    if name == "." || name == ".." {
+      return Err(format!(
+        "Path contains `{name}` as a file or directory name"
+      ));
+    }
+    if name.len() > MAX_NAME_LENGTH {
+      return Err(format!(
+        "Name `{name}` is longer than {MAX_NAME_LENGTH} bytes"
+      ));
+    }
+    if let Some(ch) = name
+      .chars()
+      .find(|ch| INVALID_CHARS.contains(ch) || ch.is_control())
+    {
+      return Err(format!(
+        "Name `{name}` contains {ch:?}, which is not allowed on Windows"
+      ));
+    }
+    if name.ends_with(['.', ' ']) {
+      return Err(format!(
+        "Name `{name}` ends with a dot or space, which is removed on Windows"
+      ));
+    }
+
+    // `aux.html` and `con.tar.gz` are reserved too
+    let stem = name.split('.').next().unwrap_or(name).trim_end();
+    if RESERVED_NAMES
+      .iter()
+      .any(|reserved| reserved.eq_ignore_ascii_case(stem))
+    {
+      return Err(format!(
+        "Name `{name}` is reserved on Windows, and cannot be checked out"
+      ));
+    }
+  }
+
+  Ok(())
+}
@@ -0,0 +1,124 @@
+use std::fmt;
+
+use crate::{CacheStats, Profile, UnreactError, UnreactResult, Vfs};
+
+/// Extension of the build pipeline, which other crates can publish, registered with `Unreact::plugin`
+///
+/// Every method has a default which does nothing, so a plugin only implements the phases it needs, and methods added in later versions do not break plugins.
+/// Phases run in order: `load` when registered (and when source files are read again in dev), `transform` for each page, `write` for each output file, then `report` after each build
+///
+/// `transform` and `write` must give the same output for the same input, or `cache_version` in config must be changed when they change
+///
+/// # Examples
+///
+/// ```
+/// use unreact::prelude::*;
+/// use serde_json::json;
+///
+/// /// Adds a footer template, and a copyright line to every page
+/// #[derive(Debug)]
+/// struct Copyright(String);
+///
+/// impl Plugin for Copyright {
+///   fn name(&self) -> &str {
+///     "copyright"
+///   }
+///
+///   fn load(&self, vfs: &mut Vfs) -> Result<(), String> {
+///     vfs.templates.insert("footer".to_string(), "<footer>{{>@partial-block}}</footer>".to_string());
+///     Ok(())
+///   }
+///
+///   fn transform(&self, _path: &str, html: &str) -> String {
+///     html.replace("</body>", &format!("<p>(c) {}</p></body>", self.0))
+///   }
+/// }
+///
+/// fn main() -> UnreactResult<()> {
+///   let mut vfs = Vfs::new();
+///   vfs.templates.insert("index".to_string(), "<body>{{#>footer}}Home{{/footer}}</body>".to_string());
+///
+///   let url = SiteUrl::parse("https://mysite.com")?;
+///   let mut app = Unreact::from_vfs(Config::default(), false, url, vfs);
+///   app.plugin(Copyright("Me".to_string()))?;
+///   app.index("index", &json!({}))?;
+///
+///   let files = app.compile()?;
+///   assert_eq!(files["index.html"], "<body><footer>Home</footer><p>(c) Me");
+///
+///   Ok(())
+/// }
+/// ```
+pub trait Plugin: fmt::Debug + Send + Sync {
+  /// Name of plugin, such as `"katex"`, used in errors
+  fn name(&self) -> &str;
+
+  /// Change source files after they are read, such as adding templates, styles, or data
+  ///
+  /// Returns error message if plugin cannot be loaded, as `UnreactError::PluginFail`
+  fn load(&self, vfs: &mut Vfs) -> Result<(), String> {
+    let _ = vfs;
+    Ok(())
+  }
+
+  /// Change html of page after it is rendered, before minify or format, see `Unreact::on_page_rendered`
+  ///
+  /// `path`: Path of page, **without** `.html` extension
+  fn transform(&self, path: &str, html: &str) -> String {
+    let _ = path;
+    html.to_string()
+  }
+
+  /// Change content of output file before it is written, see `Unreact::before_write`
+  ///
+  /// `file`: Path relative to build directory, **with** extension
+  fn write(&self, file: &str, content: &str) -> String {
+    let _ = file;
+    content.to_string()
+  }
+
+  /// Read report of build, after every file is written
+  fn report(&self, report: &BuildReport) {
+    let _ = report;
+  }
+}
+
+/// Summary of a finished build, given to `Plugin::report`
+///
+/// More fields can be added without a breaking change, so it cannot be created outside of Unreact
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct BuildReport {
+  /// If build is in dev mode
+  pub is_dev: bool,
+  /// Every output file, with path relative to build directory, in order
+  pub files: Vec<String>,
+  /// Statistics of build cache since app was created, if `cache` is set in config
+  pub cache: Option<CacheStats>,
+  /// Render times of templates since app was created, if `profile` is set in config
+  pub profile: Option<Profile>,
+}
+
+impl BuildReport {
+  /// Create report of build
+  pub(crate) fn new(
+    is_dev: bool,
+    files: Vec<String>,
+    cache: Option<CacheStats>,
+    profile: Option<Profile>,
+  ) -> Self {
+    BuildReport {
+      is_dev,
+      files,
+      cache,
+      profile,
+    }
+  }
+}
+
+/// Load plugin into source files, with name of plugin in error
+pub(crate) fn load_plugin(plugin: &dyn Plugin, vfs: &mut Vfs) -> UnreactResult<()> {
+  plugin
+    .load(vfs)
+    .map_err(|reason| UnreactError::PluginFail(plugin.name().to_string(), reason))
+}
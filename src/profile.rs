@@ -0,0 +1,100 @@
+use std::{
+  collections::BTreeMap,
+  fmt,
+  time::{Duration, Instant},
+};
+
+/// Render times and call counts of every template, across a build
+///
+/// Collected if `profile` is enabled in config, and given in `BuildReport::profile` after each build
+///
+/// Times are inclusive, so time of a template includes time of partials it uses
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Profile {
+  /// Statistics of each template, by name
+  pub templates: BTreeMap<String, TemplateProfile>,
+  /// Partials currently rendering, with start time
+  stack: Vec<(String, Instant)>,
+}
+
+/// Statistics of a single template in `Profile`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TemplateProfile {
+  /// Amount of times rendered as a page (or with `Unreact::render`)
+  pub renders: u64,
+  /// Amount of times used as a partial
+  pub partial_calls: u64,
+  /// Total time spent rendering, as page or partial
+  pub time: Duration,
+}
+
+impl Profile {
+  /// Record render of template as page
+  pub(crate) fn record_render(&mut self, name: &str, time: Duration) {
+    let entry = self.templates.entry(name.to_string()).or_default();
+    entry.renders += 1;
+    entry.time += time;
+  }
+
+  /// Record start of partial
+  pub(crate) fn start_partial(&mut self, name: &str) {
+    self.stack.push((name.to_string(), Instant::now()));
+  }
+
+  /// Record end of most recently started partial
+  pub(crate) fn end_partial(&mut self) {
+    if let Some((name, start)) = self.stack.pop() {
+      let entry = self.templates.entry(name).or_default();
+      entry.partial_calls += 1;
+      entry.time += start.elapsed();
+    }
+  }
+
+  /// Clear partials which did not end, such as after a failed render
+  pub(crate) fn clear_stack(&mut self) {
+    self.stack.clear();
+  }
+}
+
+impl fmt::Display for Profile {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    // Slowest first
+    let mut templates = self.templates.iter().collect::<Vec<_>>();
+    templates.sort_by(|a, b| b.1.time.cmp(&a.1.time).then(a.0.cmp(b.0)));
+
+    let width = templates
+      .iter()
+      .map(|(name, _)| name.len())
+      .max()
+      .unwrap_or_default()
+      .max("Template".len());
+
+    writeln!(f, "Template profile:")?;
+    writeln!(
+      f,
+      "  {:width$}  {:>8}  {:>8}  {:>12}",
+      "Template", "Renders", "Partial", "Time"
+    )?;
+    for (name, stats) in templates {
+      writeln!(
+        f,
+        "  {name:width$}  {:>8}  {:>8}  {:>12}",
+        stats.renders,
+        stats.partial_calls,
+        format!("{:.3?}", stats.time),
+      )?;
+    }
+    Ok(())
+  }
+}
+
+/// Wrap partial source, to record its render time with `Profile` helpers
+pub(crate) fn wrap_partial(name: &str, source: &str) -> String {
+  let name = name.replace('\\', "\\\\").replace('"', "\\\"");
+  format!("{{{{{PROFILE_START} \"{name}\"}}}}{source}{{{{{PROFILE_END}}}}}")
+}
+
+/// Name of helper to record start of partial
+pub(crate) const PROFILE_START: &str = "__unreact_profile_start";
+/// Name of helper to record end of partial
+pub(crate) const PROFILE_END: &str = "__unreact_profile_end";
@@ -0,0 +1,91 @@
+use std::ops::ControlFlow;
+
+use crate::{UnreactError, UnreactResult};
+
+/// Part of a build, in order of `Unreact::finish_with_progress`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildStage {
+  /// Writing a page
+  Pages,
+  /// Compiling and writing a style
+  Styles,
+  /// Copying public files
+  Public,
+  /// Creating manifest of output files
+  Manifest,
+}
+
+/// Progress of a build, given to callback of `Unreact::finish_with_progress` after each step
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuildProgress {
+  /// Stage of the finished step
+  pub stage: BuildStage,
+  /// Output path of the finished step, relative to build directory
+  ///
+  /// Empty for `BuildStage::Public` and `BuildStage::Manifest`
+  pub path: String,
+  /// Amount of steps finished, including this one
+  pub done: usize,
+  /// Amount of steps in whole build
+  pub total: usize,
+}
+
+impl BuildProgress {
+  /// Percentage of build finished, from `0.0` to `100.0`
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use unreact::prelude::*;
+  ///
+  /// let progress = BuildProgress {
+  ///   stage: BuildStage::Pages,
+  ///   path: "index.html".to_string(),
+  ///   done: 1,
+  ///   total: 4,
+  /// };
+  /// assert_eq!(progress.percent(), 25.0);
+  /// ```
+  pub fn percent(&self) -> f32 {
+    if self.total == 0 {
+      return 100.0;
+    }
+    self.done as f32 / self.total as f32 * 100.0
+  }
+}
+
+/// Counts finished steps of a build, and reports each to a callback
+pub(crate) struct ProgressTracker<'a> {
+  callback: &'a mut dyn FnMut(BuildProgress) -> ControlFlow<()>,
+  done: usize,
+  total: usize,
+}
+
+impl<'a> ProgressTracker<'a> {
+  /// Create tracker for a build with `total` steps
+  pub fn new(callback: &'a mut dyn FnMut(BuildProgress) -> ControlFlow<()>, total: usize) -> Self {
+    ProgressTracker {
+      callback,
+      done: 0,
+      total,
+    }
+  }
+
+  /// Report a finished step
+  ///
+  /// Returns error if callback breaks, to stop the build
+  pub fn step(&mut self, stage: BuildStage, path: &str) -> UnreactResult<()> {
+    self.done += 1;
+    let progress = BuildProgress {
+      stage,
+      path: path.to_string(),
+      done: self.done,
+      total: self.total,
+    };
+
+    match (self.callback)(progress) {
+      ControlFlow::Continue(()) => Ok(()),
+      ControlFlow::Break(()) => Err(UnreactError::BuildAborted(self.done, self.total)),
+    }
+  }
+}
@@ -0,0 +1,124 @@
+use std::{
+  fs,
+  time::{Duration, SystemTime},
+};
+
+use crate::{ignore::filter_ignored, list_files, Config, UnreactError, UnreactResult};
+
+/// List every file in public directory recursively, without files left out by ignore files, see `Config::ignore_files`
+pub(crate) fn list_public(config: &Config) -> UnreactResult<Vec<String>> {
+  let files = list_files(&config.public)?;
+  Ok(filter_ignored(config, &config.public, files))
+}
+
+/// Options for copying files of public directory to build directory, see `Config::public_copy`
+///
+/// Default: Keep permissions, use time of copy as modified time
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CopyOptions {
+  /// Permissions of copied files, such as executable bit of shell scripts
+  ///
+  /// Default: `PermissionMode::Preserve`
+  pub permissions: PermissionMode,
+  /// Modified time of copied files
+  ///
+  /// Default: `TimestampMode::Now`
+  pub timestamps: TimestampMode,
+}
+
+impl CopyOptions {
+  /// Normalize permissions, and set modified time to `SOURCE_DATE_EPOCH` environment variable (or Unix epoch if not set)
+  ///
+  /// The build directory is the same on every machine, for reproducible builds
+  pub fn reproducible() -> Self {
+    let seconds = std::env::var("SOURCE_DATE_EPOCH")
+      .ok()
+      .and_then(|x| x.trim().parse().ok())
+      .unwrap_or(0);
+
+    CopyOptions {
+      permissions: PermissionMode::Normalize,
+      timestamps: TimestampMode::Fixed(SystemTime::UNIX_EPOCH + Duration::from_secs(seconds)),
+    }
+  }
+}
+
+/// Permissions of copied public files
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PermissionMode {
+  /// Same permissions as source file
+  #[default]
+  Preserve,
+  /// Writable by owner, readable by everyone (`644`), and executable by everyone if source file is executable (`755`)
+  ///
+  /// Read-only files are made writable on other platforms
+  Normalize,
+}
+
+/// Modified time of copied public files
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimestampMode {
+  /// Time that file was copied
+  #[default]
+  Now,
+  /// Same modified time as source file
+  Preserve,
+  /// Same modified time for every file
+  Fixed(SystemTime),
+}
+
+/// Copy file, with permissions and modified time from options
+pub(crate) fn copy_file(from: &str, to: &str, options: &CopyOptions) -> UnreactResult<()> {
+  let io_error = |err| UnreactError::IoError(err, to.to_string());
+
+  // Permissions are always copied
+  if let Err(err) = fs::copy(from, to) {
+    return Err(UnreactError::IoError(err, from.to_string()));
+  }
+  let source = fs::metadata(from).map_err(|err| UnreactError::IoError(err, from.to_string()))?;
+
+  if options.permissions == PermissionMode::Normalize {
+    fs::set_permissions(to, normalize_permissions(source.permissions())).map_err(io_error)?;
+  }
+
+  let modified = match options.timestamps {
+    TimestampMode::Now => return Ok(()),
+    TimestampMode::Preserve => source.modified().map_err(io_error)?,
+    TimestampMode::Fixed(time) => time,
+  };
+  // Read-only files must be opened for writing to set time
+  let readonly = source.permissions().readonly() && options.permissions == PermissionMode::Preserve;
+  if readonly {
+    let mut permissions = source.permissions();
+    #[allow(clippy::permissions_set_readonly_false)]
+    permissions.set_readonly(false);
+    fs::set_permissions(to, permissions).map_err(io_error)?;
+  }
+  fs::OpenOptions::new()
+    .write(true)
+    .open(to)
+    .and_then(|file| file.set_modified(modified))
+    .map_err(io_error)?;
+  if readonly {
+    fs::set_permissions(to, source.permissions()).map_err(io_error)?;
+  }
+
+  Ok(())
+}
+
+/// Get normalized permissions, see `PermissionMode::Normalize`
+#[cfg(unix)]
+fn normalize_permissions(permissions: fs::Permissions) -> fs::Permissions {
+  use std::os::unix::fs::PermissionsExt;
+
+  let is_executable = permissions.mode() & 0o111 != 0;
+  fs::Permissions::from_mode(if is_executable { 0o755 } else { 0o644 })
+}
+
+/// Get normalized permissions, see `PermissionMode::Normalize`
+#[cfg(not(unix))]
+fn normalize_permissions(mut permissions: fs::Permissions) -> fs::Permissions {
+  #[allow(clippy::permissions_set_readonly_false)]
+  permissions.set_readonly(false);
+  permissions
+}
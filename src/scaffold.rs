@@ -0,0 +1,142 @@
+//! Starter sites, to create a working project in one function call
+//!
+//! Each starter is also a [cargo-generate](https://github.com/cargo-generate/cargo-generate) template, in the `scaffold` directory of the repository
+//!
+//! Requires the `scaffold` feature
+//!
+//! # Examples
+//!
+//! ```no_run
+//! fn main() -> unreact::UnreactResult<()> {
+//!   unreact::scaffold::blog("./my-blog")?;
+//!   Ok(())
+//! }
+//! ```
+//!
+//! Then run the new site in dev mode with `cargo run -- --dev`, in the `my-blog` directory
+
+use std::{fs, io, path::Path};
+
+use crate::{UnreactError, UnreactResult};
+
+/// Files of a starter site, as `(path, content)`
+///
+/// Paths ending in `.liquid` are templates for `cargo generate`, and are written without the extension
+type Starter = &'static [(&'static str, &'static str)];
+
+/// Include file of starter site, with path relative to its directory
+macro_rules! starter_file {
+  ($dir:literal, $path:literal) => {
+    (
+      $path,
+      include_str!(concat!("../scaffold/", $dir, "/", $path)),
+    )
+  };
+}
+
+/// Blog with a list of posts, and a page for each post
+const BLOG: Starter = &[
+  starter_file!("blog", "Cargo.toml.liquid"),
+  starter_file!("blog", ".gitignore"),
+  starter_file!("blog", "src/main.rs"),
+  starter_file!("blog", "templates/header.hbs"),
+  starter_file!("blog", "templates/footer.hbs"),
+  starter_file!("blog", "templates/index.hbs"),
+  starter_file!("blog", "templates/post.hbs"),
+  starter_file!("blog", "templates/404.hbs"),
+  starter_file!("blog", "styles/main.scss"),
+  starter_file!("blog", "public/robots.txt"),
+];
+
+/// Documentation site with a sidebar, and a layout shared by every page
+const DOCS: Starter = &[
+  starter_file!("docs", "Cargo.toml.liquid"),
+  starter_file!("docs", ".gitignore"),
+  starter_file!("docs", "src/main.rs"),
+  starter_file!("docs", "templates/layout.hbs"),
+  starter_file!("docs", "templates/index.hbs"),
+  starter_file!("docs", "templates/page.hbs"),
+  starter_file!("docs", "templates/404.hbs"),
+  starter_file!("docs", "styles/main.scss"),
+  starter_file!("docs", "public/robots.txt"),
+];
+
+/// Create a blog starter site in `dir`
+///
+/// Posts are written in Markdown in `src/main.rs`, and rendered with `templates/post.hbs`
+///
+/// Directory is created if it does not exist. Returns error if any file already exists, before writing anything
+pub fn blog(dir: impl AsRef<Path>) -> UnreactResult<()> {
+  write_starter(dir.as_ref(), BLOG)
+}
+
+/// Create a documentation starter site in `dir`
+///
+/// Pages are written in Markdown in `src/main.rs`, and rendered with `templates/page.hbs`
+///
+/// Directory is created if it does not exist. Returns error if any file already exists, before writing anything
+pub fn docs(dir: impl AsRef<Path>) -> UnreactResult<()> {
+  write_starter(dir.as_ref(), DOCS)
+}
+
+/// Write every file of starter to `dir`
+fn write_starter(dir: &Path, starter: Starter) -> UnreactResult<()> {
+  // Package name from directory name, as `cargo generate` would
+  let name = project_name(dir);
+
+  // Check before writing, so existing project is not partly overwritten
+  for (path, _) in starter {
+    let path = dir.join(path.trim_end_matches(".liquid"));
+    if path.exists() {
+      return Err(UnreactError::IoError(
+        io::Error::from(io::ErrorKind::AlreadyExists),
+        path.display().to_string(),
+      ));
+    }
+  }
+
+  for (path, content) in starter {
+    let (path, content) = match path.strip_suffix(".liquid") {
+      Some(path) => (path, content.replace("{{project-name}}", &name)),
+      None => (*path, content.to_string()),
+    };
+    let path = dir.join(path);
+
+    // Create folder recursively
+    if let Some(parent) = path.parent() {
+      if let Err(err) = fs::create_dir_all(parent) {
+        return Err(UnreactError::IoError(err, parent.display().to_string()));
+      }
+    }
+
+    // Create file
+    if let Err(err) = fs::write(&path, content) {
+      return Err(UnreactError::IoError(err, path.display().to_string()));
+    }
+  }
+
+  Ok(())
+}
+
+/// Get valid package name from last component of directory
+fn project_name(dir: &Path) -> String {
+  let name = dir
+    .canonicalize()
+    .ok()
+    .and_then(|dir| Some(dir.file_name()?.to_string_lossy().to_string()))
+    .or_else(|| Some(dir.file_name()?.to_string_lossy().to_string()))
+    .unwrap_or_default();
+
+  let name = name
+    .to_lowercase()
+    .chars()
+    .map(|ch| if ch.is_ascii_alphanumeric() { ch } else { '-' })
+    .collect::<String>();
+  let name = name.trim_matches('-');
+
+  if name.is_empty() {
+    "site".to_string()
+  } else {
+    name.to_string()
+  }
+}
@@ -0,0 +1,43 @@
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+
+use crate::date::DateTime;
+
+/// Create `publish-schedule.json` of content left out of build until its date, see `Config::publish_schedule`
+///
+/// `scheduled`: Page paths, with seconds since Unix epoch of date
+///
+/// Pages are sorted by date, with the earliest as `next`. Each date has a `cron` schedule in UTC (such as for GitHub Actions),
+/// which runs at the minute of the date, every year
+pub(crate) fn publish_schedule(scheduled: &BTreeMap<String, i64>) -> String {
+  let mut pages = scheduled
+    .iter()
+    .map(|(path, timestamp)| (*timestamp, path))
+    .collect::<Vec<_>>();
+  pages.sort();
+
+  let pages = pages
+    .into_iter()
+    .map(|(timestamp, path)| {
+      json!({
+        "path": path,
+        "date": DateTime::from_timestamp(timestamp).rfc3339(),
+        "cron": cron(timestamp),
+      })
+    })
+    .collect::<Vec<_>>();
+  let next = pages
+    .first()
+    .map_or(Value::Null, |page| page["date"].clone());
+
+  let schedule = json!({ "next": next, "pages": pages });
+  serde_json::to_string_pretty(&schedule).unwrap_or_default()
+}
+
+/// Cron schedule at minute of timestamp in UTC, such as `30 9 31 1 *`
+///
+/// Rounded up to next minute, so date has passed when schedule runs
+fn cron(timestamp: i64) -> String {
+  let minute = (timestamp + 59).div_euclid(60) * 60;
+  DateTime::from_timestamp(minute).cron()
+}
@@ -0,0 +1,190 @@
+use std::{
+  collections::BTreeSet,
+  io,
+  path::{Component, Path},
+};
+
+use crate::{list_files, Config, FileMap, UnreactError, UnreactResult, Vfs};
+
+/// Read styles, plain css files, and partials from directory into virtual file system
+///
+/// `.scss` files starting with `_` are partials. Files with other extensions are ignored
+///
+/// Returns error if a `.scss` and a `.css` file have the same name
+pub(crate) fn load_styles(vfs: &mut Vfs, dir: &str) -> UnreactResult<()> {
+  for file in list_files(dir)? {
+    let Some((name, extension)) = file.rsplit_once('.') else {
+      continue;
+    };
+    if !matches!(extension, "scss" | "css") {
+      continue;
+    }
+
+    let path = format!("./{dir}/{file}");
+    let content = match std::fs::read_to_string(&path) {
+      Ok(x) => x,
+      Err(err) => return Err(UnreactError::IoError(err, path)),
+    };
+
+    if is_partial(name) {
+      vfs.style_partials.insert(name.to_string(), content);
+      continue;
+    }
+    if vfs.styles.insert(name.to_string(), content).is_some() {
+      return Err(UnreactError::DuplicateOutput(
+        format!("{name}.css"),
+        format!("styles '{name}.scss' and '{name}.css'"),
+      ));
+    }
+    if extension == "css" {
+      vfs.plain_styles.insert(name.to_string());
+    }
+  }
+  Ok(())
+}
+
+/// If style is a partial, such as `_mixins` or `base/_colors`
+fn is_partial(name: &str) -> bool {
+  name.rsplit('/').next().unwrap_or(name).starts_with('_')
+}
+
+/// Styles and partials of site, which `grass` reads instead of files, for `@use` and `@import`
+///
+/// Imports are found relative to the importing style, then relative to styles directory
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct StyleFiles<'a> {
+  /// Directory of styles, from config
+  pub dir: &'a str,
+  /// Styles, **without** partials
+  pub styles: &'a FileMap,
+  /// Names of styles which are plain css
+  pub plain: &'a BTreeSet<String>,
+  /// Partials, **with** `_` in name
+  pub partials: &'a FileMap,
+}
+
+impl<'a> StyleFiles<'a> {
+  /// Get directory of styles, without leading `./` or trailing slash
+  fn root(&self) -> &'a str {
+    self.dir.trim_start_matches("./").trim_matches('/')
+  }
+
+  /// Get path of file, relative to styles directory, from path given by `grass`
+  ///
+  /// Returns `None` if path is outside styles directory
+  fn relative(&self, path: &Path) -> Option<Vec<String>> {
+    let mut parts = Vec::new();
+    for component in path.components() {
+      match component {
+        Component::Normal(part) => parts.push(part.to_str()?.to_string()),
+        Component::ParentDir => {
+          parts.pop()?;
+        }
+        Component::CurDir => (),
+        _ => return None,
+      }
+    }
+
+    let root = self.root().split('/').filter(|part| !part.is_empty());
+    for part in root {
+      if parts.first().map(String::as_str) != Some(part) {
+        return None;
+      }
+      parts.remove(0);
+    }
+    Some(parts)
+  }
+
+  /// Get content of style or partial, from path relative to styles directory, **with** extension
+  fn file(&self, path: &str) -> Option<&'a String> {
+    let (name, extension) = path.rsplit_once('.')?;
+    match extension {
+      "css" if self.plain.contains(name) => self.styles.get(name),
+      "scss" if is_partial(name) => self.partials.get(name),
+      "scss" if !self.plain.contains(name) => self.styles.get(name),
+      _ => None,
+    }
+  }
+
+  /// Find content of file from path given by `grass`
+  ///
+  /// If not found relative to importing style, subdirectories are removed from start of path,
+  /// so `pages/base/_colors.scss` can be `base/_colors.scss` in styles directory
+  fn find(&self, path: &Path) -> Option<&'a String> {
+    let parts = self.relative(path)?;
+    (0..parts.len()).find_map(|start| self.file(&parts[start..].join("/")))
+  }
+}
+
+impl grass::Fs for StyleFiles<'_> {
+  fn is_dir(&self, path: &Path) -> bool {
+    let Some(parts) = self.relative(path) else {
+      return false;
+    };
+    let prefix = format!("{}/", parts.join("/"));
+    parts.is_empty()
+      || (self.styles.keys())
+        .chain(self.partials.keys())
+        .any(|name| name.starts_with(&prefix))
+  }
+
+  fn is_file(&self, path: &Path) -> bool {
+    self.find(path).is_some()
+  }
+
+  fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+    match self.find(path) {
+      Some(content) => Ok(content.as_bytes().to_vec()),
+      None => Err(io::Error::new(
+        io::ErrorKind::NotFound,
+        format!("No style or partial at '{}'", path.display()),
+      )),
+    }
+  }
+}
+
+/// Convert style from scss to css, and minify if enabled in config
+///
+/// Plain css styles are only minified
+pub(crate) fn compile_scss(
+  config: &Config,
+  files: StyleFiles,
+  name: &str,
+) -> UnreactResult<String> {
+  let parsed = if files.plain.contains(name) {
+    match files.styles.get(name) {
+      Some(content) => content.to_string(),
+      None => return Err(UnreactError::StyleNotExist(name.to_string())),
+    }
+  } else {
+    // Convert from scss to css with `grass`, with styles directory as load path
+    let root = files.root();
+    let options = grass::Options::default()
+      .fs(&files)
+      .load_path(Path::new(root));
+    let path = if root.is_empty() {
+      format!("{name}.scss")
+    } else {
+      format!("{root}/{name}.scss")
+    };
+    match grass::from_path(&path, &options) {
+      Ok(x) => x,
+      Err(err) => return Err(UnreactError::ScssConvertFail(name.to_string(), err)),
+    }
+  };
+
+  // Un-minified file
+  if !config.minify {
+    return Ok(parsed);
+  }
+
+  // Minified css
+  use css_minify::optimizations::{Level, Minifier};
+  match Minifier::default().minify(&parsed, Level::Two) {
+    Ok(x) => Ok(x),
+    Err(err) => Err(UnreactError::MinifyCssFail(
+      name.to_string(),
+      err.to_string(),
+    )),
+  }
+}
@@ -1,124 +1,791 @@
+#[cfg(feature = "dev-server")]
 use http::{Method, StatusCode};
+#[cfg(feature = "dev-server")]
 use hyper::service::{make_service_fn, service_fn};
+#[cfg(feature = "dev-server")]
 use hyper::{Body, Request, Response, Server};
-use std::{convert::Infallible, fs, path::Path};
+#[cfg(feature = "dev-server")]
+use serde::Deserialize;
+#[cfg(feature = "dev-server")]
+use serde_json::Value;
+#[cfg(feature = "dev-server")]
+use std::sync::mpsc;
+#[cfg(feature = "dev-server")]
+use std::{
+  convert::Infallible, fs, net::SocketAddr, path::Path, thread::JoinHandle, time::Instant,
+};
+#[cfg(feature = "dev-server")]
+use tokio::{
+  runtime::Handle,
+  sync::{broadcast, oneshot, watch},
+};
 
-use crate::DEV_BUILD_DIR;
+#[cfg(feature = "dev-server")]
+use crate::{links::decode_path, stats_page::STATS_DIR, Host, UnreactError, UnreactResult};
 
 //TODO Add error handling ?
 
-/// Local address with port to host dev server
+/// Default local address with port to host dev server, see `Config::dev_port`
 pub const ADDRESS: &str = "127.0.0.1:8080";
 
+/// Path of Server-Sent Events stream, which sends a message after every rebuild
+///
+/// Message is `reload` if every document should reload, or a JSON array of url paths of rebuilt pages, such as `["/blog/post", "/index"]`
+pub const RELOAD_PATH: &str = "/__unreact/reload";
+
+/// Path to render a template with data, with a `POST` request of JSON, such as `{"template": "card", "data": {"title": "Hello"}}`
+///
+/// Responds with rendered html, or with the error of render as text
+pub const RENDER_PATH: &str = "/__unreact/render";
+
 /// Partial for hot reloading document in development
+///
+/// Document is reloaded when a rebuild changes it, or when the dev server is restarted, using events from `RELOAD_PATH`.
+/// Every document is reloaded when styles or public files change
+///
+/// Scroll position is saved before reload, and restored on load of the same page
 pub const DEV_SCRIPT: &str = r#"
   <script>
     console.warn("This document is in *development mode*");
+
+    (function () {
+      if (!window.EventSource) return;
+      const events = new EventSource("/__unreact/reload");
+      let disconnected = false;
+      events.addEventListener("message", function (event) {
+        // Only listed pages were rebuilt
+        if (event.data !== "reload") {
+          const here = decodeURIComponent(location.pathname)
+            .replace(/\.html$/, "")
+            .replace(/\/$/, "/index");
+          const pages = JSON.parse(event.data);
+          if (!pages.includes(here) && !pages.includes(here + "/index")) return;
+        }
+        location.reload();
+      });
+      events.addEventListener("error", function () {
+        disconnected = true;
+      });
+      // Server was restarted
+      events.addEventListener("open", function () {
+        if (disconnected) location.reload();
+      });
+    })();
+
+    (function () {
+      const key = "unreact-scroll:" + location.pathname;
+      addEventListener("beforeunload", function () {
+        sessionStorage.setItem(key, JSON.stringify([scrollX, scrollY]));
+      });
+      addEventListener("load", function () {
+        const saved = sessionStorage.getItem(key);
+        if (saved) {
+          sessionStorage.removeItem(key);
+          scrollTo.apply(window, JSON.parse(saved));
+        }
+      });
+    })();
   </script>
 "#;
 
-/// Create server and listen on local port
+/// How dev server responds to paths of directories, such as `/blog` for `blog/index.html`, see `Config::dev_directories`
+///
+/// Default: `DirectoryMode::Redirect`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DirectoryMode {
+  /// Redirect `/blog` to `/blog/`, like GitHub Pages
+  ///
+  /// Pages are only served without trailing slash, so `/about/` is not found for `about.html`
+  #[default]
+  Redirect,
+  /// Serve `blog/index.html` at `/blog` and `/blog/`, and `about.html` at `/about` and `/about/`, without redirects
+  Serve,
+  /// Same as `Redirect`, and list files of directories without `index.html`
+  List,
+}
+
+/// Signal to reload documents open in browser, sent after a rebuild
+///
+/// Clones send to the same browsers
+#[cfg(feature = "dev-server")]
+#[derive(Debug, Clone)]
+pub(crate) struct ReloadSignal(broadcast::Sender<Option<Vec<String>>>);
+
+#[cfg(feature = "dev-server")]
+impl ReloadSignal {
+  /// Create signal without any browsers listening
+  pub fn new() -> Self {
+    ReloadSignal(broadcast::channel(16).0)
+  }
+
+  /// Reload all documents currently listening
+  pub fn send(&self) {
+    // Error only means that no browsers are listening
+    let _ = self.0.send(None);
+  }
+
+  /// Reload documents of pages, by url path, such as `/blog/post` or `/index`
+  pub fn send_pages(&self, pages: Vec<String>) {
+    if !pages.is_empty() {
+      let _ = self.0.send(Some(pages));
+    }
+  }
+}
+
+/// Event sent to app while dev server is running
+#[cfg(feature = "dev-server")]
+pub(crate) enum DevEvent {
+  /// Files changed in watched directories
+  Changed(Vec<std::path::PathBuf>),
+  /// Template to render with data, from `RENDER_PATH`
+  Render(RenderRequest),
+}
+
+/// Template to render with data, from `RENDER_PATH`
+#[cfg(feature = "dev-server")]
+pub(crate) struct RenderRequest {
+  /// Name of template
+  pub template: String,
+  /// Data to render template with
+  pub data: Value,
+  /// Sends rendered html, or error of render
+  pub reply: oneshot::Sender<Result<String, String>>,
+}
+
+/// Body of request to `RENDER_PATH`
+#[cfg(feature = "dev-server")]
+#[derive(Deserialize)]
+struct RenderBody {
+  template: String,
+  #[serde(default)]
+  data: Value,
+}
+
+/// Values shared by every request to dev server
+#[cfg(feature = "dev-server")]
+#[derive(Clone)]
+pub(crate) struct DevServer {
+  /// Production host, for response headers
+  pub host: Host,
+  /// Signal to reload browsers
+  pub reload: ReloadSignal,
+  /// Base path of site, such as `/repo`, or empty
+  pub base_path: String,
+  /// Sends templates to render to app
+  pub events: mpsc::Sender<DevEvent>,
+  /// Responses to paths of directories
+  pub directories: DirectoryMode,
+  /// If a line is printed for every request, with status and time
+  pub log: bool,
+  /// Dev build directory, relative to workspace, see `Config::dev_build`
+  pub build: String,
+}
+
+/// Handle of dev server running in background, see `Unreact::listen_with`
+///
+/// Server is stopped when handle is dropped
+#[cfg(feature = "dev-server")]
+#[derive(Debug)]
+pub struct ServerHandle {
+  /// Local address which server is bound to
+  addr: SocketAddr,
+  /// Base path of site, such as `/repo`, or empty
+  base_path: String,
+  /// Sends `true` to stop server
+  stop: watch::Sender<bool>,
+  /// Thread with runtime of server, if not spawned on runtime of caller
+  thread: Option<JoinHandle<()>>,
+}
+
+#[cfg(feature = "dev-server")]
+impl ServerHandle {
+  /// Local address which server is bound to
+  ///
+  /// Port is chosen by OS if address has port `0`
+  pub fn addr(&self) -> SocketAddr {
+    self.addr
+  }
+
+  /// Url of site on dev server, including base path, such as `http://127.0.0.1:8080/repo/`
+  pub fn url(&self) -> String {
+    format!("http://{}{}/", self.addr, self.base_path)
+  }
+
+  /// Stop server, after open requests are answered
+  ///
+  /// Waits for server to stop, unless it was spawned on runtime of caller
+  pub fn shutdown(mut self) {
+    self.stop();
+  }
+
+  /// Send signal to stop server, and wait for thread of server, if any
+  fn stop(&mut self) {
+    // Error only means that server has already stopped
+    let _ = self.stop.send(true);
+    if let Some(thread) = self.thread.take() {
+      let _ = thread.join();
+    }
+  }
+}
+
+#[cfg(feature = "dev-server")]
+impl Drop for ServerHandle {
+  fn drop(&mut self) {
+    self.stop();
+  }
+}
+
+/// Create server and listen on `addr` in background
 ///
 /// Almost mimics GitHub Pages
 ///
+/// Sends response headers of `host`, see `Host::headers`
+///
+/// Site is served under `base_path` (such as `/repo`), like production. Requests to root redirect to base path
+///
+/// Missing files respond with `404.html` (if built) and status `404`. Paths of directories are handled with `directories`, see `resolve`
+///
+/// Redirects and rewrites with rules of `host`, such as a Netlify `_redirects` file, see `Host::redirect`
+///
+/// Browsers listening on `RELOAD_PATH` are sent a message every time `reload` is signalled
+///
 /// Reads file on every GET request, however this should not be a problem for a dev server
-pub fn listen() {
-  // Start `tokio` runtime (without macro)
-  tokio::runtime::Builder::new_multi_thread()
-    .enable_all()
-    .build()
-    .expect("Failed building the Runtime")
-    .block_on(async {
-      // Create service for router
-      let make_svc = make_service_fn(|_| async {
-        return Ok::<_, Infallible>(service_fn(router));
-      });
+///
+/// Server is spawned on `runtime` if given, otherwise on a new runtime in its own thread
+#[cfg(feature = "dev-server")]
+pub(crate) fn listen_with(
+  server: DevServer,
+  addr: &str,
+  runtime: Option<Handle>,
+) -> UnreactResult<ServerHandle> {
+  let fail = |reason: String| UnreactError::ServerFail(addr.to_string(), reason);
+  let socket: SocketAddr = addr
+    .parse()
+    .map_err(|err| fail(format!("Invalid address: {err}")))?;
 
-      // Create server
-      let addr = ADDRESS.parse().expect("Invalid IP address");
-      let server = Server::bind(&addr).serve(make_svc);
+  // Runtime of server, if not spawned on runtime of caller
+  let own_runtime = match runtime {
+    Some(_) => None,
+    None => Some(
+      tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .map_err(|err| fail(format!("Failed to build runtime: {err}")))?,
+    ),
+  };
+  let handle = match (&runtime, &own_runtime) {
+    (Some(runtime), _) => runtime.clone(),
+    (_, Some(own_runtime)) => own_runtime.handle().clone(),
+    _ => unreachable!(),
+  };
 
-      // Start server
-      println!("Listening on http://{}", addr);
-      server.await?;
+  // Bind in context of runtime, so errors are returned before spawning
+  let builder = {
+    let _guard = handle.enter();
+    Server::try_bind(&socket).map_err(|err| fail(err.to_string()))?
+  };
 
-      Ok::<_, hyper::Error>(())
-    })
-    .expect("Error in Runtime");
+  let (stop, stopped) = watch::channel(false);
+  let base_path = server.base_path.clone();
+
+  // Create service for router
+  let service_stopped = stopped.clone();
+  let make_svc = make_service_fn(move |_| {
+    let server = server.clone();
+    let stopped = service_stopped.clone();
+    async move {
+      Ok::<_, Infallible>(service_fn(move |req| {
+        router(req, server.clone(), stopped.clone())
+      }))
+    }
+  });
+  let running = builder.serve(make_svc);
+  let addr = running.local_addr();
+  let running = running.with_graceful_shutdown(wait_for_stop(stopped));
+
+  println!("Listening on http://{addr}{base_path}/");
+  println!("Build stats at http://{addr}{base_path}/{STATS_DIR}/");
+
+  let run = async move {
+    if let Err(err) = running.await {
+      eprintln!("Dev server error: {err}");
+    }
+  };
+  let thread = match own_runtime {
+    Some(own_runtime) => Some(std::thread::spawn(move || own_runtime.block_on(run))),
+    None => {
+      handle.spawn(run);
+      None
+    }
+  };
+
+  Ok(ServerHandle {
+    addr,
+    base_path,
+    stop,
+    thread,
+  })
+}
+
+/// Wait until `true` is sent to stop server, or handle is dropped
+#[cfg(feature = "dev-server")]
+async fn wait_for_stop(mut stopped: watch::Receiver<bool>) {
+  while !*stopped.borrow() {
+    if stopped.changed().await.is_err() {
+      return;
+    }
+  }
+}
+
+/// Route request, and print a line with status and time of response, if enabled
+#[cfg(feature = "dev-server")]
+async fn router(
+  req: Request<Body>,
+  server: DevServer,
+  stopped: watch::Receiver<bool>,
+) -> Result<Response<Body>, Infallible> {
+  let start = Instant::now();
+  let method = req.method().clone();
+  let path = req.uri().path().to_string();
+  // Every page opens a reload stream
+  let log = server.log && path != RELOAD_PATH;
+
+  let response = route(req, server, stopped).await;
+  if log {
+    println!(
+      "{method} {path} {} {:.1?}",
+      response.status().as_u16(),
+      start.elapsed()
+    );
+  }
+  Ok(response)
 }
 
 /// Route path to read and return file
-async fn router(req: Request<Body>) -> Result<Response<Body>, Infallible> {
-  // Check if is GET request
-  if req.method() == Method::GET {
-    // Return corresponding file as body if exists
-    if let Some(file) = get_best_possible_file(req.uri().path()) {
-      return Ok(Response::new(file));
+///
+/// `stopped`: Changes to `true` when server is stopped, to end reload streams
+#[cfg(feature = "dev-server")]
+async fn route(
+  req: Request<Body>,
+  server: DevServer,
+  stopped: watch::Receiver<bool>,
+) -> Response<Body> {
+  let DevServer {
+    host,
+    reload,
+    base_path,
+    events,
+    directories,
+    build,
+    ..
+  } = server;
+  let path = req.uri().path().to_string();
+
+  // Stream of reload events
+  if path == RELOAD_PATH {
+    return reload_events(reload, stopped);
+  }
+  // Template rendered by app
+  if path == RENDER_PATH {
+    return render_template(req, events).await;
+  }
+
+  // Path relative to base path of site
+  // `None` if outside of site
+  let site_path = match path.strip_prefix(&base_path) {
+    Some(rest) if rest.is_empty() || rest.starts_with('/') => Some(rest.to_string()),
+    // Root of domain, when site has a base path
+    _ if path == "/" => {
+      return Response::builder()
+        .status(StatusCode::TEMPORARY_REDIRECT)
+        .header("Location", format!("{base_path}/"))
+        .body(Body::empty())
+        .unwrap();
     }
+    _ => None,
+  };
+
+  // Static hosts only serve files to `GET` and `HEAD`
+  let is_head = req.method() == Method::HEAD;
+  if req.method() != Method::GET && !is_head {
+    return Response::builder()
+      .status(StatusCode::METHOD_NOT_ALLOWED)
+      .header("Allow", "GET, HEAD")
+      .header("Content-Type", "text/plain; charset=utf-8")
+      .body(Body::from("405 - Method not allowed"))
+      .unwrap();
   }
 
-  // 404 page
-  Ok(
+  let query = req
+    .uri()
+    .query()
+    .map(|x| format!("?{x}"))
+    .unwrap_or_default();
+  let mut resolved = match &site_path {
+    Some(site_path) => resolve(&build, site_path, directories),
+    None => Resolved::NotFound,
+  };
+  let mut status = StatusCode::OK;
+
+  // Redirects and rewrites of production host, if no file exists at path, unless forced
+  let rule = (site_path.as_deref())
+    .and_then(|site_path| host.redirect(site_path, &build))
+    .filter(|rule| rule.force || !matches!(resolved, Resolved::File(_)));
+  if let Some(rule) = rule {
+    let to = &rule.to;
+    let is_url = !to.starts_with('/');
+    if rule.is_redirect() {
+      let location = match (is_url, to.contains('?')) {
+        (true, _) => to.clone(),
+        (false, true) => format!("{base_path}{to}"),
+        (false, false) => format!("{base_path}{to}{query}"),
+      };
+      return Response::builder()
+        .status(StatusCode::from_u16(rule.status).unwrap_or(StatusCode::MOVED_PERMANENTLY))
+        .header("Location", location)
+        .header("Cache-Control", "no-store")
+        .body(Body::empty())
+        .unwrap();
+    }
+    if is_url {
+      return Response::builder()
+        .status(StatusCode::NOT_IMPLEMENTED)
+        .header("Content-Type", "text/plain; charset=utf-8")
+        .body(Body::from(format!(
+          "501 - Dev server cannot proxy to '{to}'"
+        )))
+        .unwrap();
+    }
+    // Serve file at path of rule, with status of rule
+    let target = to.split_once('?').map_or(to.as_str(), |(target, _)| target);
+    resolved = resolve(&build, target, directories);
+    status = StatusCode::from_u16(rule.status).unwrap_or(StatusCode::OK);
+  }
+
+  let (status, file) = match resolved {
+    Resolved::File(file) => (status, Some(file)),
+    Resolved::Redirect => {
+      return Response::builder()
+        .status(StatusCode::MOVED_PERMANENTLY)
+        .header("Location", format!("{path}/{query}"))
+        // Browsers keep permanent redirects, even after directory becomes a page
+        .header("Cache-Control", "no-store")
+        .body(Body::empty())
+        .unwrap();
+    }
+    Resolved::Listing(dir) => {
+      return Response::builder()
+        .header("Content-Type", "text/html; charset=utf-8")
+        .body(Body::from(listing(&build, &dir)))
+        .unwrap();
+    }
+    // Custom 404 page, if defined
+    Resolved::NotFound => (
+      StatusCode::NOT_FOUND,
+      Some("404.html".to_string()).filter(|file| is_build_file(&build, file)),
+    ),
+  };
+
+  let (body, mime) = match file {
+    Some(file) => match fs::read(format!("./{build}/{file}")) {
+      Ok(content) => (content, content_type(&file)),
+      Err(err) => {
+        return Response::builder()
+          .status(StatusCode::INTERNAL_SERVER_ERROR)
+          .body(Body::from(format!("Could not read file '{file}': {err}")))
+          .unwrap()
+      }
+    },
+    // Fallback 404 response
+    None => (
+      b"404 - File not found. Custom 404 page not found.".to_vec(),
+      "text/plain; charset=utf-8",
+    ),
+  };
+
+  // Add headers of production host
+  let headers = host.headers(site_path.as_deref().unwrap_or(&path), &build);
+  let mut response = Response::builder().status(status);
+  // Host can override type of file
+  if !headers
+    .iter()
+    .any(|(name, _)| name.eq_ignore_ascii_case("Content-Type"))
+  {
+    response = response.header("Content-Type", mime);
+  }
+  for (name, value) in headers {
+    response = response.header(name, value);
+  }
+  // Same headers without body
+  let body = if is_head {
+    response = response.header("Content-Length", body.len());
+    Body::empty()
+  } else {
+    Body::from(body)
+  };
+
+  response.body(body).unwrap_or_else(|err| {
+    // Invalid header from host
     Response::builder()
-      .status(StatusCode::NOT_FOUND)
-      .body(Body::from(
-        // If custom 404 page is defined
-        if let Some(file) = get_best_possible_file("404") {
-          // Custom 404 page using request `/404`
-          return Ok(
-            Response::builder()
-              .status(StatusCode::NOT_FOUND)
-              .body(Body::from(file))
-              .unwrap(),
-          );
-        } else {
-          // Fallback 404 response
-          "404 - File not found. Custom 404 page not found.".to_string()
-        },
-      ))
-      .unwrap(),
-  )
+      .status(StatusCode::INTERNAL_SERVER_ERROR)
+      .body(Body::from(format!("Invalid response header: {err}")))
+      .unwrap()
+  })
 }
 
-/// Loops through files in `possible_files_from_path` to find best file match
-///
-/// Returns `None` if no file was founds
+/// Create Server-Sent Events response, which sends a message every time `reload` is signalled
 ///
-/// Returns as `Option<Body>`, to allow non-UTF-8 file formats (such as images)
+/// Stream ends when browser disconnects, or when server is stopped
+#[cfg(feature = "dev-server")]
+fn reload_events(reload: ReloadSignal, stopped: watch::Receiver<bool>) -> Response<Body> {
+  let (mut sender, body) = Body::channel();
+  let mut receiver = reload.0.subscribe();
+
+  tokio::spawn(async move {
+    // Comment line opens stream in browser, before first reload
+    if sender.send_data(": connected\n\n".into()).await.is_err() {
+      return;
+    }
+    loop {
+      let message = tokio::select! {
+        message = receiver.recv() => message,
+        () = wait_for_stop(stopped.clone()) => return,
+      };
+      let data = match message {
+        Ok(Some(pages)) => Value::from(pages).to_string(),
+        // Missed messages could list any page
+        Ok(None) | Err(broadcast::error::RecvError::Lagged(_)) => "reload".to_string(),
+        Err(broadcast::error::RecvError::Closed) => return,
+      };
+      if sender
+        .send_data(format!("data: {data}\n\n").into())
+        .await
+        .is_err()
+      {
+        return;
+      }
+    }
+  });
+
+  Response::builder()
+    .header("Content-Type", "text/event-stream")
+    .header("Cache-Control", "no-cache")
+    .body(body)
+    .unwrap()
+}
+
+/// Send template and data from body of request to app, and respond with rendered html
 ///
-/// Panics if file exists, but was unable to be read
-fn get_best_possible_file(path: &str) -> Option<Body> {
-  // Convert request to possible filepaths
-  let possible_files = possible_files_from_path(path);
-  for file in &possible_files {
-    let file = &format!("./{DEV_BUILD_DIR}/{file}");
-    // If file exists, and not directory
-    if Path::new(file).is_file() {
-      // Returns file content as `Body`
-      // Automatically parses to string, if is valid UTF-8, otherwise uses buffer
-      return Some(Body::from(
-        fs::read(file).expect(&format!("Could not read file '{file}'")),
-      ));
+/// Responds with `400 Bad Request` if body is not valid, and `422 Unprocessable Entity` with the error if template cannot be rendered
+#[cfg(feature = "dev-server")]
+async fn render_template(req: Request<Body>, events: mpsc::Sender<DevEvent>) -> Response<Body> {
+  let text = |status: StatusCode, message: String| {
+    Response::builder()
+      .status(status)
+      .header("Content-Type", "text/plain; charset=utf-8")
+      .body(Body::from(message))
+      .unwrap()
+  };
+
+  if req.method() != Method::POST {
+    let mut response = text(
+      StatusCode::METHOD_NOT_ALLOWED,
+      format!("Use a POST request to render, to {RENDER_PATH}"),
+    );
+    (response.headers_mut()).insert("Allow", http::HeaderValue::from_static("POST"));
+    return response;
+  }
+
+  let body = match hyper::body::to_bytes(req.into_body()).await {
+    Ok(x) => x,
+    Err(err) => {
+      return text(
+        StatusCode::BAD_REQUEST,
+        format!("Failed to read body: {err}"),
+      )
     }
+  };
+  let RenderBody { template, data } = match serde_json::from_slice(&body) {
+    Ok(x) => x,
+    Err(err) => {
+      return text(
+        StatusCode::BAD_REQUEST,
+        format!("Body must be JSON with `template` and `data`: {err}"),
+      )
+    }
+  };
+
+  let (reply, result) = oneshot::channel();
+  let request = RenderRequest {
+    template,
+    data,
+    reply,
+  };
+  // App is not running, such as after a panic
+  if events.send(DevEvent::Render(request)).is_err() {
+    return text(
+      StatusCode::SERVICE_UNAVAILABLE,
+      "App is not running".to_string(),
+    );
   }
-  None
+  match result.await {
+    Ok(Ok(html)) => Response::builder()
+      .header("Content-Type", "text/html; charset=utf-8")
+      .body(Body::from(html))
+      .unwrap(),
+    Ok(Err(err)) => text(StatusCode::UNPROCESSABLE_ENTITY, err),
+    Err(_) => text(
+      StatusCode::SERVICE_UNAVAILABLE,
+      "App is not running".to_string(),
+    ),
+  }
+}
+
+/// Response to path of request, relative to base path of site
+#[cfg(feature = "dev-server")]
+enum Resolved {
+  /// File to serve, relative to build directory
+  File(String),
+  /// Redirect to same path with trailing slash, for a directory
+  Redirect,
+  /// Directory without `index.html` to list, relative to build directory
+  Listing(String),
+  /// No file for path
+  NotFound,
 }
 
-/// Converts path from request into possible files to correspond to
+/// Find file in build directory `build` for path of request, like GitHub Pages
 ///
-/// If path ends with `.html`, or starts with `/styles` or `/public`, returns path, unchanged
+/// `/about` is `about`, or `about.html`. `/blog/` is `blog/index.html`, and `/blog` redirects to `/blog/`, with `DirectoryMode::Redirect`
+#[cfg(feature = "dev-server")]
+fn resolve(build: &str, path: &str, directories: DirectoryMode) -> Resolved {
+  let path = decode_path(path);
+  // Not outside of build directory
+  if path.split(['/', '\\']).any(|part| part == "..") {
+    return Resolved::NotFound;
+  }
+  let path = path.trim_start_matches('/');
+  let is_dir = |dir: &str| Path::new(&format!("./{build}/{dir}")).is_dir();
+  let is_build_file = |file: &str| is_build_file(build, file);
+
+  // Directory
+  if path.is_empty() || path.ends_with('/') {
+    let index = format!("{path}index.html");
+    let page = format!("{}.html", path.trim_end_matches('/'));
+    return match directories {
+      _ if is_build_file(&index) => Resolved::File(index),
+      DirectoryMode::List if is_dir(path) => Resolved::Listing(path.to_string()),
+      DirectoryMode::Serve if !path.is_empty() && is_build_file(&page) => Resolved::File(page),
+      _ => Resolved::NotFound,
+    };
+  }
+
+  let page = format!("{path}.html");
+  let index = format!("{path}/index.html");
+  if is_build_file(path) {
+    Resolved::File(path.to_string())
+  } else if is_build_file(&page) {
+    Resolved::File(page)
+  } else if !is_dir(path) {
+    Resolved::NotFound
+  } else {
+    match directories {
+      DirectoryMode::Serve if is_build_file(&index) => Resolved::File(index),
+      DirectoryMode::Serve => Resolved::NotFound,
+      DirectoryMode::Redirect if !is_build_file(&index) => Resolved::NotFound,
+      _ => Resolved::Redirect,
+    }
+  }
+}
+
+/// If file exists in build directory, and is not a directory
+#[cfg(feature = "dev-server")]
+fn is_build_file(build: &str, file: &str) -> bool {
+  Path::new(&format!("./{build}/{file}")).is_file()
+}
+
+/// Create page which lists files of directory in build directory, with `DirectoryMode::List`
 ///
-/// Else returns path + `.html`, and path + `/index.html`
+/// `dir`: Directory relative to build directory, with trailing slash, or empty
+#[cfg(feature = "dev-server")]
+fn listing(build: &str, dir: &str) -> String {
+  let mut entries = fs::read_dir(format!("./{build}/{dir}"))
+    .map(|entries| {
+      entries
+        .flatten()
+        .map(|entry| {
+          let name = entry.file_name().to_string_lossy().to_string();
+          match entry.path().is_dir() {
+            true => format!("{name}/"),
+            false => name,
+          }
+        })
+        .collect::<Vec<_>>()
+    })
+    .unwrap_or_default();
+  entries.sort();
+
+  let mut items = String::new();
+  if !dir.is_empty() {
+    items += r#"<li><a href="../">../</a></li>"#;
+  }
+  for name in entries {
+    let name = handlebars::html_escape(&name);
+    items += &format!(r#"<li><a href="{name}">{name}</a></li>"#);
+  }
+  format!(
+    r#"<!DOCTYPE html><html><head><meta charset="utf-8" /><title>Index of /{dir}</title></head><body><h1>Index of /{dir}</h1><ul>{items}</ul></body></html>"#,
+    dir = handlebars::html_escape(dir),
+  )
+}
+
+/// Get MIME type of file from extension, for `Content-Type` header
 ///
-/// All file paths returned are relative to workspace directory, and include dev build path
-fn possible_files_from_path(path: &str) -> Vec<String> {
-  if path.ends_with(".html") || path.starts_with("/styles") || path.starts_with("/public") {
-    vec![path.to_string()]
-  } else {
-    vec![
-      path.to_string(),
-      path.to_string() + ".html",
-      path.to_string() + "/index.html",
-    ]
+/// Unknown extensions are sent as `application/octet-stream`, as most static hosts do
+#[cfg(feature = "dev-server")]
+fn content_type(file: &str) -> &'static str {
+  let extension = Path::new(file)
+    .extension()
+    .and_then(|x| x.to_str())
+    .unwrap_or_default()
+    .to_lowercase();
+
+  match extension.as_str() {
+    // Text
+    "html" | "htm" => "text/html; charset=utf-8",
+    "css" => "text/css; charset=utf-8",
+    "js" | "mjs" => "text/javascript; charset=utf-8",
+    "json" | "map" => "application/json",
+    "jsonld" => "application/ld+json",
+    "xml" => "application/xml",
+    "rss" => "application/rss+xml",
+    "atom" => "application/atom+xml",
+    "txt" => "text/plain; charset=utf-8",
+    "md" => "text/markdown; charset=utf-8",
+    "csv" => "text/csv; charset=utf-8",
+    "webmanifest" => "application/manifest+json",
+    "wasm" => "application/wasm",
+    "pdf" => "application/pdf",
+    // Images
+    "png" => "image/png",
+    "jpg" | "jpeg" => "image/jpeg",
+    "gif" => "image/gif",
+    "webp" => "image/webp",
+    "avif" => "image/avif",
+    "svg" => "image/svg+xml",
+    "ico" => "image/x-icon",
+    "bmp" => "image/bmp",
+    // Fonts
+    "woff" => "font/woff",
+    "woff2" => "font/woff2",
+    "ttf" => "font/ttf",
+    "otf" => "font/otf",
+    // Media
+    "mp3" => "audio/mpeg",
+    "ogg" => "audio/ogg",
+    "wav" => "audio/wav",
+    "mp4" => "video/mp4",
+    "webm" => "video/webm",
+    _ => "application/octet-stream",
   }
 }
@@ -0,0 +1,56 @@
+use std::fmt;
+
+use crate::UnreactResult;
+
+/// Storage which output files of build are written to, instead of build directory, see `Unreact::set_sink`
+///
+/// Such as a zip archive, a cloud storage bucket, or memory of a custom server
+///
+/// Files are written in parallel if `threads` is set in config, so use a `Mutex` for shared state
+///
+/// # Examples
+///
+/// ```
+/// use unreact::prelude::*;
+/// use std::sync::{Arc, Mutex};
+///
+/// // Saves files in memory
+/// #[derive(Debug, Default)]
+/// struct MemorySink(Arc<Mutex<Vec<(String, Vec<u8>)>>>);
+///
+/// impl BuildSink for MemorySink {
+///   fn write(&self, _build: &str, file: &str, content: &[u8]) -> UnreactResult<()> {
+///     self.0.lock().unwrap().push((file.to_string(), content.to_vec()));
+///     Ok(())
+///   }
+/// }
+///
+/// fn main() -> UnreactResult<()> {
+///   let url = SiteUrl::parse("https://mysite.com")?;
+///   let mut app = Unreact::from_vfs(Config::default(), false, url, Vfs::new());
+///
+///   let files = Arc::new(Mutex::new(Vec::new()));
+///   app.set_sink(MemorySink(files.clone()));
+///   app.page_plain("index", "Home").finish()?;
+///
+///   assert!(files.lock().unwrap().contains(&("index.html".to_string(), b"Home".to_vec())));
+///
+///   Ok(())
+/// }
+/// ```
+pub trait BuildSink: fmt::Debug + Send + Sync {
+  /// Write output file
+  ///
+  /// `build`: Build directory in config, such as `"build"`, or build directory of one of `targets` in config
+  ///
+  /// `file`: Path relative to build directory, **with** extension, such as `"index.html"` or `"public/logo.png"`
+  fn write(&self, build: &str, file: &str, content: &[u8]) -> UnreactResult<()>;
+
+  /// Called after every file of a build directory is written, such as to finish an upload
+  ///
+  /// Default: Does nothing
+  fn finish(&self, build: &str) -> UnreactResult<()> {
+    let _ = build;
+    Ok(())
+  }
+}
@@ -0,0 +1,211 @@
+use std::fmt;
+use url::Url;
+
+use crate::{UnreactError, UnreactResult};
+
+/// Validated base URL of production site
+///
+/// Always uses `http` or `https`, and never has a trailing slash, so paths can be joined without double slashes
+///
+/// Create with `SiteUrl::parse`, or pass a `&str` to `Unreact::new`
+///
+/// # Examples
+///
+/// ```
+/// use unreact::prelude::*;
+///
+/// fn main() -> UnreactResult<()> {
+///   let url = SiteUrl::parse("https://user.github.io/repo/")?;
+///
+///   assert_eq!(url.as_str(), "https://user.github.io/repo");
+///   assert_eq!(url.base_path(), "/repo");
+///   assert_eq!(url.join("/posts/hello"), "https://user.github.io/repo/posts/hello");
+///
+///   assert!(SiteUrl::parse("mysite.com").is_err());
+///   assert!(SiteUrl::parse("ftp://mysite.com").is_err());
+///
+///   Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SiteUrl {
+  /// Normalized url, without trailing slash
+  url: String,
+}
+
+impl SiteUrl {
+  /// Parse and validate url
+  ///
+  /// Returns error if url is not absolute, has a scheme other than `http` or `https`, or has a query or fragment
+  pub fn parse(url: &str) -> UnreactResult<Self> {
+    let invalid = |reason: &str| UnreactError::InvalidUrl(url.to_string(), reason.to_string());
+
+    let parsed = match Url::parse(url.trim()) {
+      Ok(x) => x,
+      Err(err) => return Err(invalid(&err.to_string())),
+    };
+
+    if parsed.scheme() != "http" && parsed.scheme() != "https" {
+      return Err(invalid("Scheme must be `http` or `https`"));
+    }
+    if parsed.host_str().is_none() {
+      return Err(invalid("Url has no host"));
+    }
+    if parsed.query().is_some() || parsed.fragment().is_some() {
+      return Err(invalid("Url cannot have a query or fragment"));
+    }
+
+    Ok(SiteUrl {
+      url: parsed.as_str().trim_end_matches('/').to_string(),
+    })
+  }
+
+  /// Get url as string, without trailing slash
+  pub fn as_str(&self) -> &str {
+    &self.url
+  }
+
+  /// Get path of url, without trailing slash
+  ///
+  /// Empty if site is hosted at root of domain
+  pub fn base_path(&self) -> &str {
+    // Skip `scheme://`, then find start of path
+    let after_scheme = self.url.find("://").map_or(0, |i| i + 3);
+    match self.url[after_scheme..].find('/') {
+      Some(i) => &self.url[after_scheme + i..],
+      None => "",
+    }
+  }
+
+  /// Join path to url, with exactly one slash between
+  ///
+  /// `path`: Path relative to site root, with or without leading slash
+  pub fn join(&self, path: &str) -> String {
+    let path = path.trim_start_matches('/');
+    if path.is_empty() {
+      format!("{}/", self.url)
+    } else {
+      format!("{}/{path}", self.url)
+    }
+  }
+
+  /// Add repository name as base path, for a GitHub Pages project site, see `Config::github_base_path`
+  ///
+  /// `repository`: Owner and name of repository, such as `"user/repo"`, from `GITHUB_REPOSITORY` in GitHub Actions
+  ///
+  /// Url is unchanged if it is not a `github.io` domain (such as a custom domain), already has a base path, or is a user site (such as `user/user.github.io`)
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use unreact::prelude::*;
+  ///
+  /// fn main() -> UnreactResult<()> {
+  ///   let url = SiteUrl::parse("https://user.github.io")?;
+  ///   assert_eq!(url.with_repository("user/repo").as_str(), "https://user.github.io/repo");
+  ///   assert_eq!(url.with_repository("user/user.github.io").as_str(), "https://user.github.io");
+  ///
+  ///   let url = SiteUrl::parse("https://mysite.com")?;
+  ///   assert_eq!(url.with_repository("user/repo").as_str(), "https://mysite.com");
+  ///
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn with_repository(&self, repository: &str) -> SiteUrl {
+    let host = self.url.split_once("://").map_or("", |(_, host)| host);
+    let name = repository
+      .trim_matches('/')
+      .rsplit('/')
+      .next()
+      .unwrap_or("");
+    if !self.base_path().is_empty()
+      || !host.to_lowercase().ends_with(".github.io")
+      || name.is_empty()
+      || name.eq_ignore_ascii_case(host)
+    {
+      return self.clone();
+    }
+    SiteUrl {
+      url: format!("{}/{name}", self.url),
+    }
+  }
+
+  /// Replace path of url with base path, see `Config::base_path`
+  ///
+  /// `path`: Path to serve site under, with or without slashes, such as `"repo"` or `"/docs/v2/"`. Empty for root of domain
+  ///
+  /// Returns error if url with path is not valid
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use unreact::prelude::*;
+  ///
+  /// fn main() -> UnreactResult<()> {
+  ///   let url = SiteUrl::parse("https://mysite.com/old")?;
+  ///   assert_eq!(url.with_base_path("/docs/v2/")?.as_str(), "https://mysite.com/docs/v2");
+  ///   assert_eq!(url.with_base_path("")?.as_str(), "https://mysite.com");
+  ///
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn with_base_path(&self, path: &str) -> UnreactResult<SiteUrl> {
+    let origin = &self.url[..self.url.len() - self.base_path().len()];
+    SiteUrl::parse(&format!("{origin}/{}", path.trim_matches('/')))
+  }
+
+  /// Get absolute url of link, which is a path relative to site root, or an absolute url
+  pub(crate) fn resolve(&self, link: &str) -> String {
+    if link.starts_with("http://") || link.starts_with("https://") {
+      link.to_string()
+    } else {
+      self.join(link)
+    }
+  }
+
+  /// Replace every occurence of url in content with `other`, for `Config::targets`
+  ///
+  /// Longer urls which start with this url (such as `https://mysite.com.au` for `https://mysite.com`) are not replaced
+  pub(crate) fn replace_in(&self, content: &str, other: &SiteUrl) -> String {
+    let mut output = String::with_capacity(content.len());
+    let mut rest = content;
+    while let Some(i) = rest.find(&self.url) {
+      let end = i + self.url.len();
+      output.push_str(&rest[..i]);
+      // Only if url ends here
+      let is_end = !rest[end..]
+        .starts_with(|ch: char| ch.is_alphanumeric() || matches!(ch, '.' | '-' | '_' | ':'));
+      output.push_str(if is_end { &other.url } else { &self.url });
+      rest = &rest[end..];
+    }
+    output.push_str(rest);
+    output
+  }
+}
+
+impl fmt::Display for SiteUrl {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.url)
+  }
+}
+
+impl TryFrom<&str> for SiteUrl {
+  type Error = UnreactError;
+  fn try_from(url: &str) -> UnreactResult<Self> {
+    SiteUrl::parse(url)
+  }
+}
+
+impl TryFrom<String> for SiteUrl {
+  type Error = UnreactError;
+  fn try_from(url: String) -> UnreactResult<Self> {
+    SiteUrl::parse(&url)
+  }
+}
+
+impl TryFrom<&String> for SiteUrl {
+  type Error = UnreactError;
+  fn try_from(url: &String) -> UnreactResult<Self> {
+    SiteUrl::parse(url)
+  }
+}
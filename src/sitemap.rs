@@ -0,0 +1,123 @@
+use std::fmt;
+
+use crate::{escape_xml as escape, Page, SiteUrl};
+
+/// File name of sitemap, in root of build directory
+pub(crate) const SITEMAP_FILE: &str = "sitemap.xml";
+
+/// File name of robots file, in root of build directory
+pub(crate) const ROBOTS_FILE: &str = "robots.txt";
+
+/// Options of a page in `sitemap.xml`, see `Config::sitemap` and `Unreact::set_sitemap`
+///
+/// Pages can also be left out with `sitemap: false`, `noindex: true`, or `canonical` in page data, see `Page::is_listed`
+///
+/// Default: Included, without priority or change frequency
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SitemapOptions {
+  /// Priority of page relative to other pages of site, from `0.0` to `1.0`
+  ///
+  /// Default: `None` (search engines use `0.5`)
+  pub priority: Option<f32>,
+  /// How often page is likely to change
+  ///
+  /// Default: `None`
+  pub changefreq: Option<ChangeFreq>,
+  /// If page is left out of sitemap
+  ///
+  /// Default: `false`
+  pub exclude: bool,
+}
+
+/// How often a page is likely to change, for `changefreq` in `sitemap.xml`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeFreq {
+  Always,
+  Hourly,
+  Daily,
+  Weekly,
+  Monthly,
+  Yearly,
+  Never,
+}
+
+impl fmt::Display for ChangeFreq {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let name = match self {
+      ChangeFreq::Always => "always",
+      ChangeFreq::Hourly => "hourly",
+      ChangeFreq::Daily => "daily",
+      ChangeFreq::Weekly => "weekly",
+      ChangeFreq::Monthly => "monthly",
+      ChangeFreq::Yearly => "yearly",
+      ChangeFreq::Never => "never",
+    };
+    write!(f, "{name}")
+  }
+}
+
+/// Get url path of page, **without** `.html` extension
+///
+/// `about` is `about`, `blog/index` is `blog/`
+///
+/// Returns `None` for the 404 page, which should not be listed or submitted
+pub(crate) fn url_path(path: &str) -> Option<String> {
+  if path == "404" {
+    return None;
+  }
+  Some(match path.strip_suffix("index") {
+    Some(dir) if dir.is_empty() || dir.ends_with('/') => dir.to_string(),
+    _ => path.to_string(),
+  })
+}
+
+/// Create `sitemap.xml` of pages, in order of registration
+pub(crate) fn sitemap<'a>(pages: impl IntoIterator<Item = &'a Page>, url: &SiteUrl) -> String {
+  let mut xml = String::from(
+    "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+  );
+
+  for page in pages {
+    if !page.is_listed(url) {
+      continue;
+    }
+    let Some(path) = url_path(&page.path) else {
+      continue;
+    };
+
+    xml += &format!("  <url>\n    <loc>{}</loc>\n", escape(&url.join(&path)));
+    if let Some(changefreq) = page.sitemap.changefreq {
+      xml += &format!("    <changefreq>{changefreq}</changefreq>\n");
+    }
+    if let Some(priority) = page.sitemap.priority {
+      xml += &format!("    <priority>{}</priority>\n", priority.clamp(0.0, 1.0));
+    }
+    xml += "  </url>\n";
+  }
+
+  xml + "</urlset>\n"
+}
+
+/// Create sitemap index, which lists other sitemaps, as `sitemap.xml`
+///
+/// `files`: Paths of sitemaps, relative to build directory, such as `blog/sitemap.xml`
+pub(crate) fn sitemap_index(files: &[String], url: &SiteUrl) -> String {
+  let mut xml = String::from(
+    "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<sitemapindex xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+  );
+  for file in files {
+    xml += &format!(
+      "  <sitemap>\n    <loc>{}</loc>\n  </sitemap>\n",
+      escape(&url.join(file))
+    );
+  }
+  xml + "</sitemapindex>\n"
+}
+
+/// Create `robots.txt` which allows every page, and links to sitemap
+pub(crate) fn robots(url: &SiteUrl) -> String {
+  format!(
+    "User-agent: *\nAllow: /\n\nSitemap: {}\n",
+    url.join(SITEMAP_FILE)
+  )
+}
@@ -0,0 +1,157 @@
+use std::collections::BTreeMap;
+
+use crate::{watch::template_dependencies, FileMap, Page, PageSource};
+
+/// Directory of stats page in dev build directory, served at `/__unreact/`
+pub(crate) const STATS_DIR: &str = "__unreact";
+
+/// Data size of page above which a warning is shown, in bytes
+const LARGE_DATA: usize = 1024 * 1024;
+
+/// Create html of stats page, listing pages, templates, styles, and warnings of the last build
+///
+/// `styles`: Name of each style, and output path in build directory
+pub(crate) fn stats_page(
+  pages: &[Page],
+  templates: &FileMap,
+  styles: &[(String, String)],
+) -> String {
+  let mut warnings = Vec::new();
+
+  // Pages using each template, directly or as a partial
+  let mut uses: BTreeMap<&str, usize> = templates.keys().map(|name| (name.as_str(), 0)).collect();
+  // Set if any template has dependencies which cannot be known, so unused templates are not warned
+  let mut unknown_deps = false;
+
+  let mut page_rows = String::new();
+  for page in pages {
+    let (source, data_size) = match &page.source {
+      PageSource::Plain => ("Plain".to_string(), 0),
+      PageSource::Markdown(markdown) => {
+        // Shortcodes can use any template
+        unknown_deps = true;
+        ("Markdown".to_string(), markdown.len())
+      }
+      PageSource::Template(name, data) => {
+        match template_dependencies(templates, name) {
+          Some(deps) => {
+            for dep in deps {
+              if let Some(count) = uses.get_mut(dep.as_str()) {
+                *count += 1;
+              }
+            }
+          }
+          None => unknown_deps = true,
+        }
+        (
+          format!("Template <code>{}</code>", escape(name)),
+          data.to_string().len(),
+        )
+      }
+    };
+
+    if page.content.trim().is_empty() {
+      warnings.push(format!("Page <code>{}</code> is empty", escape(&page.path)));
+    }
+    if data_size > LARGE_DATA {
+      warnings.push(format!(
+        "Page <code>{}</code> has {} of data",
+        escape(&page.path),
+        size(data_size)
+      ));
+    }
+
+    page_rows += &format!(
+      r#"<tr><td><a href="/{path}">/{path}</a></td><td>{source}</td><td>{}</td><td>{}</td></tr>"#,
+      size(data_size),
+      size(page.content.len()),
+      path = escape(&page.path),
+    );
+  }
+
+  let mut template_rows = String::new();
+  for (name, count) in &uses {
+    if *count == 0 && !unknown_deps {
+      warnings.push(format!(
+        "Template <code>{}</code> is not used by any page",
+        escape(name)
+      ));
+    }
+    template_rows += &format!(
+      "<tr><td><code>{}</code></td><td>{}</td><td>{count}</td></tr>",
+      escape(name),
+      size(templates.get(*name).map_or(0, String::len)),
+    );
+  }
+
+  let mut style_rows = String::new();
+  for (name, path) in styles {
+    style_rows += &format!(
+      r#"<tr><td><code>{}</code></td><td><a href="/{path}.css">/{path}.css</a></td></tr>"#,
+      escape(name),
+      path = escape(path),
+    );
+  }
+
+  let warning_items = if warnings.is_empty() {
+    "<li>None</li>".to_string()
+  } else {
+    warnings
+      .iter()
+      .map(|warning| format!("<li>{warning}</li>"))
+      .collect()
+  };
+
+  format!(
+    r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8" />
+<meta name="viewport" content="width=device-width, initial-scale=1" />
+<title>Unreact build stats</title>
+<style>
+body {{ font-family: system-ui, sans-serif; max-width: 60rem; margin: 2rem auto; padding: 0 1rem; }}
+table {{ border-collapse: collapse; width: 100%; }}
+th, td {{ text-align: left; padding: 0.25rem 0.5rem; border-bottom: 1px solid #ccc; }}
+</style>
+</head>
+<body>
+<h1>Unreact build stats</h1>
+<p>{} pages, {} templates, {} styles. Only generated in dev mode</p>
+<p><a href="partials/">Partials reference</a></p>
+<h2>Warnings</h2>
+<ul>{warning_items}</ul>
+<h2>Pages</h2>
+<table><tr><th>Path</th><th>Source</th><th>Data</th><th>Output</th></tr>{page_rows}</table>
+<h2>Templates</h2>
+<table><tr><th>Name</th><th>Size</th><th>Pages</th></tr>{template_rows}</table>
+<h2>Styles</h2>
+<table><tr><th>Name</th><th>Output</th></tr>{style_rows}</table>
+</body>
+</html>
+"#,
+    pages.len(),
+    templates.len(),
+    styles.len(),
+  )
+}
+
+/// Format amount of bytes, such as `1.5 KB`
+fn size(bytes: usize) -> String {
+  if bytes < 1024 {
+    format!("{bytes} B")
+  } else if bytes < 1024 * 1024 {
+    format!("{:.1} KB", bytes as f64 / 1024.0)
+  } else {
+    format!("{:.1} MB", bytes as f64 / 1024.0 / 1024.0)
+  }
+}
+
+/// Escape text for html
+fn escape(text: &str) -> String {
+  text
+    .replace('&', "&amp;")
+    .replace('"', "&quot;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+}
@@ -1,6 +1,9 @@
 use handlebars::{RenderError, TemplateError};
+use serde_json::Value;
 use std::collections::HashMap;
 
+use crate::{sitemap::url_path, SiteUrl, SitemapOptions};
+
 /// Alias of result type, with [UnreactError]
 //TODO Rename enum
 pub type UnreactResult<T> = Result<T, UnreactError>;
@@ -24,13 +27,49 @@ pub enum UnreactError {
   ///  - Verifying template name matches path in template directory
   TemplateNotExist(String),
 
+  /// Cannot find registered page with path given
+  ///
+  /// Try:
+  ///  - Removing file extension `.html` from page path
+  ///  - Registering the page before replacing it
+  PageNotExist(String),
+
+  /// Cannot find collection with name given
+  ///
+  /// Try:
+  ///  - Registering the collection with `Unreact::collection` before paginating it
+  CollectionNotExist(String),
+
+  /// Output path of page or file cannot be used on every OS
+  ///
+  /// Try:
+  ///  - Renaming page with a reserved Windows name, such as `aux` to `aux-page`
+  ///  - Removing characters `< > : " \ | ? *` from path
+  ///  - Using a shorter path
+  InvalidPath(String, String),
+
+  /// Cannot find style with name given
+  ///
+  /// Try:
+  ///  - Removing file extension `.scss` from style name
+  ///  - Verifying style name matches path in styles directory
+  StyleNotExist(String),
+
+  /// Url of production site is not valid
+  ///
+  /// Try:
+  ///  - Starting url with `https://` or `http://`
+  ///  - Removing any query (`?`) or fragment (`#`) from url
+  InvalidUrl(String, String),
+
   /// Failed to convert `.scss` to `.css`
   ///
   /// Try:
   ///  - Checking for any bugs or unsupported features in the `.scss` file
+  ///  - Checking that `@use` and `@import` paths are relative to the style, or to the styles directory
   ///
   /// See: [grass](https://crates.io/crates/grass) crate
-  ScssConvertFail(String, String),
+  ScssConvertFail(String, Box<grass::Error>),
 
   /// Failed to minify `.css` file
   ///
@@ -46,7 +85,26 @@ pub enum UnreactError {
   ///  - Checking for any bugs or unsupported features in the `.hbs` file
   ///
   /// See: [handlebars](https://crates.io/crates/handlebars) crate
-  HandlebarsFail(String, RenderError),
+  HandlebarsFail(String, Box<RenderError>),
+
+  /// Render of template exceeded a limit, with `limits` set in config, see `RenderLimits`
+  ///
+  /// Try:
+  ///  - Checking for loops over very large data, or partials which include themselves
+  ///  - Raising the limit in config, if the page is only large
+  RenderLimit(String, String),
+
+  /// Failed to read translation file of locale, see `Unreact::set_locales`
+  ///
+  /// Try:
+  ///  - Checking that file is valid JSON, with an object of translations
+  TranslationFail(String, String),
+
+  /// Failed to render template with template engine, see `Unreact::set_engine`
+  ///
+  /// Try:
+  ///  - Checking for any bugs or unsupported features in the template, for the template engine
+  EngineFail(String, String),
 
   /// Failed to register partial
   ///
@@ -56,19 +114,266 @@ pub enum UnreactError {
   ///  - Checking for any bugs or unsupported features in the `.hbs` file
   ///
   /// See: [handlebars](https://crates.io/crates/handlebars) crate
-  RegisterPartialFail(String, TemplateError),
+  RegisterPartialFail(String, Box<TemplateError>),
 
   /// Failed to register inbuilt partial
   ///
   /// Try:
   ///  - Reporting this bug [here](https://github.com/darccyy/unreact/issues/new)
-  RegisterInbuiltPartialFail(String, TemplateError),
+  RegisterInbuiltPartialFail(String, Box<TemplateError>),
+
+  /// Failed to parse shortcode in Markdown content
+  ///
+  /// Try:
+  ///  - Closing shortcode with `>}}`
+  ///  - Closing any quotes in shortcode arguments
+  ///  - Starting shortcode with name of partial, such as `{{< youtube id >}}`
+  ShortcodeFail(String, String),
+
+  /// Failed to parse front matter of Markdown file
+  ///
+  /// Try:
+  ///  - Closing YAML front matter with `---`, or TOML front matter with `+++`
+  ///  - Using string, number, boolean, or list values, with nested keys indented by spaces in YAML
+  ///  - Quoting strings in TOML front matter
+  FrontMatterFail(String, String),
+
+  /// Failed to read config file
+  ///
+  /// Try:
+  ///  - Using a `.toml`, `.yaml`, or `.yml` file extension
+  ///  - Checking key names match fields of `Config`, with tables or dotted keys for nested options
+  ///  - Quoting strings in TOML files
+  ConfigFail(String, String),
+
+  /// Failed to read data file
+  ///
+  /// Try:
+  ///  - Checking that JSON files are valid
+  ///  - Using a JSON file for inline tables or multi-line strings, which are not supported in YAML and TOML files
+  ///  - Renaming files with the same name and different extensions
+  DataFail(String, String),
+
+  /// Failed to create feed
+  ///
+  /// Try:
+  ///  - Using `YYYY-MM-DD` or RFC 3339 (`YYYY-MM-DDTHH:MM:SS+HH:MM`) format for dates of items
+  FeedFail(String, String),
+
+  /// Failed to process image of public directory, see `Config::images`
+  ///
+  /// Try:
+  ///  - Checking that the image opens in other programs
+  ///  - Setting an image processor which supports the image, see `Unreact::set_image_processor`
+  ///  - Disabling `compress` and `widths` of `images` in config
+  ImageFail(String, String),
+
+  /// Failed to read or write build manifest as JSON
+  ///
+  /// Try:
+  ///  - Checking that the file is a manifest written by Unreact
+  ManifestFail(String, serde_json::Error),
+
+  /// Failed to package build directory into an archive
+  ///
+  /// Try:
+  ///  - Using a supported file extension: `.tar`, `.tar.gz`, `.tgz`, or `.zip`
+  PackageFail(String, String),
+
+  /// Two different sources are written to the same output file
+  ///
+  /// Try:
+  ///  - Disabling `flatten_styles` in config, if two styles have the same file name
+  ///  - Checking for duplicates in `style_paths` in config
+  ///  - Using a different build directory for each of `targets` in config
+  DuplicateOutput(String, String),
+
+  /// Build was stopped by progress callback, after some steps were finished
+  ///
+  /// Files already written are left in the build directory
+  ///
+  /// Try:
+  ///  - Returning `ControlFlow::Continue` from the callback of `Unreact::finish_with_progress`
+  BuildAborted(usize, usize),
+
+  /// IndexNow key is not valid
+  ///
+  /// Try:
+  ///  - Using only letters, numbers, and dashes
+  ///  - Using between 8 and 128 characters
+  InvalidIndexNowKey(String),
+
+  /// Files could not be written to build directory, with `skip_write_errors` set in config
+  ///
+  /// Try:
+  ///  - Checking that the build directory is not on a read-only mount
+  ///  - Closing programs which lock files in the build directory, such as antivirus or sync software
+  WriteFail(Vec<(String, std::io::Error)>),
+
+  /// Pages of `Unreact::pages_from_iter` failed to render, with index and path of each item
+  ///
+  /// Try:
+  ///  - Checking data of each failed item, as other items with the same template rendered
+  ///  - Checking the error of each item, such as `UnreactError::TemplateNotExist`
+  PagesFail(Vec<(usize, String, UnreactError)>),
+
+  /// Pages link to files which are not in build, with `check_links` set to `LinkCheck::Fail` in config
+  ///
+  /// Try:
+  ///  - Checking spelling of link, and that the linked page is registered
+  ///  - Linking with `{{>URL}}`, so root-relative links include base path of url
+  ///  - Setting `check_links` to `LinkCheck::Warn`, to build anyway
+  BrokenLinks(Vec<(String, String)>),
+
+  /// Failed to start dev server at address
+  ///
+  /// Try:
+  ///  - Closing other servers using the same port, such as another dev server
+  ///  - Using an address with port, such as `127.0.0.1:8080`, or port `0` for any free port
+  ServerFail(String, String),
+
+  /// Failed to read or write lockfile as JSON
+  ///
+  /// Try:
+  ///  - Checking that the file is a lockfile written by Unreact
+  ///  - Deleting the lockfile, so it is written again by the next build
+  LockfileFail(String, serde_json::Error),
+
+  /// Inputs of build are different to lockfile, with `locked` set in config
+  ///
+  /// Try:
+  ///  - Checking that changes to listed inputs are expected, such as a changed template
+  ///  - Building without `locked`, to write the lockfile with the current inputs
+  LockfileMismatch(String, Vec<String>),
+
+  /// Plugin failed to load, see `Plugin::load`
+  ///
+  /// Try:
+  ///  - Checking the error of the plugin, such as a missing option
+  ///  - Checking that the plugin supports this version of Unreact
+  PluginFail(String, String),
+
+  /// Written file is different to output, or cannot be parsed, with `verify_output` set in config
+  ///
+  /// Try:
+  ///  - Checking that the disk is not full or failing, and building again
+  ///  - Checking hooks and plugins which change files before they are written
+  ///  - Building without `minify`, to check if the minifier broke the file
+  VerifyFail(String, String),
 
   /// An IO or FS error occurred
   IoError(std::io::Error, String),
 }
 
-impl std::error::Error for UnreactError {}
+impl std::error::Error for UnreactError {
+  fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+    match self {
+      UnreactError::ScssConvertFail(_, err) => Some(err),
+      UnreactError::HandlebarsFail(_, err) => Some(err),
+      UnreactError::RegisterPartialFail(_, err) => Some(err),
+      UnreactError::RegisterInbuiltPartialFail(_, err) => Some(err),
+      UnreactError::ManifestFail(_, err) => Some(err),
+      UnreactError::LockfileFail(_, err) => Some(err),
+      UnreactError::IoError(err, _) => Some(err),
+      _ => None,
+    }
+  }
+}
+
+impl UnreactError {
+  /// Get template, style, or file, with line and column, where error occurred
+  ///
+  /// Returns `None` if error has no location, or location is not known
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use unreact::prelude::*;
+  /// use serde_json::json;
+  ///
+  /// fn main() -> UnreactResult<()> {
+  ///   let mut vfs = Vfs::new();
+  ///   vfs.templates.insert("index".to_string(), "<h1>\n{{#each}}</h1>".to_string());
+  ///
+  ///   let url = SiteUrl::parse("https://mysite.com")?;
+  ///   let app = Unreact::from_vfs(Config::default(), false, url, vfs);
+  ///
+  ///   let err = app.render("index", &json!({})).unwrap_err();
+  ///   let location = err.location().unwrap();
+  ///   assert_eq!(location.name, "index");
+  ///   assert_eq!(location.line, Some(2));
+  ///
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn location(&self) -> Option<ErrorLocation> {
+    match self {
+      UnreactError::HandlebarsFail(_, err) => Some(ErrorLocation {
+        name: err.template_name.clone()?,
+        line: err.line_no,
+        column: err.column_no,
+      }),
+      UnreactError::RegisterPartialFail(name, err)
+      | UnreactError::RegisterInbuiltPartialFail(name, err) => Some(ErrorLocation {
+        name: err
+          .template_name
+          .clone()
+          .unwrap_or_else(|| name.to_string()),
+        line: err.line_no,
+        column: err.column_no,
+      }),
+      UnreactError::ScssConvertFail(_, err) => match (**err).clone().kind() {
+        grass::ErrorKind::ParseError { loc, .. } => Some(ErrorLocation {
+          name: loc.file.name().to_string(),
+          // Zero-indexed
+          line: Some(loc.begin.line + 1),
+          column: Some(loc.begin.column + 1),
+        }),
+        _ => None,
+      },
+      UnreactError::ManifestFail(path, err) | UnreactError::LockfileFail(path, err) => {
+        Some(ErrorLocation {
+          name: path.to_string(),
+          line: Some(err.line()),
+          column: Some(err.column()),
+        })
+      }
+      _ => None,
+    }
+  }
+}
+
+/// Location of error in template, style, or file, see `UnreactError::location`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorLocation {
+  /// Name of template, or path of style or file
+  pub name: String,
+  /// Line number, starting at 1
+  pub line: Option<usize>,
+  /// Column number, starting at 1
+  pub column: Option<usize>,
+}
+
+impl std::fmt::Display for ErrorLocation {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", self.name)?;
+    if let Some(line) = self.line {
+      write!(f, ":{line}")?;
+      if let Some(column) = self.column {
+        write!(f, ":{column}")?;
+      }
+    }
+    Ok(())
+  }
+}
+
+// Allows `TryInto` conversions which cannot fail, such as `SiteUrl` to `SiteUrl`
+impl From<std::convert::Infallible> for UnreactError {
+  fn from(err: std::convert::Infallible) -> Self {
+    match err {}
+  }
+}
+
 impl std::fmt::Display for UnreactError {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     match self {
@@ -80,50 +385,256 @@ impl std::fmt::Display for UnreactError {
         f,
         "Template does not exist with name '{name}' (UnreactError::TemplateNotExist)"
       ),
+      UnreactError::PageNotExist(path) => write!(
+        f,
+        "Page is not registered with path '{path}' (UnreactError::PageNotExist)"
+      ),
+      UnreactError::CollectionNotExist(name) => write!(
+        f,
+        "Collection is not registered with name '{name}' (UnreactError::CollectionNotExist)"
+      ),
+      UnreactError::InvalidPath(path, reason) => write!(
+        f,
+        "Output path '{path}' is not valid (UnreactError::InvalidPath) - {reason}"
+      ),
+      UnreactError::StyleNotExist(name) => write!(
+        f,
+        "Style does not exist with name '{name}' (UnreactError::StyleNotExist)"
+      ),
+      UnreactError::InvalidUrl(url, reason) => write!(
+        f,
+        "Invalid site url '{url}' (UnreactError::InvalidUrl) - {reason}"
+      ),
       UnreactError::ScssConvertFail(name, err) => write!(
         f,
-        "Failed to convert SCSS to CSS for '{name}' (UnreactError::ScssConvertFail) - {err:?}"
+        "Failed to convert SCSS to CSS for '{name}' (UnreactError::ScssConvertFail) - {err}"
       ),
       UnreactError::MinifyCssFail(name, err) => write!(
         f,
-        "Failed to minify CSS file for '{name}' (UnreactError::MinifyCssFail) - {err:?}"
+        "Failed to minify CSS file for '{name}' (UnreactError::MinifyCssFail) - {err}"
       ),
       UnreactError::HandlebarsFail(name, err) => write!(
         f,
-        "Failed to render template with name '{name}' (UnreactError::RenderFail) - {err:?}"
+        "Failed to render template with name '{name}' (UnreactError::HandlebarsFail) - {err}"
+      ),
+      UnreactError::RenderLimit(name, reason) => write!(
+        f,
+        "Render of template with name '{name}' exceeded a limit (UnreactError::RenderLimit) - {reason}"
+      ),
+      UnreactError::TranslationFail(path, reason) => write!(
+        f,
+        "Failed to read translations at '{path}' (UnreactError::TranslationFail) - {reason}"
+      ),
+      UnreactError::EngineFail(name, err) => write!(
+        f,
+        "Failed to render template with name '{name}' (UnreactError::EngineFail) - {err}"
       ),
       UnreactError::RegisterPartialFail(name, err) => write!(
         f,
-        "Failed to register custom partial with name '{name}' (UnreactError::RegisterPartialFail) - {err:?}"
+        "Failed to register custom partial with name '{name}' (UnreactError::RegisterPartialFail) - {err}"
       ),
       UnreactError::RegisterInbuiltPartialFail(name, err) => write!(
         f,
-        "Failed to register *inbuilt* partial '{name}' (UnreactError::RegisterInbuiltPartialFail) - {err:?}"
+        "Failed to register *inbuilt* partial '{name}' (UnreactError::RegisterInbuiltPartialFail) - {err}"
+      ),
+      UnreactError::ShortcodeFail(shortcode, reason) => write!(
+        f,
+        "Failed to parse shortcode '{shortcode}' (UnreactError::ShortcodeFail) - {reason}"
+      ),
+      UnreactError::FrontMatterFail(path, reason) => write!(
+        f,
+        "Failed to parse front matter of '{path}' (UnreactError::FrontMatterFail) - {reason}"
+      ),
+      UnreactError::ConfigFail(path, reason) => write!(
+        f,
+        "Failed to read config file '{path}' (UnreactError::ConfigFail) - {reason}"
+      ),
+      UnreactError::DataFail(path, reason) => write!(
+        f,
+        "Failed to read data file '{path}' (UnreactError::DataFail) - {reason}"
+      ),
+      UnreactError::FeedFail(path, reason) => write!(
+        f,
+        "Failed to create feed '{path}' (UnreactError::FeedFail) - {reason}"
+      ),
+      UnreactError::ImageFail(path, reason) => write!(
+        f,
+        "Failed to process image '{path}' (UnreactError::ImageFail) - {reason}"
+      ),
+      UnreactError::ManifestFail(path, err) => write!(
+        f,
+        "Failed to read or write manifest at '{path}' (UnreactError::ManifestFail) - {err}"
+      ),
+      UnreactError::PackageFail(path, reason) => write!(
+        f,
+        "Failed to package build to '{path}' (UnreactError::PackageFail) - {reason}"
+      ),
+      UnreactError::DuplicateOutput(path, sources) => write!(
+        f,
+        "Output file '{path}' is written by both {sources} (UnreactError::DuplicateOutput)"
+      ),
+      UnreactError::BuildAborted(done, total) => write!(
+        f,
+        "Build was aborted after {done} of {total} steps (UnreactError::BuildAborted)"
+      ),
+      UnreactError::InvalidIndexNowKey(key) => write!(
+        f,
+        "IndexNow key '{key}' is not valid (UnreactError::InvalidIndexNowKey) - Must be 8 to 128 letters, numbers, or dashes"
+      ),
+      UnreactError::WriteFail(failures) => {
+        write!(
+          f,
+          "Failed to write {} files (UnreactError::WriteFail)",
+          failures.len()
+        )?;
+        for (path, err) in failures {
+          write!(f, "\n  - '{path}': {err}")?;
+        }
+        Ok(())
+      }
+      UnreactError::PagesFail(failures) => {
+        write!(
+          f,
+          "Failed to render {} pages (UnreactError::PagesFail)",
+          failures.len()
+        )?;
+        for (i, path, err) in failures {
+          write!(f, "\n  - Item {i}, '{path}': {err}")?;
+        }
+        Ok(())
+      }
+      UnreactError::BrokenLinks(links) => {
+        write!(
+          f,
+          "Found {} broken links (UnreactError::BrokenLinks)",
+          links.len()
+        )?;
+        for (page, link) in links {
+          write!(f, "\n  - '{link}' in '{page}'")?;
+        }
+        Ok(())
+      }
+      UnreactError::ServerFail(addr, reason) => write!(
+        f,
+        "Failed to start dev server at '{addr}' (UnreactError::ServerFail) - {reason}"
+      ),
+      UnreactError::LockfileFail(path, err) => write!(
+        f,
+        "Failed to read or write lockfile at '{path}' (UnreactError::LockfileFail) - {err}"
+      ),
+      UnreactError::LockfileMismatch(path, inputs) => {
+        write!(
+          f,
+          "Found {} inputs different to lockfile at '{path}' (UnreactError::LockfileMismatch)",
+          inputs.len()
+        )?;
+        for input in inputs {
+          write!(f, "\n  - '{input}'")?;
+        }
+        Ok(())
+      }
+      UnreactError::PluginFail(name, reason) => write!(
+        f,
+        "Failed to load plugin '{name}' (UnreactError::PluginFail) - {reason}"
+      ),
+      UnreactError::VerifyFail(path, reason) => write!(
+        f,
+        "Failed to verify written file '{path}' (UnreactError::VerifyFail) - {reason}"
       ),
       UnreactError::IoError(err, path) => write!(
         f,
-        "File Error: {err:?}, at path '{path}' (UnreactError::IoError)"
+        "File Error: {err}, at path '{path}' (UnreactError::IoError)"
       ),
     }
   }
 }
 
-/// Alias of hashmap
+/// Alias of hashmap, of file paths to file contents
 pub type FileMap = HashMap<String, String>;
 
-/// File object
-#[derive(Debug)]
-pub struct File {
+/// Page registered with `Unreact`
+#[derive(Debug, Clone)]
+pub struct Page {
+  /// Output path in build directory, **without** `.html` extension
   pub path: String,
+  /// Rendered html
   pub content: String,
+  /// Source of page, to render again if templates change in watch mode
+  pub source: PageSource,
+  /// Options of page in `sitemap.xml`, if enabled in config
+  pub sitemap: SitemapOptions,
+  /// If `content` is rendered, or page is rendered from `source` when building, see `Unreact::page`
+  pub(crate) rendered: bool,
 }
 
-impl File {
-  /// Create new `File` struct
-  pub fn new(path: &str, content: &str) -> Self {
-    File {
+/// Source of a registered page
+#[derive(Debug, Clone)]
+pub enum PageSource {
+  /// Raw content, without template
+  Plain,
+  /// Template name, and data to render with
+  Template(String, Value),
+  /// Markdown content
+  Markdown(String),
+}
+
+impl Page {
+  /// Create new `Page` struct
+  pub fn new(path: &str, content: &str, source: PageSource) -> Self {
+    Page {
       path: path.to_string(),
       content: content.to_string(),
+      source,
+      sitemap: SitemapOptions::default(),
+      rendered: true,
+    }
+  }
+
+  /// Create page which is rendered from source when building
+  pub(crate) fn unrendered(path: &str, source: PageSource) -> Self {
+    Page {
+      rendered: false,
+      ..Page::new(path, "", source)
+    }
+  }
+
+  /// Get value of key in data of page, if rendered with a template
+  pub fn data(&self, key: &str) -> Option<&Value> {
+    match &self.source {
+      PageSource::Template(_, data) => data.get(key),
+      _ => None,
+    }
+  }
+
+  /// Canonical path or url of page, from `canonical` in page data or front matter
+  pub fn canonical(&self) -> Option<&str> {
+    self
+      .data("canonical")
+      .and_then(Value::as_str)
+      .filter(|canonical| !canonical.is_empty())
+  }
+
+  /// If page is hidden from search engines, from `noindex` in page data or front matter
+  pub fn noindex(&self) -> bool {
+    self.data("noindex").and_then(Value::as_bool) == Some(true)
+  }
+
+  /// If page is listed in `sitemap.xml`
+  ///
+  /// Pages are not listed if excluded with `Unreact::set_sitemap` or `sitemap: false` in page data, if `noindex` is set,
+  /// or if `canonical` is the url of another page
+  pub fn is_listed(&self, url: &SiteUrl) -> bool {
+    if self.sitemap.exclude || self.noindex() {
+      return false;
+    }
+    if self.data("sitemap").and_then(Value::as_bool) == Some(false) {
+      return false;
+    }
+    match (self.canonical(), url_path(&self.path)) {
+      (Some(canonical), Some(path)) => {
+        url.resolve(canonical).trim_end_matches('/') == url.join(&path).trim_end_matches('/')
+      }
+      _ => true,
     }
   }
 }
@@ -0,0 +1,244 @@
+use crate::hash_content;
+
+/// Check that written file is identical to output, and can still be parsed, see `Config::verify_output`
+///
+/// `file`: Path relative to build directory, **with** extension
+///
+/// `written`: Content read from written file
+///
+/// `hash`: Hash of output which was written
+///
+/// HTML, CSS, JSON, and XML files are checked by extension. Other files are only compared
+pub(crate) fn verify_file(file: &str, written: &[u8], hash: &str) -> Result<(), String> {
+  if hash_content(written) != hash {
+    return Err("Written file is different to output".to_string());
+  }
+
+  let extension = file.rsplit_once('.').map(|(_, extension)| extension);
+  let check = match extension.map(str::to_ascii_lowercase).as_deref() {
+    Some("html" | "htm") => check_html,
+    Some("css") => check_css,
+    Some("xml" | "rss" | "atom" | "svg") => check_xml,
+    Some("json" | "webmanifest") => {
+      return serde_json::from_slice::<serde::de::IgnoredAny>(written)
+        .map(|_| ())
+        .map_err(|err| format!("Invalid JSON - {err}"));
+    }
+    _ => return Ok(()),
+  };
+  let Ok(text) = std::str::from_utf8(written) else {
+    return Err("File is not valid UTF-8".to_string());
+  };
+  check(text)
+}
+
+/// Get line number of byte index in text, for errors
+fn line(text: &str, index: usize) -> usize {
+  text[..index].matches('\n').count() + 1
+}
+
+/// Find text case-insensitively, from an index
+fn find_ignore_case(text: &str, needle: &str, from: usize) -> Option<usize> {
+  (text.as_bytes()[from..].windows(needle.len()))
+    .position(|window| window.eq_ignore_ascii_case(needle.as_bytes()))
+    .map(|i| from + i)
+}
+
+/// Get index of `>` which ends tag starting at `start`, outside of quoted attribute values
+fn tag_end(text: &str, start: usize) -> Option<usize> {
+  let mut quote = None;
+  let mut previous = b' ';
+  for (i, ch) in text.bytes().enumerate().skip(start + 1) {
+    match quote {
+      Some(q) if ch == q => quote = None,
+      Some(_) => (),
+      // Quotes only start a value after `=`, such as `title="a > b"`
+      None if (ch == b'"' || ch == b'\'') && previous == b'=' => quote = Some(ch),
+      None if ch == b'>' => return Some(i),
+      None => (),
+    }
+    if !ch.is_ascii_whitespace() {
+      previous = ch;
+    }
+  }
+  None
+}
+
+/// Get lowercase name of tag, such as `script` for `<script src=a>` or `</Script>`
+fn tag_name(tag: &str) -> String {
+  (tag.trim_start_matches(['<', '/']).chars())
+    .take_while(|ch| ch.is_ascii_alphanumeric() || *ch == '-' || *ch == ':')
+    .collect::<String>()
+    .to_ascii_lowercase()
+}
+
+/// Check that html can be tokenized: every tag, comment, and quoted attribute value is closed, and `script` and `style` elements end
+///
+/// Closing tags can be left out, as minified html omits optional tags
+fn check_html(html: &str) -> Result<(), String> {
+  let mut i = 0;
+  while let Some(start) = html[i..].find('<').map(|offset| i + offset) {
+    let rest = &html[start..];
+    if rest.starts_with("<!--") {
+      match rest.find("-->") {
+        Some(end) => i = start + end + 3,
+        None => {
+          return Err(format!(
+            "Comment on line {} is not closed",
+            line(html, start)
+          ))
+        }
+      }
+      continue;
+    }
+    // Other `<` is text, such as `a < b`
+    if !rest[1..].starts_with(|ch: char| ch.is_ascii_alphabetic() || matches!(ch, '/' | '!' | '?'))
+    {
+      i = start + 1;
+      continue;
+    }
+
+    let Some(end) = tag_end(html, start) else {
+      return Err(format!("Tag on line {} is not closed", line(html, start)));
+    };
+    i = end + 1;
+
+    // Content of raw text elements is not html, and ends at closing tag
+    let name = tag_name(&html[start..end]);
+    if !rest.starts_with("</") && matches!(name.as_str(), "script" | "style" | "textarea" | "title")
+    {
+      match find_ignore_case(html, &format!("</{name}"), i) {
+        Some(close) => i = close,
+        None => {
+          return Err(format!(
+            "Element `{name}` on line {} is not closed",
+            line(html, start)
+          ))
+        }
+      }
+    }
+  }
+  Ok(())
+}
+
+/// Check that css can be tokenized: every string and comment is closed, and brackets are balanced
+fn check_css(css: &str) -> Result<(), String> {
+  let bytes = css.as_bytes();
+  let mut brackets = Vec::new();
+  let mut i = 0;
+  while i < bytes.len() {
+    match bytes[i] {
+      b'/' if bytes.get(i + 1) == Some(&b'*') => match css[i + 2..].find("*/") {
+        Some(end) => i += end + 3,
+        None => return Err(format!("Comment on line {} is not closed", line(css, i))),
+      },
+      quote @ (b'"' | b'\'') => {
+        let start = i;
+        i += 1;
+        loop {
+          match bytes.get(i) {
+            Some(b'\\') => i += 1,
+            Some(ch) if *ch == quote => break,
+            Some(b'\n') | None => {
+              return Err(format!("String on line {} is not closed", line(css, start)))
+            }
+            Some(_) => (),
+          }
+          i += 1;
+        }
+      }
+      b'\\' => i += 1,
+      open @ (b'{' | b'(' | b'[') => brackets.push((open, i)),
+      close @ (b'}' | b')' | b']') => {
+        let expected = match close {
+          b'}' => b'{',
+          b')' => b'(',
+          _ => b'[',
+        };
+        match brackets.pop() {
+          Some((open, _)) if open == expected => (),
+          _ => {
+            return Err(format!(
+              "Unexpected `{}` on line {}",
+              close as char,
+              line(css, i)
+            ))
+          }
+        }
+      }
+      _ => (),
+    }
+    i += 1;
+  }
+
+  match brackets.pop() {
+    Some((open, start)) => Err(format!(
+      "`{}` on line {} is not closed",
+      open as char,
+      line(css, start)
+    )),
+    None => Ok(()),
+  }
+}
+
+/// Check that xml is well-formed: every element is closed in order, and comments and CDATA sections are closed
+fn check_xml(xml: &str) -> Result<(), String> {
+  let mut elements = Vec::new();
+  let mut i = 0;
+  while let Some(start) = xml[i..].find('<').map(|offset| i + offset) {
+    let rest = &xml[start..];
+    // Sections which can contain `<` and `>`
+    let section = [("<!--", "-->"), ("<![CDATA[", "]]>"), ("<?", "?>")]
+      .into_iter()
+      .find(|(open, _)| rest.starts_with(open));
+    if let Some((open, close)) = section {
+      match rest[open.len()..].find(close) {
+        Some(end) => i = start + open.len() + end + close.len(),
+        None => {
+          return Err(format!(
+            "Section on line {} is not closed",
+            line(xml, start)
+          ))
+        }
+      }
+      continue;
+    }
+
+    let Some(end) = tag_end(xml, start) else {
+      return Err(format!("Tag on line {} is not closed", line(xml, start)));
+    };
+    i = end + 1;
+    let tag = &xml[start..=end];
+    // Doctype
+    if tag.starts_with("<!") {
+      continue;
+    }
+    let name = &tag[1..tag.len() - 1];
+    if let Some(name) = name.strip_prefix('/') {
+      let name = name.trim();
+      match elements.pop() {
+        Some((open, _)) if open == name => (),
+        _ => {
+          return Err(format!(
+            "Unexpected closing tag `{name}` on line {}",
+            line(xml, start)
+          ))
+        }
+      }
+    } else if !name.ends_with('/') {
+      let name = name
+        .split(|ch: char| ch.is_whitespace())
+        .next()
+        .unwrap_or(name);
+      elements.push((name, start));
+    }
+  }
+
+  match elements.pop() {
+    Some((name, start)) => Err(format!(
+      "Element `{name}` on line {} is not closed",
+      line(xml, start)
+    )),
+    None => Ok(()),
+  }
+}
@@ -0,0 +1,73 @@
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+
+use crate::{
+  data::load_data, ignore::IgnoreRules, load_filemap, scss::load_styles, Config, FileMap,
+  UnreactResult,
+};
+
+/// Virtual file system of source files, for building without reading directories
+///
+/// Keys are paths relative to the source directory, **without** file extension
+///
+/// Use with `Unreact::from_vfs`
+///
+/// # Examples
+///
+/// ```
+/// use unreact::prelude::*;
+///
+/// fn main() -> UnreactResult<()> {
+///   let mut vfs = Vfs::new();
+///   vfs.style_partials.insert("base/_colors".to_string(), "$main: red;".to_string());
+///   vfs.styles.insert("pages/about".to_string(), "@use 'base/colors' as colors;\na { color: colors.$main; }".to_string());
+///   vfs.styles.insert("reset".to_string(), "p  {  margin: 0  }".to_string());
+///   vfs.plain_styles.insert("reset".to_string());
+///
+///   let url = SiteUrl::parse("https://mysite.com")?;
+///   let app = Unreact::from_vfs(Config::default(), false, url, vfs);
+///
+///   assert_eq!(app.render_style("pages/about")?, "a{color:red}");
+///   assert_eq!(app.render_style("reset")?, "p{margin:0}");
+///
+///   Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Vfs {
+  /// Templates and partials (`.hbs`), such as `"index"` or `"other/template"`
+  pub templates: FileMap,
+  /// Styles (`.scss` or `.css`), such as `"main"` or `"pages/about"`
+  pub styles: FileMap,
+  /// Names of styles which are plain css (`.css`), and are not compiled
+  pub plain_styles: BTreeSet<String>,
+  /// Partials of styles (`.scss` starting with `_`), such as `"_mixins"` or `"base/_colors"`
+  ///
+  /// Only used by `@use` and `@import` in other styles, and not written to build directory
+  pub style_partials: FileMap,
+  /// Parsed data files, as nested objects, such as `"nav"` or `"authors"`, see `Config::data`
+  pub data: Map<String, Value>,
+}
+
+impl Vfs {
+  /// Create empty virtual file system
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Read all templates, styles, and data files from directories in `config`
+  pub fn read(config: &Config) -> UnreactResult<Self> {
+    let mut vfs = Vfs::read_without_templates(config)?;
+    let ignore = IgnoreRules::read(config, &config.templates);
+    load_filemap(&mut vfs.templates, &config.templates, "", &ignore)?;
+    Ok(vfs)
+  }
+
+  /// Read styles and data files from directories in `config`, for `Config::lazy_templates`
+  pub(crate) fn read_without_templates(config: &Config) -> UnreactResult<Self> {
+    let mut vfs = Vfs::new();
+    load_styles(&mut vfs, &config.styles)?;
+    vfs.data = load_data(&config.data)?;
+    Ok(vfs)
+  }
+}
@@ -0,0 +1,130 @@
+use std::collections::HashSet;
+
+use crate::FileMap;
+
+#[cfg(feature = "dev-server")]
+use crate::server::DevEvent;
+
+/// Get names of partials used in template, such as `{{>name}}` and `{{#>name}}`
+///
+/// Returns `None` if template uses a dynamic partial (`{{> (lookup ...)}}`), as it could use any partial
+pub(crate) fn partials_used(template: &str) -> Option<Vec<String>> {
+  let mut names = Vec::new();
+
+  for (i, _) in template.match_indices("{{") {
+    let rest = template[i + 2..].trim_start_matches('~');
+    let rest = rest.strip_prefix('#').unwrap_or(rest);
+    let Some(rest) = rest.strip_prefix('>') else {
+      continue;
+    };
+    let rest = rest.trim_start();
+
+    // Dynamic partial
+    if rest.starts_with('(') {
+      return None;
+    }
+
+    // Name ends at whitespace or closing braces
+    let name = rest
+      .split(|ch: char| ch.is_whitespace() || ch == '}' || ch == '~')
+      .next()
+      .unwrap_or_default()
+      .trim_matches(|ch| ch == '"' || ch == '\'');
+    if !name.is_empty() {
+      names.push(name.to_string());
+    }
+  }
+
+  Some(names)
+}
+
+/// Get names of styles declared in comments of template, such as `{{!-- styles: code, tables --}}` or `{{! styles: code }}`
+pub(crate) fn styles_declared(template: &str) -> Vec<String> {
+  let mut names = Vec::new();
+
+  for (i, _) in template.match_indices("{{!") {
+    let rest = &template[i + 3..];
+    let (comment, end) = match rest.strip_prefix("--") {
+      Some(rest) => (rest, "--}}"),
+      None => (rest, "}}"),
+    };
+    let Some(comment) = comment.split(end).next() else {
+      continue;
+    };
+    if let Some(list) = comment.trim().strip_prefix("styles:") {
+      names.extend(
+        list
+          .split(|ch: char| ch == ',' || ch.is_whitespace())
+          .filter(|name| !name.is_empty())
+          .map(String::from),
+      );
+    }
+  }
+
+  names
+}
+
+/// Get every template which `name` depends on, including itself, and partials used by partials
+///
+/// Returns `None` if any dependency cannot be known, see `partials_used`
+pub(crate) fn template_dependencies(templates: &FileMap, name: &str) -> Option<HashSet<String>> {
+  let mut deps = HashSet::new();
+  let mut queue = vec![name.to_string()];
+
+  while let Some(name) = queue.pop() {
+    if !deps.insert(name.to_string()) {
+      continue;
+    }
+    // Inbuilt partials are not in map
+    if let Some(template) = templates.get(&name) {
+      queue.extend(partials_used(template)?);
+    }
+  }
+
+  Some(deps)
+}
+
+/// Watch directories for changes, sending changed paths to `events`
+///
+/// Events within a short time are collected into one list
+///
+/// Directories are watched until watcher is dropped
+#[cfg(feature = "dev-server")]
+pub(crate) fn watch_dirs(
+  dirs: &[&str],
+  events: std::sync::mpsc::Sender<DevEvent>,
+) -> notify::Result<notify::RecommendedWatcher> {
+  use notify::{RecursiveMode, Watcher};
+  use std::{sync::mpsc, thread, time::Duration};
+
+  let (event_tx, event_rx) = mpsc::channel::<notify::Result<notify::Event>>();
+  let mut watcher = notify::recommended_watcher(event_tx)?;
+  for dir in dirs {
+    watcher.watch(std::path::Path::new(dir), RecursiveMode::Recursive)?;
+  }
+
+  // Collect events, until none are received for a short time
+  thread::spawn(move || {
+    while let Ok(event) = event_rx.recv() {
+      let mut paths = Vec::new();
+      let mut add = |event: notify::Result<notify::Event>| {
+        if let Ok(event) = event {
+          if !event.kind.is_access() {
+            paths.extend(event.paths);
+          }
+        }
+      };
+
+      add(event);
+      while let Ok(event) = event_rx.recv_timeout(Duration::from_millis(100)) {
+        add(event);
+      }
+
+      if !paths.is_empty() && events.send(DevEvent::Changed(paths)).is_err() {
+        break;
+      }
+    }
+  });
+
+  Ok(watcher)
+}
@@ -0,0 +1,121 @@
+use std::{
+  env,
+  net::{Ipv4Addr, TcpListener},
+  path::{Component, Path, PathBuf},
+};
+
+use crate::{Config, ImageOptions};
+
+/// Amount of ports tried after `dev_port` in config, if it is in use
+const PORT_ATTEMPTS: u16 = 100;
+
+/// Directory of manifest of crate which is run, from `CARGO_MANIFEST_DIR`, which is set by `cargo run` and `cargo test`
+fn manifest_dir() -> Option<PathBuf> {
+  env::var_os("CARGO_MANIFEST_DIR").map(PathBuf::from)
+}
+
+/// Make directories of config relative to directory of crate manifest, if directory of templates is not in current directory, see `Config::manifest_dir`
+///
+/// Current directory is not changed. `dev_build` is kept, so dev builds of every crate are in `target` of workspace
+///
+/// Returns config unchanged if templates are not in manifest directory either, so the error is the same as before
+pub(crate) fn use_manifest_dir(config: Config) -> Config {
+  if !config.manifest_dir || Path::new(&config.templates).is_dir() {
+    return config;
+  }
+  let Some(dir) = manifest_dir().filter(|dir| dir.join(&config.templates).is_dir()) else {
+    return config;
+  };
+  let Some(prefix) = env::current_dir()
+    .ok()
+    .and_then(|current| relative_path(&current, &dir))
+  else {
+    return config;
+  };
+
+  let join = |path: &str| {
+    if Path::new(path).is_absolute() {
+      path.to_string()
+    } else {
+      format!("{prefix}/{path}").trim_end_matches('/').to_string()
+    }
+  };
+  let join_option = |path: &Option<String>| path.as_deref().map(join);
+  Config {
+    build: join(&config.build),
+    templates: join(&config.templates),
+    public: join(&config.public),
+    styles: join(&config.styles),
+    content: join(&config.content),
+    data: join(&config.data),
+    locales: join(&config.locales),
+    publish_schedule: join_option(&config.publish_schedule),
+    partial_docs: join_option(&config.partial_docs),
+    lockfile: join_option(&config.lockfile),
+    cache: join_option(&config.cache),
+    targets: (config.targets.iter())
+      .map(|(url, build)| (url.to_string(), join(build)))
+      .collect(),
+    images: ImageOptions {
+      dir: join(&config.images.dir),
+      ..config.images.clone()
+    },
+    ..config
+  }
+}
+
+/// Get relative path from one absolute directory to another, joined with `/`, such as `sites/blog` or `../blog`
+///
+/// Returns `None` if directories are the same, or on different roots (such as drives on Windows)
+fn relative_path(from: &Path, to: &Path) -> Option<String> {
+  let from = from.components().collect::<Vec<_>>();
+  let to = to.components().collect::<Vec<_>>();
+  if from.first() != to.first() {
+    return None;
+  }
+  let common = (from.iter().zip(&to)).take_while(|(a, b)| a == b).count();
+
+  let parts = (from[common..].iter())
+    .filter(|part| matches!(part, Component::Normal(_)))
+    .map(|_| "..".to_string())
+    .chain(to[common..].iter().filter_map(|part| match part {
+      Component::Normal(part) => Some(part.to_string_lossy().to_string()),
+      _ => None,
+    }))
+    .collect::<Vec<_>>();
+  if parts.is_empty() {
+    return None;
+  }
+  Some(parts.join("/"))
+}
+
+/// Get path of file relative to current directory, or to directory of crate manifest if it only exists there
+///
+/// Absolute paths are not changed
+pub(crate) fn manifest_path(path: &str) -> String {
+  if Path::new(path).is_absolute() || Path::new(path).exists() {
+    return path.to_string();
+  }
+  match manifest_dir().map(|dir| dir.join(path)) {
+    Some(file) if file.exists() => file.to_string_lossy().to_string(),
+    _ => path.to_string(),
+  }
+}
+
+/// Get name of crate which is run, from `CARGO_PKG_NAME`, to tell apart dev builds of crates in a workspace
+pub(crate) fn package_name() -> Option<String> {
+  env::var("CARGO_PKG_NAME")
+    .ok()
+    .filter(|name| !name.is_empty())
+}
+
+/// Get local address of dev server, with `port`, or the next port which is free, so dev servers of several sites can run at once
+///
+/// Port `0` chooses any free port. Port is only checked, not kept, so another program could still bind it before the server starts
+pub(crate) fn dev_address(port: u16) -> String {
+  let free = (port..port.saturating_add(PORT_ATTEMPTS))
+    .find_map(|port| TcpListener::bind((Ipv4Addr::LOCALHOST, port)).ok())
+    .and_then(|listener| listener.local_addr().ok())
+    .map_or(port, |addr| addr.port());
+  format!("{}:{free}", Ipv4Addr::LOCALHOST)
+}